@@ -7,15 +7,21 @@ mod report;
 mod report_column;
 mod report_data;
 mod obj_type;
+mod table_csv;
+mod recent_files;
+#[cfg(test)]
+mod test_util;
+use std::collections::HashMap;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use serde::{Serialize, Deserialize};
 use tauri::menu::{ContextMenu, Menu, MenuItem, MenuBuilder};
 use tauri::{AppHandle, WebviewWindowBuilder, WebviewUrl, Emitter, Size, PhysicalSize, Manager};
-use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_dialog::DialogExt;
 use tauri::ipc::{Channel, InvokeError};
 use crate::util::error;
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(rename_all="camelCase", rename_all_fields="camelCase")]
 pub enum Action {
     CreateTable {
@@ -28,6 +34,23 @@ pub enum Action {
     RestoreDeletedTable {
         table_oid: i64
     },
+    RenameTable {
+        table_oid: i64,
+        table_name: String
+    },
+    DuplicateTable {
+        table_oid: i64,
+        table_name: String,
+        include_data: bool
+    },
+    SetTablePrimaryKey {
+        table_oid: i64,
+        column_oids: Vec<i64>
+    },
+    RestoreTablePrimaryKey {
+        table_oid: i64,
+        column_oids: Vec<i64>
+    },
     CreateReport {
         report_name: String,
         base_table_oid: i64
@@ -48,6 +71,14 @@ pub enum Action {
     RestoreDeletedObjectType {
         obj_type_oid: i64
     },
+    AddObjectTypeInheritance {
+        inheritor_oid: i64,
+        master_oid: i64
+    },
+    RemoveObjectTypeInheritance {
+        inheritor_oid: i64,
+        master_oid: i64
+    },
     CreateTableColumn {
         table_oid: i64, 
         column_name: String, 
@@ -56,7 +87,8 @@ pub enum Action {
         column_style: String, 
         is_nullable: bool, 
         is_unique: bool, 
-        is_primary_key: bool
+        is_primary_key: bool,
+        default_value: Option<String>
     },
     EditTableColumnMetadata {
         table_oid: i64, 
@@ -66,7 +98,9 @@ pub enum Action {
         column_style: String, 
         is_nullable: bool, 
         is_unique: bool, 
-        is_primary_key: bool
+        is_primary_key: bool,
+        is_pinned: bool,
+        default_value: Option<String>
     },
     RestoreEditedTableColumnMetadata {
         table_oid: i64,
@@ -78,6 +112,39 @@ pub enum Action {
         column_oid: i64,
         dropdown_values: Vec<table_column::DropdownValue>
     },
+    ImportTableColumnDropdownValues {
+        table_oid: i64,
+        column_oid: i64,
+        values: Vec<String>
+    },
+    DeleteTableColumnDropdownValues {
+        table_oid: i64,
+        column_oid: i64,
+        value_oids: Vec<i64>
+    },
+    RestoreDeletedTableColumnDropdownValues {
+        table_oid: i64,
+        column_oid: i64,
+        value_oids: Vec<i64>
+    },
+    MergeTableColumnDropdownValues {
+        table_oid: i64,
+        column_oid: i64,
+        from_value_oid: i64,
+        into_value_oid: i64
+    },
+    SplitMergedTableColumnDropdownValues {
+        table_oid: i64,
+        column_oid: i64,
+        from_value_oid: i64,
+        into_value_oid: i64,
+        merge: table_column::DropdownMerge
+    },
+    ReorderTableColumn {
+        table_oid: i64,
+        column_oid: i64,
+        new_ordering: i64
+    },
     DeleteTableColumn {
         table_oid: i64,
         column_oid: i64
@@ -101,19 +168,216 @@ pub enum Action {
         table_oid: i64,
         row_oid: i64
     },
+    RestorePermanentlyDeletedTableRow {
+        table_oid: i64,
+        row_oid: i64,
+        snapshot: table_data::RowSnapshot
+    },
+    DeduplicateTableRows {
+        table_oid: i64,
+        key_column_oids: Vec<i64>,
+        keep: table_data::KeepStrategy
+    },
+    DeleteTableRows {
+        table_oid: i64,
+        row_oids: Vec<i64>
+    },
+    ClearTable {
+        table_oid: i64
+    },
+    RestoreDeletedTableRows {
+        table_oid: i64,
+        row_oids: Vec<i64>
+    },
     UpdateTableCellStoredAsPrimitiveValue {
         table_oid: i64,
         column_oid: i64,
         row_oid: i64,
         value: Option<String>
+    },
+    SetTableCellStringList {
+        table_oid: i64,
+        column_oid: i64,
+        row_oid: i64,
+        values: Vec<String>
+    },
+    SetTableCellComment {
+        table_oid: i64,
+        row_oid: i64,
+        column_oid: i64,
+        comment: Option<String>
+    },
+    AddTableRowTag {
+        table_oid: i64,
+        row_oid: i64,
+        tag: String
+    },
+    RemoveTableRowTag {
+        table_oid: i64,
+        row_oid: i64,
+        tag: String
+    },
+    FindReplaceTableColumn {
+        table_oid: i64,
+        column_oid: i64,
+        find: String,
+        replace: String,
+        case_sensitive: bool
+    },
+    NormalizeTableColumn {
+        table_oid: i64,
+        column_oid: i64,
+        ops: Vec<table_data::NormalizeOp>
+    },
+    FillDownTableColumn {
+        table_oid: i64,
+        column_oid: i64
+    },
+    RestoreTableColumnValues {
+        table_oid: i64,
+        column_oid: i64,
+        prior_values: Vec<table_data::PriorCellValue>
+    },
+    SplitTableColumn {
+        table_oid: i64,
+        source_column_oid: i64,
+        delimiter: String,
+        into_column_oids: Vec<i64>,
+        create_columns: bool
+    },
+    MergeTableColumns {
+        table_oid: i64,
+        source_column_oids: Vec<i64>,
+        separator: String,
+        into_column_oid: i64,
+        skip_nulls: bool
+    },
+    RestoreSplitTableColumn {
+        table_oid: i64,
+        source_column_oid: i64,
+        delimiter: String,
+        into_column_oids: Vec<i64>,
+        create_columns: bool,
+        created_column_oids: Vec<i64>,
+        prior_values: Vec<table_data::ColumnPriorValues>
+    },
+    PromoteTableChildTable {
+        table_oid: i64,
+        column_oid: i64
+    },
+    DemoteTableToChildTable {
+        parent_table_oid: i64,
+        table_oid: i64,
+        column_oid: i64,
+        link: table::ChildTableLink
+    },
+    PromoteTableColumnDropdown {
+        table_oid: i64,
+        column_oid: i64
+    },
+    DemoteTableColumnDropdown {
+        table_oid: i64,
+        column_oid: i64,
+        shared_table_oid: i64
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// What an action would affect if it were executed.
+pub struct ActionPreview {
+    /// The number of rows of user data whose values the action would remove from view.
+    affected_row_count: i64,
+    /// The number of rows in the database that the action would insert, update, or delete.
+    changed_row_count: u64,
+    /// The error that the action would fail with, if any.
+    error: Option<String>
+}
+
+#[derive(PartialEq)]
+/// The reason that an action is being executed.
+enum ActionDirection {
+    Execute,
+    Undo,
+    Redo
+}
+
+impl ActionDirection {
+    /// Gets the name of the direction, as recorded in the audit log.
+    fn name(&self) -> &'static str {
+        return match self {
+            Self::Execute => "execute",
+            Self::Undo => "undo",
+            Self::Redo => "redo"
+        };
     }
 }
 
 static REVERSE_STACK: Mutex<Vec<Action>> = Mutex::new(Vec::new());
 static FORWARD_STACK: Mutex<Vec<Action>> = Mutex::new(Vec::new());
+static IS_PROFILING_ENABLED: AtomicBool = AtomicBool::new(false);
 
 impl Action {
-    fn execute(&self, app: &AppHandle, is_forward: bool) -> Result<(), error::Error> {
+    /// Runs the action inside a transaction that is rolled back afterwards, to report what the action would affect without changing anything.
+    /// Only actions that change many rows at once can be previewed: deleting a table or a column, splitting or merging columns, deduplicating rows, and clearing a table.
+    pub fn preview(&self) -> Result<ActionPreview, error::Error> {
+        let action = db::begin_db_action()?;
+        let changes_before = action.total_changes();
+        let result = match self {
+            Self::DeleteTable { table_oid } => {
+                table::count_rows_in(&action, *table_oid)
+                    .and_then(|affected_row_count| table::move_trash_in(&action, *table_oid).map(|_| affected_row_count))
+            },
+            Self::DeleteTableColumn { table_oid, column_oid } => {
+                table_column::count_values_in(&action, *table_oid, *column_oid)
+                    .and_then(|affected_row_count| table_column::move_trash_in(&action, *table_oid, *column_oid).map(|_| affected_row_count))
+            },
+            Self::SplitTableColumn { table_oid, source_column_oid, delimiter, into_column_oids, create_columns } => {
+                table_data::split_column_in(&action, *table_oid, *source_column_oid, delimiter, into_column_oids, *create_columns)
+                    .map(|changes| changes.affected_row_count)
+            },
+            Self::MergeTableColumns { table_oid, source_column_oids, separator, into_column_oid, skip_nulls } => {
+                table_data::merge_columns_in(&action, *table_oid, source_column_oids, separator, *into_column_oid, *skip_nulls)
+                    .map(|prior_values| prior_values.len() as i64)
+            },
+            Self::DeduplicateTableRows { table_oid, key_column_oids, keep } => {
+                table_data::deduplicate_in(&action, *table_oid, key_column_oids, *keep)
+                    .map(|trashed_row_oids| trashed_row_oids.len() as i64)
+            },
+            Self::ClearTable { table_oid } => {
+                table_data::clear_in(&action, *table_oid)
+                    .map(|trashed_row_oids| trashed_row_oids.len() as i64)
+            },
+            _ => {
+                return action.rollback(Err(error::Error::AdhocError("This action cannot be previewed.")));
+            }
+        };
+        let changed_row_count = action.total_changes() - changes_before;
+        return action.rollback(Ok(match result {
+            Ok(affected_row_count) => ActionPreview {
+                affected_row_count,
+                changed_row_count,
+                error: None
+            },
+            Err(e) => ActionPreview {
+                affected_row_count: 0,
+                changed_row_count,
+                error: Some(e.into())
+            }
+        }));
+    }
+
+    /// Executes the action, recording it in the audit log as part of the first change it commits.
+    fn execute(&self, app: &AppHandle, direction: ActionDirection) -> Result<(), error::Error> {
+        let (action_type, details) = self.get_audit_details();
+        db::set_pending_audit_entry(direction.name(), action_type, details);
+        let result = self.apply(app, &direction);
+        db::clear_pending_audit_entry();
+        return result;
+    }
+
+    fn apply(&self, app: &AppHandle, direction: &ActionDirection) -> Result<(), error::Error> {
+        let is_forward = *direction != ActionDirection::Undo;
         match self {
             Self::CreateTable { table_name, master_table_oid_list } => {
                 match table::create(table_name.clone(), master_table_oid_list) {
@@ -169,6 +433,81 @@ impl Action {
                     }
                 }
             },
+            Self::DuplicateTable { table_oid, table_name, include_data } => {
+                match table::duplicate(table_oid.clone(), table_name.clone(), include_data.clone()) {
+                    Ok(new_table_oid) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DeleteTable { 
+                            table_oid: new_table_oid
+                        });
+                        msg_update_table_list(app);
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RenameTable { table_oid, table_name } => {
+                match table::rename(table_oid.clone(), table_name.clone()) {
+                    Ok(prior_table_name) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RenameTable { 
+                            table_oid: table_oid.clone(),
+                            table_name: prior_table_name
+                        });
+                        msg_update_table_list(app);
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::SetTablePrimaryKey { table_oid, column_oids } => {
+                match table::set_primary_key(table_oid.clone(), column_oids.clone()) {
+                    Ok(prior_column_oids) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreTablePrimaryKey { 
+                            table_oid: table_oid.clone(),
+                            column_oids: prior_column_oids
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RestoreTablePrimaryKey { table_oid, column_oids } => {
+                match table::restore_primary_key(table_oid.clone(), column_oids.clone()) {
+                    Ok(replaced_column_oids) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::SetTablePrimaryKey { 
+                            table_oid: table_oid.clone(),
+                            column_oids: replaced_column_oids
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
             Self::CreateReport { report_name, base_table_oid } => {
                 match report::create(&report_name, base_table_oid.clone()) {
                     Ok(report_oid) => {
@@ -277,6 +616,44 @@ impl Action {
                     }
                 }
             },
+            Self::AddObjectTypeInheritance { inheritor_oid, master_oid } => {
+                match obj_type::add_inheritance(inheritor_oid.clone(), master_oid.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RemoveObjectTypeInheritance { 
+                            inheritor_oid: inheritor_oid.clone(),
+                            master_oid: master_oid.clone()
+                        });
+                        msg_update_obj_type_list(app);
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RemoveObjectTypeInheritance { inheritor_oid, master_oid } => {
+                match obj_type::remove_inheritance(inheritor_oid.clone(), master_oid.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::AddObjectTypeInheritance { 
+                            inheritor_oid: inheritor_oid.clone(),
+                            master_oid: master_oid.clone()
+                        });
+                        msg_update_obj_type_list(app);
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
             Self::CreateTableColumn { 
                 table_oid, 
                 column_name, 
@@ -285,7 +662,8 @@ impl Action {
                 column_style, 
                 is_nullable, 
                 is_unique, 
-                is_primary_key } => {
+                is_primary_key,
+                default_value } => {
                 
                 match table_column::create(
                     table_oid.clone(), 
@@ -295,7 +673,8 @@ impl Action {
                     column_style, 
                     is_nullable.clone(), 
                     is_unique.clone(), 
-                    is_primary_key.clone()) {
+                    is_primary_key.clone(),
+                    default_value.clone()) {
 
                     Ok(column_oid) => {
                         let mut reverse_stack = if is_forward {
@@ -322,7 +701,9 @@ impl Action {
                 column_style, 
                 is_nullable, 
                 is_unique, 
-                is_primary_key } => {
+                is_primary_key,
+                is_pinned,
+                default_value } => {
 
                 match table_column::edit(
                     table_oid.clone(),
@@ -332,7 +713,9 @@ impl Action {
                     column_style, 
                     is_nullable.clone(), 
                     is_unique.clone(), 
-                    is_primary_key.clone()) {
+                    is_primary_key.clone(),
+                    is_pinned.clone(),
+                    default_value.clone()) {
 
                     Ok(trash_column_oid_optional) => {
                         match trash_column_oid_optional {
@@ -378,17 +761,18 @@ impl Action {
                     }
                 }
             },
-            Self::DeleteTableColumn { table_oid, column_oid } => {
-                match table_column::move_trash(table_oid.clone(), column_oid.clone()) {
-                    Ok(_) => {
+            Self::ImportTableColumnDropdownValues { table_oid, column_oid, values } => {
+                match table_column::import_dropdown_values(column_oid.clone(), values.clone()) {
+                    Ok(summary) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::RestoreDeletedTableColumn {
+                        (*reverse_stack).push(Self::DeleteTableColumnDropdownValues {
                             table_oid: table_oid.clone(),
-                            column_oid: column_oid.clone()
+                            column_oid: column_oid.clone(),
+                            value_oids: summary.added_value_oids
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
@@ -397,17 +781,18 @@ impl Action {
                     }
                 }
             },
-            Self::RestoreDeletedTableColumn { table_oid, column_oid } => {
-                match table_column::unmove_trash(table_oid.clone(), column_oid.clone()) {
+            Self::DeleteTableColumnDropdownValues { table_oid, column_oid, value_oids } => {
+                match table_column::set_dropdown_values_trash(column_oid.clone(), value_oids, true) {
                     Ok(_) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::DeleteTableColumn { 
+                        (*reverse_stack).push(Self::RestoreDeletedTableColumnDropdownValues {
                             table_oid: table_oid.clone(),
-                            column_oid: column_oid.clone() 
+                            column_oid: column_oid.clone(),
+                            value_oids: value_oids.clone()
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
@@ -416,17 +801,18 @@ impl Action {
                     }
                 }
             },
-            Self::PushTableRow { table_oid } => {
-                match table_data::push(table_oid.clone()) {
-                    Ok(row_oid) => {
+            Self::RestoreDeletedTableColumnDropdownValues { table_oid, column_oid, value_oids } => {
+                match table_column::set_dropdown_values_trash(column_oid.clone(), value_oids, false) {
+                    Ok(_) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::DeleteTableRow { 
+                        (*reverse_stack).push(Self::DeleteTableColumnDropdownValues {
                             table_oid: table_oid.clone(),
-                            row_oid: row_oid.clone() 
+                            column_oid: column_oid.clone(),
+                            value_oids: value_oids.clone()
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
@@ -435,17 +821,20 @@ impl Action {
                     }
                 }
             },
-            Self::InsertTableRow { table_oid, row_oid } => {
-                match table_data::insert(table_oid.clone(), row_oid.clone()) {
-                    Ok(row_oid) => {
+            Self::MergeTableColumnDropdownValues { table_oid, column_oid, from_value_oid, into_value_oid } => {
+                match table_column::merge_dropdown_values_tracked(column_oid.clone(), from_value_oid.clone(), into_value_oid.clone()) {
+                    Ok(merge) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::DeleteTableRow { 
+                        (*reverse_stack).push(Self::SplitMergedTableColumnDropdownValues {
                             table_oid: table_oid.clone(),
-                            row_oid: row_oid.clone() 
+                            column_oid: column_oid.clone(),
+                            from_value_oid: from_value_oid.clone(),
+                            into_value_oid: into_value_oid.clone(),
+                            merge: merge
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
@@ -454,17 +843,19 @@ impl Action {
                     }
                 }
             },
-            Self::DeleteTableRow { table_oid, row_oid } => {
-                match table_data::move_trash(table_oid.clone(), row_oid.clone()) {
+            Self::SplitMergedTableColumnDropdownValues { table_oid, column_oid, from_value_oid, into_value_oid, merge } => {
+                match table_column::split_dropdown_values(column_oid.clone(), from_value_oid.clone(), into_value_oid.clone(), merge) {
                     Ok(_) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::RestoreDeletedTableRow { 
+                        (*reverse_stack).push(Self::MergeTableColumnDropdownValues {
                             table_oid: table_oid.clone(),
-                            row_oid: row_oid.clone() 
+                            column_oid: column_oid.clone(),
+                            from_value_oid: from_value_oid.clone(),
+                            into_value_oid: into_value_oid.clone()
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
@@ -473,17 +864,17 @@ impl Action {
                     }
                 }
             },
-            Self::RestoreDeletedTableRow { table_oid, row_oid } => {
-                match table_data::unmove_trash(table_oid.clone(), row_oid.clone()) {
+            Self::DeleteTableColumn { table_oid, column_oid } => {
+                match table_column::move_trash(table_oid.clone(), column_oid.clone()) {
                     Ok(_) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::DeleteTableRow { 
+                        (*reverse_stack).push(Self::RestoreDeletedTableColumn {
                             table_oid: table_oid.clone(),
-                            row_oid: row_oid.clone() 
+                            column_oid: column_oid.clone()
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
@@ -492,42 +883,665 @@ impl Action {
                     }
                 }
             },
-            Self::UpdateTableCellStoredAsPrimitiveValue { table_oid, column_oid, row_oid, value } => {
-                match table_data::try_update_primitive_value(table_oid.clone(), row_oid.clone(), column_oid.clone(), value.clone()) {
-                    Ok(old_value) => {
+            Self::RestoreDeletedTableColumn { table_oid, column_oid } => {
+                match table_column::unmove_trash(table_oid.clone(), column_oid.clone()) {
+                    Ok(_) => {
                         let mut reverse_stack = if is_forward {
                             REVERSE_STACK.lock().unwrap() 
                         } else { 
                             FORWARD_STACK.lock().unwrap() 
                         };
-                        (*reverse_stack).push(Self::UpdateTableCellStoredAsPrimitiveValue { 
+                        (*reverse_stack).push(Self::DeleteTableColumn { 
                             table_oid: table_oid.clone(),
-                            column_oid: column_oid.clone(),
-                            row_oid: row_oid.clone(),
-                            value: old_value
+                            column_oid: column_oid.clone() 
                         });
                         msg_update_table_data(app, table_oid.clone());
                     },
                     Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::PushTableRow { table_oid } => {
+                match table_data::push(table_oid.clone()) {
+                    Ok(row_oid) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DeleteTableRow { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone() 
+                        });
                         msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
                         return Err(e);
                     }
                 }
-            }
+            },
+            Self::InsertTableRow { table_oid, row_oid } => {
+                match table_data::insert(table_oid.clone(), row_oid.clone()) {
+                    Ok(row_oid) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DeleteTableRow { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone() 
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::DeleteTableRow { table_oid, row_oid } => {
+                match table::get_delete_mode(table_oid.clone())? {
+                    table::DeleteMode::Soft => {
+                        match table_data::move_trash(table_oid.clone(), row_oid.clone()) {
+                            Ok(_) => {
+                                let mut reverse_stack = if is_forward {
+                                    REVERSE_STACK.lock().unwrap() 
+                                } else { 
+                                    FORWARD_STACK.lock().unwrap() 
+                                };
+                                (*reverse_stack).push(Self::RestoreDeletedTableRow { 
+                                    table_oid: table_oid.clone(),
+                                    row_oid: row_oid.clone() 
+                                });
+                                msg_update_table_data(app, table_oid.clone());
+                            },
+                            Err(e) => {
+                                return Err(e);
+                            }
+                        }
+                    },
+                    table::DeleteMode::Hard => {
+                        match table_data::delete(table_oid.clone(), row_oid.clone()) {
+                            Ok(snapshot) => {
+                                let mut reverse_stack = if is_forward {
+                                    REVERSE_STACK.lock().unwrap() 
+                                } else { 
+                                    FORWARD_STACK.lock().unwrap() 
+                                };
+                                (*reverse_stack).push(Self::RestorePermanentlyDeletedTableRow { 
+                                    table_oid: table_oid.clone(),
+                                    row_oid: row_oid.clone(),
+                                    snapshot
+                                });
+                                msg_update_table_data(app, table_oid.clone());
+                            },
+                            Err(e) => {
+                                return Err(e);
+                            }
+                        }
+                    }
+                }
+            },
+            Self::RestorePermanentlyDeletedTableRow { table_oid, row_oid, snapshot } => {
+                match table_data::restore_deleted(snapshot.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DeleteTableRow { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone() 
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RestoreDeletedTableRow { table_oid, row_oid } => {
+                match table_data::unmove_trash(table_oid.clone(), row_oid.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DeleteTableRow { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone() 
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::DeduplicateTableRows { table_oid, key_column_oids, keep } => {
+                match table_data::deduplicate(table_oid.clone(), key_column_oids.clone(), keep.clone()) {
+                    Ok(report) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreDeletedTableRows { 
+                            table_oid: table_oid.clone(),
+                            row_oids: report.trashed_row_oids
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::DeleteTableRows { table_oid, row_oids } => {
+                match table_data::move_trash_rows(table_oid.clone(), row_oids.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreDeletedTableRows { 
+                            table_oid: table_oid.clone(),
+                            row_oids: row_oids.clone()
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::ClearTable { table_oid } => {
+                match table_data::clear(table_oid.clone()) {
+                    Ok(trashed_row_oids) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreDeletedTableRows { 
+                            table_oid: table_oid.clone(),
+                            row_oids: trashed_row_oids
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RestoreDeletedTableRows { table_oid, row_oids } => {
+                match table_data::unmove_trash_rows(table_oid.clone(), row_oids.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DeleteTableRows { 
+                            table_oid: table_oid.clone(),
+                            row_oids: row_oids.clone()
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::UpdateTableCellStoredAsPrimitiveValue { table_oid, column_oid, row_oid, value } => {
+                match table_data::try_update_primitive_value(table_oid.clone(), row_oid.clone(), column_oid.clone(), value.clone()) {
+                    Ok(old_value) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::UpdateTableCellStoredAsPrimitiveValue { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            row_oid: row_oid.clone(),
+                            value: old_value
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        msg_update_table_data(app, table_oid.clone());
+                        return Err(e);
+                    }
+                }
+            },
+            Self::SetTableCellStringList { table_oid, column_oid, row_oid, values } => {
+                match table_data::set_string_list(table_oid.clone(), row_oid.clone(), column_oid.clone(), values.clone()) {
+                    Ok(old_value) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::UpdateTableCellStoredAsPrimitiveValue { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            row_oid: row_oid.clone(),
+                            value: old_value
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        msg_update_table_data(app, table_oid.clone());
+                        return Err(e);
+                    }
+                }
+            },
+            Self::SetTableCellComment { table_oid, row_oid, column_oid, comment } => {
+                match table_data::set_cell_comment(table_oid.clone(), row_oid.clone(), column_oid.clone(), comment.clone()) {
+                    Ok(prior_comment) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::SetTableCellComment { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            comment: prior_comment
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::AddTableRowTag { table_oid, row_oid, tag } => {
+                match table_data::add_row_tag(table_oid.clone(), row_oid.clone(), tag) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RemoveTableRowTag { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone(),
+                            tag: tag.clone()
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RemoveTableRowTag { table_oid, row_oid, tag } => {
+                match table_data::remove_row_tag(table_oid.clone(), row_oid.clone(), tag) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::AddTableRowTag { 
+                            table_oid: table_oid.clone(),
+                            row_oid: row_oid.clone(),
+                            tag: tag.clone()
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::FindReplaceTableColumn { table_oid, column_oid, find, replace, case_sensitive } => {
+                match table_data::find_replace(table_oid.clone(), column_oid.clone(), find, replace, case_sensitive.clone()) {
+                    Ok(result) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreTableColumnValues { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            prior_values: result.prior_values
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::NormalizeTableColumn { table_oid, column_oid, ops } => {
+                match table_data::normalize_column_tracked(table_oid.clone(), column_oid.clone(), ops) {
+                    Ok(prior_values) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreTableColumnValues { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            prior_values
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::FillDownTableColumn { table_oid, column_oid } => {
+                match table_data::fill_down_tracked(table_oid.clone(), column_oid.clone()) {
+                    Ok(prior_values) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreTableColumnValues { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            prior_values
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RestoreTableColumnValues { table_oid, column_oid, prior_values } => {
+                match table_data::restore_column_values(table_oid.clone(), column_oid.clone(), prior_values) {
+                    Ok(replaced_values) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreTableColumnValues { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            prior_values: replaced_values
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::SplitTableColumn { table_oid, source_column_oid, delimiter, into_column_oids, create_columns } => {
+                match table_data::split_column_tracked(table_oid.clone(), source_column_oid.clone(), delimiter, into_column_oids, create_columns.clone()) {
+                    Ok(changes) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreSplitTableColumn { 
+                            table_oid: table_oid.clone(),
+                            source_column_oid: source_column_oid.clone(),
+                            delimiter: delimiter.clone(),
+                            into_column_oids: into_column_oids.clone(),
+                            create_columns: create_columns.clone(),
+                            created_column_oids: changes.created_column_oids,
+                            prior_values: changes.prior_values
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::MergeTableColumns { table_oid, source_column_oids, separator, into_column_oid, skip_nulls } => {
+                match table_data::merge_columns_tracked(table_oid.clone(), source_column_oids, separator, into_column_oid.clone(), skip_nulls.clone()) {
+                    Ok(prior_values) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::RestoreTableColumnValues { 
+                            table_oid: table_oid.clone(),
+                            column_oid: into_column_oid.clone(),
+                            prior_values
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::RestoreSplitTableColumn { table_oid, source_column_oid, delimiter, into_column_oids, create_columns, created_column_oids, prior_values } => {
+                match table_data::restore_split_column(table_oid.clone(), created_column_oids, prior_values) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::SplitTableColumn { 
+                            table_oid: table_oid.clone(),
+                            source_column_oid: source_column_oid.clone(),
+                            delimiter: delimiter.clone(),
+                            into_column_oids: into_column_oids.clone(),
+                            create_columns: create_columns.clone()
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::PromoteTableChildTable { table_oid, column_oid } => {
+                let link = table::get_child_table_link(table_oid.clone(), column_oid.clone())?;
+                match table::promote_child_table(table_oid.clone(), column_oid.clone()) {
+                    Ok(child_table_oid) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DemoteTableToChildTable { 
+                            parent_table_oid: table_oid.clone(),
+                            table_oid: child_table_oid,
+                            column_oid: column_oid.clone(),
+                            link
+                        });
+                        msg_update_table_list(app);
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::DemoteTableToChildTable { parent_table_oid, table_oid, column_oid, link } => {
+                match table::demote_to_child_table(parent_table_oid.clone(), table_oid.clone(), column_oid.clone(), link) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::PromoteTableChildTable { 
+                            table_oid: parent_table_oid.clone(),
+                            column_oid: column_oid.clone()
+                        });
+                        msg_update_table_list(app);
+                        msg_update_table_data(app, parent_table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::PromoteTableColumnDropdown { table_oid, column_oid } => {
+                match table_column::promote_dropdown_to_shared(column_oid.clone()) {
+                    Ok(shared_table_oid) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::DemoteTableColumnDropdown { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            shared_table_oid
+                        });
+                        msg_update_table_list(app);
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::DemoteTableColumnDropdown { table_oid, column_oid, shared_table_oid } => {
+                match table_column::demote_shared_to_dropdown(column_oid.clone(), shared_table_oid.clone()) {
+                    Ok(_) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::PromoteTableColumnDropdown { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone()
+                        });
+                        msg_update_table_list(app);
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
+            Self::ReorderTableColumn { table_oid, column_oid, new_ordering } => {
+                match table_column::reorder(table_oid.clone(), column_oid.clone(), new_ordering.clone()) {
+                    Ok(prior_ordering) => {
+                        let mut reverse_stack = if is_forward {
+                            REVERSE_STACK.lock().unwrap() 
+                        } else { 
+                            FORWARD_STACK.lock().unwrap() 
+                        };
+                        (*reverse_stack).push(Self::ReorderTableColumn { 
+                            table_oid: table_oid.clone(),
+                            column_oid: column_oid.clone(),
+                            new_ordering: prior_ordering
+                        });
+                        msg_update_table_data(app, table_oid.clone());
+                    },
+                    Err(e) => {
+                        return Err(e);
+                    }
+                }
+            },
             _ => {
                 return Err(error::Error::AdhocError("Action has not been implemented."));
             }
         }
         return Ok(());
     }
+
+    /// Gets the type of the action and the OIDs it affects as JSON, as recorded in the audit log.
+    /// Values and snapshots carried by the action are left out, so that the log stays small and does not keep deleted data.
+    fn get_audit_details(&self) -> (String, String) {
+        let serialized = match serde_json::to_value(self) {
+            Ok(v) => v,
+            Err(e) => {
+                log::warn!("Could not serialize action for the audit log: {e}");
+                serde_json::Value::Null
+            }
+        };
+        return match serialized {
+            serde_json::Value::Object(variant) => match variant.into_iter().next() {
+                Some((action_type, serde_json::Value::Object(fields))) => {
+                    let oids: serde_json::Map<String, serde_json::Value> = fields.into_iter()
+                        .filter(|(name, _)| name.ends_with("Oid") || name.ends_with("Oids") || name.ends_with("OidList"))
+                        .collect();
+                    (action_type, serde_json::Value::Object(oids).to_string())
+                },
+                Some((action_type, _)) => (action_type, String::from("{}")),
+                None => (String::from("unknown"), String::from("{}"))
+            },
+            serde_json::Value::String(action_type) => (action_type, String::from("{}")),
+            _ => (String::from("unknown"), String::from("{}"))
+        };
+    }
 }
 
 
 
 #[tauri::command]
 /// Initialize a connection to a StaticDB database file.
-pub fn init(path: String) -> Result<(), error::Error> {
-    return db::init(path);
+pub fn init(app: AppHandle, path: String) -> Result<(), error::Error> {
+    db::init(path.clone())?;
+
+    // Remember the file as recently opened
+    match app.path().app_config_dir() {
+        Ok(config_dir) => {
+            if let Err(e) = recent_files::add(&config_dir, path) {
+                let msg: String = e.into();
+                log::warn!("Could not record the database in the recent files: {msg}");
+            }
+        },
+        Err(e) => {
+            log::warn!("Could not find the app config directory: {e}");
+        }
+    }
+    return Ok(());
+}
+
+#[tauri::command]
+/// Get the title, description, and author of the open database file.
+pub fn get_document_metadata() -> Result<db::DocumentMetadata, error::Error> {
+    return db::get_document_metadata();
+}
+
+#[tauri::command]
+/// Set the title, description, and author of the open database file.
+pub fn set_document_metadata(title: Option<String>, description: Option<String>, author: Option<String>) -> Result<(), error::Error> {
+    return db::set_document_metadata(title, description, author);
+}
+
+#[tauri::command]
+/// Record a StaticDB database file as the most recently opened.
+pub fn add_recent_file(app: AppHandle, path: String) -> Result<(), error::Error> {
+    return recent_files::add(&app.path().app_config_dir()?, path);
+}
+
+#[tauri::command]
+/// Get the StaticDB database files that were recently opened, most recent first.
+pub fn get_recent_files(app: AppHandle) -> Result<Vec<String>, error::Error> {
+    return recent_files::get(&app.path().app_config_dir()?);
+}
+
+#[tauri::command]
+/// Forget every StaticDB database file that was recently opened.
+pub fn clear_recent_files(app: AppHandle) -> Result<(), error::Error> {
+    return recent_files::clear(&app.path().app_config_dir()?);
+}
+
+#[tauri::command]
+/// Re-point the application at a StaticDB database file that was moved while open.
+pub fn relocate_database(new_path: String) -> Result<(), error::Error> {
+    return db::relocate(new_path);
 }
 
 /// Sends a message to the frontend that the list of tables needs to be updated.
@@ -647,6 +1661,30 @@ pub fn get_table_list(table_channel: Channel<table::BasicMetadata>) -> Result<()
     return Ok(());
 }
 
+#[tauri::command]
+/// Get the display value of a single row, as shown wherever the row is referenced.
+pub fn get_row_display_value(table_oid: i64, row_oid: i64) -> Result<Option<String>, error::Error> {
+    return table::get_surrogate_display(table_oid, row_oid);
+}
+
+#[tauri::command]
+/// Get the display values of several rows at once, keyed by row OID.
+pub fn get_row_display_values(table_oid: i64, row_oids: Vec<i64>) -> Result<HashMap<i64, String>, error::Error> {
+    return table::get_surrogate_displays(table_oid, row_oids);
+}
+
+#[tauri::command]
+/// Set how many row display values are kept in memory, or turn off keeping them with a size of 0.
+pub fn set_row_display_value_cache_size(size: usize) {
+    table::set_surrogate_cache_size(size);
+}
+
+#[tauri::command]
+/// Get the tables and object types that have no primary key column.
+pub fn get_tables_without_primary_key() -> Result<Vec<table::BasicMetadata>, error::Error> {
+    return table::tables_without_primary_key();
+}
+
 #[tauri::command]
 pub fn get_report_list(report_channel: Channel<table::BasicMetadata>) -> Result<(), error::Error> {
     // Use channel to send BasicMetadata objects
@@ -667,12 +1705,215 @@ pub fn get_subtype_list(table_oid: i64, object_type_channel: Channel<obj_type::B
     return Ok(());
 }
 
+#[tauri::command]
+/// Get the number of direct subtypes of each object type.
+pub fn get_object_type_subtype_counts() -> Result<HashMap<i64, i64>, error::Error> {
+    return obj_type::subtype_counts();
+}
+
+#[tauri::command]
+/// Get every object in a type hierarchy that belongs to more than one subtype branch.
+pub fn get_object_type_integrity_issues(root_oid: i64) -> Result<Vec<obj_type::IntegrityIssue>, error::Error> {
+    return obj_type::check_integrity(root_oid);
+}
+
+#[tauri::command]
+/// Get every value that appears more than once in a dropdown column.
+pub fn get_duplicate_dropdown_values(column_oid: i64) -> Result<Vec<table_column::DuplicateDropdownValue>, error::Error> {
+    return table_column::find_duplicate_dropdown_values(column_oid);
+}
+
+#[tauri::command]
+/// Reorder the values of a dropdown column.
+pub fn reorder_table_column_dropdown_values(app: AppHandle, table_oid: i64, column_oid: i64, ordered_value_oids: Vec<i64>) -> Result<(), error::Error> {
+    table_column::reorder_dropdown_values(column_oid, ordered_value_oids)?;
+    msg_update_table_data(&app, table_oid);
+    return Ok(());
+}
+
+#[tauri::command]
+/// Get the SQL of the query used to get a page of data from a table.
+pub fn get_table_data_query(table_oid: i64, parent_row_oid: Option<i64>) -> Result<String, error::Error> {
+    return table_data::explain_query(table_oid, parent_row_oid);
+}
+
+#[tauri::command]
+/// Get the plan that SQLite uses to execute the query for a page of data from a table.
+pub fn get_table_data_query_plan(table_oid: i64, parent_row_oid: Option<i64>) -> Result<Vec<table_data::QueryPlanStep>, error::Error> {
+    return table_data::explain_query_plan(table_oid, parent_row_oid);
+}
+
+#[tauri::command]
+/// Get whether deleted rows of a table are moved to the trash or deleted permanently.
+pub fn get_table_delete_mode(table_oid: i64) -> Result<table::DeleteMode, error::Error> {
+    return table::get_delete_mode(table_oid);
+}
+
+#[tauri::command]
+/// Set whether deleted rows of a table are moved to the trash or deleted permanently.
+pub fn set_table_delete_mode(table_oid: i64, delete_mode: table::DeleteMode) -> Result<(), error::Error> {
+    return table::set_delete_mode(table_oid, delete_mode);
+}
+
+#[tauri::command]
+/// Get the rules under which the rows of a table are displayed with a style.
+pub fn get_table_format_rules(table_oid: i64) -> Result<Vec<table::FormatRule>, error::Error> {
+    return table::get_format_rules(table_oid);
+}
+
+#[tauri::command]
+/// Add a rule under which the rows of a table are displayed with a style.
+pub fn add_table_format_rule(table_oid: i64, condition_column_oid: i64, operator: table::FormatRuleOperator, value: Option<String>, css_style: String) -> Result<i64, error::Error> {
+    return table::add_format_rule(table_oid, condition_column_oid, operator, value, css_style);
+}
+
+#[tauri::command]
+/// Remove a rule under which the rows of a table are displayed with a style.
+pub fn remove_table_format_rule(rule_oid: i64) -> Result<bool, error::Error> {
+    return table::remove_format_rule(rule_oid);
+}
+
+#[tauri::command]
+/// Turn on or off the caching of display values for a table.
+pub fn set_table_display_value_materialized(table_oid: i64, materialize: bool) -> Result<(), error::Error> {
+    table::set_display_value_materialized(table_oid, materialize)?;
+    return Ok(());
+}
+
+#[tauri::command]
+/// Recreate the views that present each table with human-readable table and column names.
+pub fn refresh_friendly_views() -> Result<(), error::Error> {
+    return db::create_friendly_views();
+}
+
+#[tauri::command]
+/// Run an ad-hoc, read-only SQL query against the database.
+pub fn run_query(sql: String) -> Result<db::QueryResult, error::Error> {
+    return db::run_readonly_query(&sql);
+}
+
+#[tauri::command]
+/// Export the rows of a table to a CSV file, returning the number of rows written.
+/// If no dialect is given, the file is comma-separated with double quotes.
+/// If a view is given, only the rows passing its filter are exported, in its sort order.
+pub fn export_table_csv(table_oid: i64, path: String, dialect: Option<table_csv::CsvDialect>, view: Option<table_data::ExportView>, progress: Channel<table_data::ExportProgress>, operation_id: Option<i64>) -> Result<i64, error::Error> {
+    return table_csv::export_table(table_oid, &path, dialect.unwrap_or_default(), &view.unwrap_or_default(), Some(progress), operation_id);
+}
+
+#[tauri::command]
+/// Export the rows of a table to an Excel workbook, keeping numbers and dates as typed cells.
+/// If a view is given, only the rows passing its filter are exported, in its sort order.
+pub fn export_table_xlsx(table_oid: i64, path: String, view: Option<table_data::ExportView>, progress: Channel<table_data::ExportProgress>, operation_id: Option<i64>) -> Result<(), error::Error> {
+    return table_data::export_xlsx(table_oid, std::path::Path::new(&path), &view.unwrap_or_default(), Some(progress), operation_id);
+}
+
+#[tauri::command]
+/// Import the rows of a CSV file into a table, returning the number of rows imported.
+/// If no dialect is given, the file is read as comma-separated with double quotes.
+/// If a mapping is given, it overrides which column each column of the file is imported into.
+pub fn import_table_csv(app: AppHandle, table_oid: i64, path: String, dialect: Option<table_csv::CsvDialect>, mapping: Option<Vec<table_csv::CsvColumnOverride>>, progress: Channel<table_data::ExportProgress>, operation_id: Option<i64>) -> Result<i64, error::Error> {
+    let row_count = table_csv::import_table(table_oid, &path, dialect.unwrap_or_default(), mapping.unwrap_or_default(), Some(progress), operation_id)?;
+    msg_update_table_data(&app, table_oid);
+    return Ok(row_count);
+}
+
+#[tauri::command]
+/// Describe how the rows of a CSV file would be imported into a table, without importing them.
+/// If no dialect is given, the file is read as comma-separated with double quotes.
+pub fn preview_csv_import(table_oid: i64, path: String, dialect: Option<table_csv::CsvDialect>, has_header: Option<bool>) -> Result<table_csv::ImportPreview, error::Error> {
+    let mut file = std::fs::File::open(&path)?;
+    return table_csv::preview_import(table_oid, &mut file, dialect.unwrap_or_default(), has_header.unwrap_or(true));
+}
+
+#[tauri::command]
+/// Register a long-running import or export, returning the ID to pass to it so that it can be cancelled.
+pub fn begin_operation() -> i64 {
+    return table_data::begin_operation();
+}
+
+#[tauri::command]
+/// Cancel a long-running import or export, returning false if it is not running.
+pub fn cancel_operation(operation_id: i64) -> bool {
+    return table_data::cancel_operation(operation_id);
+}
+
+#[tauri::command]
+/// Export the structure of the database as JSON, excluding the data itself.
+pub fn export_schema_json() -> Result<String, error::Error> {
+    return db::export_schema_json();
+}
+
+#[tauri::command]
+/// Apply a schema exported as JSON to the database, creating anything that does not already exist.
+pub fn apply_schema_json(json: String) -> Result<db::SchemaApplyReport, error::Error> {
+    return db::apply_schema_json(&json);
+}
+
+#[tauri::command]
+/// Get every user-defined type whose metadata and backing tables are out of sync.
+pub fn get_orphaned_types() -> Result<Vec<db::OrphanedType>, error::Error> {
+    return db::find_orphaned_types();
+}
+
+#[tauri::command]
+/// Get the OID of every dropdown value table that no column uses.
+pub fn get_unreferenced_value_tables() -> Result<Vec<i64>, error::Error> {
+    return db::find_unreferenced_value_tables();
+}
+
+#[tauri::command]
+/// Report what repairing the database would remove and rebuild, without changing anything.
+pub fn preview_repair_database() -> Result<db::RepairReport, error::Error> {
+    return db::preview_repair();
+}
+
+#[tauri::command]
+/// Repair the database by removing the orphaned types and unused dropdown value tables confirmed from a preview and rebuilding every surrogate view, and report any columns whose metadata does not match the physical tables.
+pub fn repair_database(app: AppHandle, confirmation: db::RepairConfirmation) -> Result<db::RepairReport, error::Error> {
+    let report = db::repair(&confirmation)?;
+    msg_update_table_list(&app);
+    msg_update_obj_type_list(&app);
+    return Ok(report);
+}
+
+#[tauri::command]
+/// Rebuild the surrogate view of every table, returning the number of views rebuilt.
+pub fn rebuild_surrogate_views(app: AppHandle) -> Result<i64, error::Error> {
+    let rebuilt_count = table::rebuild_all_surrogate_views()?;
+    msg_update_table_list(&app);
+    return Ok(rebuilt_count);
+}
+
+#[tauri::command]
+/// Get every column in a table whose metadata does not match the physical columns of the table.
+pub fn get_table_column_mismatches(table_oid: i64) -> Result<Vec<table::ColumnMismatch>, error::Error> {
+    return table::verify_columns(table_oid);
+}
+
+#[tauri::command]
+/// Get a summary of the problems across every table of the database.
+pub fn get_database_health() -> Result<db::HealthReport, error::Error> {
+    return db::health_report();
+}
+
+#[tauri::command]
+/// Get every cell in a table that refers to a row that does not exist.
+pub fn get_integrity_report(table_oid: i64) -> Result<Vec<table_data::IntegrityViolation>, error::Error> {
+    return table_data::integrity_report(table_oid);
+}
+
 #[tauri::command]
 /// Get the metadata for a particular column in a table.
 pub fn get_table_column(column_oid: i64) -> Result<Option<table_column::Metadata>, error::Error> {
     return table_column::get_metadata(column_oid);
 }
 
+#[tauri::command]
+/// Get the rules that a value of a particular column must follow.
+pub fn get_column_validation_rules(column_oid: i64) -> Result<Option<table_column::ValidationRules>, error::Error> {
+    return table_column::get_validation_rules(column_oid);
+}
+
 #[tauri::command]
 /// Send possible dropdown values for a column.
 pub fn get_table_column_dropdown_values(column_oid: i64, dropdown_value_channel: Channel<table_column::DropdownValue>) -> Result<(), error::Error> {
@@ -681,20 +1922,53 @@ pub fn get_table_column_dropdown_values(column_oid: i64, dropdown_value_channel:
     return Ok(());
 }
 
+#[tauri::command]
+/// Set the default value of a column, which may be @now for a timestamp column or @today for a date column.
+pub fn set_table_column_default_value(column_oid: i64, default_value: Option<String>) -> Result<(), error::Error> {
+    return table_column::set_default_value(column_oid, default_value);
+}
+
+#[tauri::command]
+/// Set the range of plausible values for a date or timestamp column, outside of which values are flagged.
+pub fn set_table_column_date_bound(column_oid: i64, date_bound: Option<table_column::DateBound>) -> Result<(), error::Error> {
+    return table_column::set_date_bound(column_oid, date_bound);
+}
+
+#[tauri::command]
+/// Set the number of decimal places a number column is displayed with, or clear it to display values as stored.
+pub fn set_table_column_decimal_places(column_oid: i64, decimal_places: Option<i64>) -> Result<(), error::Error> {
+    return table_column::set_decimal_places(column_oid, decimal_places);
+}
+
+#[tauri::command]
+/// Set whether a timestamp column is set to the current time whenever another cell in the same row is edited.
+pub fn set_table_column_auto_update(column_oid: i64, auto_update: bool) -> Result<(), error::Error> {
+    return table_column::set_auto_update(column_oid, auto_update);
+}
+
 #[tauri::command] 
-/// Send possible tables to be referenced.
-pub fn get_table_column_reference_values(reference_type_channel: Channel<table_column::BasicTypeMetadata>) -> Result<(), error::Error> {
-    table_column::send_type_metadata_list(data_type::MetadataColumnType::Reference(0), reference_type_channel)?;
+/// Send possible tables to be referenced by a column of the given table.
+/// Unless allow_self is false, the table itself is included, as self-references are sometimes valid (e.g. an employee referencing their manager).
+/// Whichever table is chosen, a primary key column referencing it still passes through the check for loops of primary keys when the surrogate view is rebuilt.
+pub fn get_table_column_reference_values(table_oid: Option<i64>, allow_self: Option<bool>, reference_type_channel: Channel<table_column::BasicTypeMetadata>) -> Result<(), error::Error> {
+    let excluded_table_oid: Option<i64> = if allow_self.unwrap_or(true) { None } else { table_oid };
+    table_column::send_reference_type_list(excluded_table_oid, reference_type_channel)?;
     return Ok(());
 }
 
 #[tauri::command] 
-/// Send possible global data types for an object.
-pub fn get_table_column_object_values(object_type_channel: Channel<table_column::BasicTypeMetadata>) -> Result<(), error::Error> {
-    table_column::send_type_metadata_list(data_type::MetadataColumnType::ChildObject(0), object_type_channel)?;
+/// Send possible global data types for an object in the given table, excluding any that would make the table contain itself.
+pub fn get_table_column_object_values(table_oid: i64, object_type_channel: Channel<table_column::BasicTypeMetadata>) -> Result<(), error::Error> {
+    table_column::send_child_object_type_list(table_oid, object_type_channel)?;
     return Ok(());
 }
 
+#[tauri::command]
+/// Get the names and types of every table and column, for autocompletion of formulas.
+pub fn get_all_table_names_for_autocomplete() -> Result<db::SymbolTable, error::Error> {
+    return db::symbol_table();
+}
+
 #[tauri::command]
 pub fn get_table_column_list(table_oid: i64, column_channel: Channel<table_column::Metadata>) -> Result<(), error::Error> {
     // Use channel to send BasicMetadata objects
@@ -703,29 +1977,181 @@ pub fn get_table_column_list(table_oid: i64, column_channel: Channel<table_colum
 }
 
 #[tauri::command]
-pub fn get_table_data(table_oid: i64, parent_row_oid: Option<i64>, page_num: i64, page_size: i64, cell_channel: Channel<table_data::Cell>) -> Result<(), error::Error> {
-    table_data::send_table_data(table_oid, parent_row_oid, page_num, page_size, cell_channel)?;
+/// Turn on or off the profiling of table data queries.
+/// While on, a table-data-profile event is emitted every time a page of table data is sent.
+pub fn set_profiling_enabled(enabled: bool) {
+    IS_PROFILING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+#[tauri::command]
+pub fn get_table_data(app: AppHandle, table_oid: i64, parent_row_oid: Option<i64>, page_num: i64, page_size: i64, cell_channel: Channel<table_data::Cell>) -> Result<(), error::Error> {
+    let profile = table_data::send_table_data(table_oid, parent_row_oid, page_num, page_size, cell_channel)?;
+    if IS_PROFILING_ENABLED.load(Ordering::Relaxed) {
+        app.emit("table-data-profile", profile)?;
+    }
     return Ok(());
 }
 
+#[tauri::command]
+/// Get all rows of a table, grouped by the displayed value of a column.
+pub fn get_table_grouped_data(table_oid: i64, group_column_oid: i64, cell_channel: Channel<table_data::GroupedCell>) -> Result<(), error::Error> {
+    return table_data::send_grouped_data(table_oid, group_column_oid, cell_channel);
+}
+
+#[tauri::command]
+/// Insert many rows into a table at once, returning their OIDs.
+/// Unlike inserting a single row, this cannot be undone.
+pub fn bulk_insert_table_rows(app: AppHandle, table_oid: i64, rows: Vec<HashMap<i64, Option<String>>>, validate: Option<bool>) -> Result<Vec<i64>, error::Error> {
+    let row_oids = table_data::bulk_insert(table_oid, rows, validate.unwrap_or(true))?;
+    msg_update_table_data(&app, table_oid);
+    return Ok(row_oids);
+}
+
+#[tauri::command]
+/// Get the OID of the row displayed after or before a row, if there is one.
+pub fn get_adjacent_row(table_oid: i64, row_oid: i64, direction: table_data::Direction, parent_row_oid: Option<i64>) -> Result<Option<i64>, error::Error> {
+    return table_data::adjacent_row(table_oid, row_oid, direction, parent_row_oid);
+}
+
+#[tauri::command]
+/// Get everything needed to display a form for editing a single row.
+pub fn get_row_edit_form(table_oid: i64, row_oid: i64) -> Result<table_data::RowEditForm, error::Error> {
+    return table_data::get_row_edit_form(table_oid, row_oid);
+}
+
+#[tauri::command]
+/// Save the page, sort, filter, and scroll position that a table is being viewed with.
+pub fn save_table_view_state(table_oid: i64, view_state: table::ViewState) -> Result<(), error::Error> {
+    return table::save_view_state(table_oid, &view_state);
+}
+
+#[tauri::command]
+/// Get the page, sort, filter, and scroll position that a table was last viewed with.
+pub fn get_table_view_state(table_oid: i64) -> Result<Option<table::ViewState>, error::Error> {
+    return table::get_view_state(table_oid);
+}
+
+#[tauri::command]
+/// Get the comment attached to a cell, if any.
+pub fn get_cell_comment(table_oid: i64, row_oid: i64, column_oid: i64) -> Result<Option<table_data::CellComment>, error::Error> {
+    return table_data::get_cell_comment(table_oid, row_oid, column_oid);
+}
+
+#[tauri::command]
+/// Get every tag used on the rows of a table.
+pub fn get_table_tags(table_oid: i64) -> Result<Vec<String>, error::Error> {
+    return table_data::get_table_tags(table_oid);
+}
+
+#[tauri::command]
+/// Get the OIDs of the rows in a table that have a tag.
+pub fn get_table_rows_by_tag(table_oid: i64, tag: String) -> Result<Vec<i64>, error::Error> {
+    return table_data::get_rows_by_tag(table_oid, &tag);
+}
+
+#[tauri::command]
+/// Get the display values of a single column, in the order the rows are displayed.
+pub fn get_column_values(table_oid: i64, column_oid: i64, limit: i64, offset: i64) -> Result<Vec<Option<String>>, error::Error> {
+    return table_data::get_column_values(table_oid, column_oid, limit, offset);
+}
+
+#[tauri::command]
+/// Get the values of a single integer or number column as numbers, in the order the rows are displayed.
+pub fn get_column_numeric_values(table_oid: i64, column_oid: i64, limit: i64, offset: i64) -> Result<Vec<Option<f64>>, error::Error> {
+    return table_data::get_column_numeric_values(table_oid, column_oid, limit, offset);
+}
+
+#[tauri::command]
+/// Get the display values of only the specified columns in a row.
+pub fn get_cell_values(table_oid: i64, row_oid: i64, column_oids: Vec<i64>) -> Result<HashMap<i64, Option<String>>, error::Error> {
+    return table_data::get_cell_values(table_oid, row_oid, column_oids);
+}
+
 #[tauri::command]
 pub fn get_table_row(table_oid: i64, row_oid: i64, cell_channel: Channel<table_data::RowCell>) -> Result<(), error::Error> {
     table_data::send_table_row(table_oid, row_oid, cell_channel)?;
     return Ok(());
 }
 
+#[tauri::command]
+/// Store a file from the given path in a cell.
+pub fn upload_table_cell_file(app: AppHandle, table_oid: i64, row_oid: i64, column_oid: i64, path: String) -> Result<(), error::Error> {
+    let file_path = std::path::Path::new(&path);
+    let file_name: Option<String> = file_path.file_name().map(|name| name.to_string_lossy().into_owned());
+    let mime_type: Option<String> = match &file_name {
+        Some(name) => table_data::guess_mime_type(name),
+        None => None
+    };
+    let data: Vec<u8> = std::fs::read(file_path)?;
+    table_data::set_file(table_oid, row_oid, column_oid, Some(table_data::File { 
+        name: file_name, 
+        mime_type, 
+        data 
+    }))?;
+    msg_update_table_row(&app, table_oid, row_oid);
+    return Ok(());
+}
+
+#[tauri::command]
+/// Get the name, MIME type, and size of the file stored in a cell.
+pub fn get_file_metadata(table_oid: i64, row_oid: i64, column_oid: i64) -> Result<Option<table_data::FileMeta>, error::Error> {
+    return table_data::get_file_metadata(table_oid, row_oid, column_oid);
+}
+
+#[tauri::command]
+/// Send the contents of the file stored in a cell a chunk at a time.
+pub fn stream_table_cell_file(table_oid: i64, row_oid: i64, column_oid: i64, file_channel: Channel<Vec<u8>>) -> Result<(), error::Error> {
+    table_data::stream_file(table_oid, row_oid, column_oid, file_channel)?;
+    return Ok(());
+}
+
+#[tauri::command]
+/// Save the file stored in a cell to a location chosen by the user, suggesting the file's original name.
+pub async fn download_table_cell_file(app: AppHandle, table_oid: i64, row_oid: i64, column_oid: i64) -> Result<(), error::Error> {
+    match table_data::get_file(table_oid, row_oid, column_oid)? {
+        Some(file) => {
+            let mut file_dialog = app.dialog().file();
+            match &file.name {
+                Some(name) => {
+                    file_dialog = file_dialog.set_file_name(name);
+                },
+                None => {}
+            }
+            match file_dialog.blocking_save_file() {
+                Some(file_path) => {
+                    let path = match file_path.into_path() {
+                        Ok(p) => p,
+                        Err(_) => { return Err(error::Error::AdhocError("The chosen location is not a valid file path.")); }
+                    };
+                    std::fs::write(path, &file.data)?;
+                },
+                None => {}
+            }
+        },
+        None => {}
+    }
+    return Ok(());
+}
+
 #[tauri::command]
 pub fn get_object_data(obj_type_oid: i64, obj_row_oid: i64, obj_data_channel: Channel<table_data::RowCell>) -> Result<(), error::Error> {
     obj_type::send_obj_data(obj_type_oid, obj_row_oid, obj_data_channel)?;
     return Ok(());
 }
 
+#[tauri::command]
+/// Send the combined values of an object type and all of its supertypes.
+pub fn get_object_type_flat_data(obj_type_oid: i64, flat_channel: Channel<obj_type::FlatCell>) -> Result<(), error::Error> {
+    obj_type::send_flattened_data(obj_type_oid, flat_channel)?;
+    return Ok(());
+}
+
 
 #[tauri::command]
 /// Executes an action that affects the state of the database.
 pub fn execute(app: AppHandle, action: Action) -> Result<(), error::Error> {
     // Do something that affects the database
-    action.execute(&app, true)?;
+    action.execute(&app, ActionDirection::Execute)?;
 
     // Clear the stack of undone actions
     let mut forward_stack = FORWARD_STACK.lock().unwrap();
@@ -733,6 +2159,58 @@ pub fn execute(app: AppHandle, action: Action) -> Result<(), error::Error> {
     return Ok(());
 }
 
+#[tauri::command]
+/// Renumbers the rows of a table to a contiguous sequence of OIDs.
+/// Since undoing or redoing an earlier action could refer to a row by its old OID, the history of actions is cleared.
+pub fn compact_table_oids(app: AppHandle, table_oid: i64) -> Result<(), error::Error> {
+    table_data::compact_oids(table_oid)?;
+    *REVERSE_STACK.lock().unwrap() = Vec::new();
+    *FORWARD_STACK.lock().unwrap() = Vec::new();
+    msg_update_table_data(&app, table_oid);
+    return Ok(());
+}
+
+#[tauri::command]
+/// Previews what an action would affect, without executing it.
+pub fn preview(action: Action) -> Result<ActionPreview, error::Error> {
+    return action.preview();
+}
+
+#[tauri::command]
+/// Set how many minutes pass between automatic checkpoints of the write-ahead log, or 0 to disable them.
+pub fn set_checkpoint_interval(minutes: u64) {
+    db::set_checkpoint_interval_minutes(minutes);
+}
+
+/// Start running automatic checkpoints of the write-ahead log in the background.
+pub fn spawn_checkpoint_timer() {
+    tauri::async_runtime::spawn_blocking(db::run_checkpoint_timer);
+}
+
+/// Start watching for changes made to the database by other processes in the background, notifying the frontend of each one.
+pub fn spawn_external_change_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn_blocking(move || {
+        db::run_external_change_watcher(|| {
+            // A failure to notify the window should not stop the watcher
+            if let Err(e) = app.emit("external-change", ()) {
+                log::warn!("Could not report an external change: {e}");
+            }
+        });
+    });
+}
+
+#[tauri::command]
+/// Get the data version of the database, which changes whenever another process commits a change to it.
+pub fn get_data_version() -> Result<i64, error::Error> {
+    return db::data_version();
+}
+
+#[tauri::command]
+/// Get a page of the log of committed actions, most recent first.
+pub fn get_audit_log(limit: i64, offset: i64) -> Result<Vec<db::AuditLogEntry>, error::Error> {
+    return db::get_audit_log(limit, offset);
+}
+
 #[tauri::command]
 /// Undoes the last action by popping the top of the reverse stack.
 pub fn undo(app: AppHandle) -> Result<(), error::Error> {
@@ -742,7 +2220,7 @@ pub fn undo(app: AppHandle) -> Result<(), error::Error> {
         (*reverse_stack).pop()
     } {
         Some(reverse_action) => {
-            reverse_action.execute(&app, false)?;
+            reverse_action.execute(&app, ActionDirection::Undo)?;
         },
         None => {}
     }
@@ -758,9 +2236,97 @@ pub fn redo(app: AppHandle) -> Result<(), error::Error> {
         (*forward_stack).pop()
     } {
         Some(forward_action) => {
-            forward_action.execute(&app, true)?;
+            forward_action.execute(&app, ActionDirection::Redo)?;
         },
         None => {}
     }
     return Ok(());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{params, OptionalExtension};
+
+    #[test]
+    fn clear_table_can_be_previewed_and_undone() {
+        let _db = test_util::open_test_db();
+
+        // Create a table with two rows, and one row already in the trash
+        let table_oid = table::create(String::from("Inbox"), &vec![]).unwrap();
+        let kept_row_oids: Vec<i64> = (0..2).map(|_| table_data::push(table_oid).unwrap()).collect();
+        let trashed_row_oid = table_data::push(table_oid).unwrap();
+        table_data::move_trash(table_oid, trashed_row_oid).unwrap();
+        let count_rows = || table::count_rows_in(&db::open().unwrap(), table_oid).unwrap();
+
+        // Previewing the clear reports the rows it would trash without trashing them
+        let preview = Action::ClearTable { table_oid }.preview().unwrap();
+        assert_eq!(preview.affected_row_count, 2);
+        assert!(preview.error.is_none());
+        assert_eq!(count_rows(), 2);
+
+        // Clearing the table trashes only the rows that were not already trash
+        let trashed_row_oids = table_data::clear(table_oid).unwrap();
+        assert_eq!(trashed_row_oids, kept_row_oids);
+        assert_eq!(count_rows(), 0);
+
+        // Undoing the clear restores those rows
+        table_data::unmove_trash_rows(table_oid, trashed_row_oids).unwrap();
+        assert_eq!(count_rows(), 2);
+    }
+
+    #[test]
+    fn audit_details_only_keep_oids() {
+        let (action_type, details) = Action::DeleteTableRows { table_oid: 3, row_oids: vec![1, 2] }.get_audit_details();
+        assert_eq!(action_type, "deleteTableRows");
+        assert_eq!(details, r#"{"rowOids":[1,2],"tableOid":3}"#);
+
+        // Values carried by the action are left out
+        let (action_type, details) = Action::UpdateTableCellStoredAsPrimitiveValue { table_oid: 3, column_oid: 4, row_oid: 5, value: Some(String::from("secret")) }.get_audit_details();
+        assert_eq!(action_type, "updateTableCellStoredAsPrimitiveValue");
+        assert_eq!(details, r#"{"columnOid":4,"rowOid":5,"tableOid":3}"#);
+    }
+
+    #[test]
+    fn deleted_rows_are_restored_in_either_delete_mode() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+
+        // Create a table with a named row, and a table referencing that row
+        let table_oid = table::create(String::from("Contacts"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(table_oid, "Name", text_type, None, "", true, false, false, None).unwrap();
+        let row_oid = table_data::push(table_oid).unwrap();
+        table_data::try_update_primitive_value(table_oid, row_oid, name_column_oid, Some(String::from("Ada"))).unwrap();
+        let source_oid = table::create(String::from("Messages"), &vec![]).unwrap();
+        let reference_column_oid = table_column::create(source_oid, "Sender", data_type::MetadataColumnType::Reference(table_oid), None, "", true, false, false, None).unwrap();
+        let source_row_oid = table_data::push(source_oid).unwrap();
+        let conn = db::open().unwrap();
+        conn.execute(&format!("UPDATE TABLE{source_oid} SET COLUMN{reference_column_oid} = ?1 WHERE OID = ?2;"), params![row_oid, source_row_oid]).unwrap();
+        let get_row = || conn.query_one(&format!("SELECT TRASH, COLUMN{name_column_oid} FROM TABLE{table_oid} WHERE OID = ?1;"), params![row_oid], |row| Ok((row.get::<_, bool>(0)?, row.get::<_, String>(1)?))).optional().unwrap();
+        let get_reference = || conn.query_one(&format!("SELECT COLUMN{reference_column_oid} FROM TABLE{source_oid} WHERE OID = ?1;"), params![source_row_oid], |row| row.get::<_, Option<i64>>(0)).unwrap();
+
+        // Rows are moved to the trash by default, and undoing takes them back out
+        assert!(table::get_delete_mode(table_oid).unwrap() == table::DeleteMode::Soft);
+        table_data::move_trash(table_oid, row_oid).unwrap();
+        assert_eq!(get_row(), Some((true, String::from("Ada"))));
+        table_data::unmove_trash(table_oid, row_oid).unwrap();
+        assert_eq!(get_row(), Some((false, String::from("Ada"))));
+
+        // Once deletions are permanent, the row is removed along with any reference to it
+        table::set_delete_mode(table_oid, table::DeleteMode::Hard).unwrap();
+        assert!(table::get_delete_mode(table_oid).unwrap() == table::DeleteMode::Hard);
+        let snapshot = table_data::delete(table_oid, row_oid).unwrap();
+        assert_eq!(get_row(), None);
+        assert_eq!(get_reference(), None);
+
+        // The snapshot survives being stored with its undo action, and restores the row and the reference to it
+        let undo_action = serde_json::to_string(&Action::RestorePermanentlyDeletedTableRow { table_oid, row_oid, snapshot }).unwrap();
+        let snapshot = match serde_json::from_str::<Action>(&undo_action).unwrap() {
+            Action::RestorePermanentlyDeletedTableRow { snapshot, .. } => snapshot,
+            _ => panic!("The undo action was not restored as itself.")
+        };
+        table_data::restore_deleted(snapshot).unwrap();
+        assert_eq!(get_row(), Some((false, String::from("Ada"))));
+        assert_eq!(get_reference(), Some(row_oid));
+    }
+}
@@ -1,6 +1,6 @@
-use rusqlite::{Transaction, params};
+use rusqlite::{Connection, params};
 use serde::{Serialize, Deserialize};
-use crate::backend::{db};
+use crate::backend::{db, table};
 use crate::util::error;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
@@ -15,6 +15,7 @@ pub enum Primitive {
     JSON,       // Mode = 0 && OID = 7
     File,       // Mode = 0 && OID = 8
     Image,      // Mode = 0 && OID = 9
+    StringList, // Mode = 0 && OID = -1
 }
 
 impl Primitive {
@@ -27,11 +28,19 @@ impl Primitive {
             Self::Number => "REAL",
             Self::Date => "REAL",
             Self::Timestamp => "REAL",
-            Self::Text | Self::JSON => "TEXT",
+            Self::Text | Self::JSON | Self::StringList => "TEXT",
             Self::File | Self::Image => "BLOB",
         }
     }
 
+    /// Checks whether the primitive type stores a file, alongside the file's name and MIME type.
+    pub fn is_file(&self) -> bool {
+        return match self {
+            Self::File | Self::Image => true,
+            _ => false
+        };
+    }
+
     /// Gets the corresponding type OID of a given primitive type.
     pub fn get_type_oid(&self) -> i64 {
         match self {
@@ -45,6 +54,7 @@ impl Primitive {
             Self::JSON => 7,
             Self::File => 8,
             Self::Image => 9,
+            Self::StringList => -1,
         }
     }
 }
@@ -75,6 +85,7 @@ impl MetadataColumnType {
                     7 => { return Self::Primitive(Primitive::JSON); },
                     8 => { return Self::Primitive(Primitive::File); },
                     9 => { return Self::Primitive(Primitive::Image); },
+                    -1 => { return Self::Primitive(Primitive::StringList); },
                     _ => {
                         return Self::Primitive(Primitive::Any);
                     }
@@ -117,7 +128,7 @@ impl MetadataColumnType {
 
     /// If the type is unique to a specific table (i.e. single-select dropdown, multi-select dropdown, child table), creates rows/tables for the specified type.
     /// Returns the OID for the type.
-    pub fn create_for_table(self, trans: &Transaction, table_oid: &i64) -> Result<Self, error::Error> {
+    pub fn create_for_table(self, trans: &Connection, table_oid: &i64) -> Result<Self, error::Error> {
         match self {
             Self::Primitive(_)
             | Self::Reference(_)
@@ -133,8 +144,10 @@ impl MetadataColumnType {
                 let column_type_oid = trans.last_insert_rowid();
 
                 // Create table to store dropdown values
-                let create_table_cmd = format!("CREATE TABLE TABLE{column_type_oid} (OID INTEGER PRIMARY KEY, TRASH TINYINT NOT NULL DEFAULT 0, VALUE TEXT NOT NULL);");
-                trans.execute(&create_table_cmd, [])?;
+                let create_table_cmd = format!("
+                CREATE TABLE TABLE{column_type_oid} (OID INTEGER PRIMARY KEY, TRASH TINYINT NOT NULL DEFAULT 0, VALUE TEXT NOT NULL, ORDERING INTEGER NOT NULL DEFAULT 0);
+                CREATE UNIQUE INDEX TABLE{column_type_oid}_UNIQUE_VALUE ON TABLE{column_type_oid} (VALUE) WHERE TRASH = 0;");
+                trans.execute_batch(&create_table_cmd)?;
 
                 // Return the OID of the created type
                 return Ok(Self::SingleSelectDropdown(column_type_oid));
@@ -148,8 +161,10 @@ impl MetadataColumnType {
                 let column_type_oid = trans.last_insert_rowid();
 
                 // Create table to store dropdown values
-                let create_table_cmd = format!("CREATE TABLE TABLE{column_type_oid} (OID INTEGER PRIMARY KEY, TRASH TINYINT NOT NULL DEFAULT 0, VALUE TEXT NOT NULL);");
-                trans.execute(&create_table_cmd, [])?;
+                let create_table_cmd = format!("
+                CREATE TABLE TABLE{column_type_oid} (OID INTEGER PRIMARY KEY, TRASH TINYINT NOT NULL DEFAULT 0, VALUE TEXT NOT NULL, ORDERING INTEGER NOT NULL DEFAULT 0);
+                CREATE UNIQUE INDEX TABLE{column_type_oid}_UNIQUE_VALUE ON TABLE{column_type_oid} (VALUE) WHERE TRASH = 0;");
+                trans.execute_batch(&create_table_cmd)?;
 
                 // Create table to store relationship with base table
                 let create_relationship_cmd = format!("
@@ -192,16 +207,10 @@ impl MetadataColumnType {
                         ON DELETE CASCADE
                 );");
                 trans.execute(&create_table_cmd, [])?;
+                db::add_order_key(trans, column_type_oid)?;
 
                 // Create a surrogate view for the child table
-                let create_view_cmd = format!("
-                CREATE VIEW TABLE{column_type_oid}_SURROGATE (OID, DISPLAY_VALUE) 
-                AS 
-                SELECT 
-                    OID, 
-                    CASE WHEN TRASH = 0 THEN '— NO PRIMARY KEY —' ELSE '— DELETED —' END AS DISPLAY_VALUE 
-                FROM TABLE{column_type_oid};");
-                trans.execute(&create_view_cmd, [])?;
+                table::update_surrogate_view(trans, column_type_oid)?;
 
                 // Return the OID of the created type
                 return Ok(Self::ChildTable(column_type_oid));
@@ -210,7 +219,7 @@ impl MetadataColumnType {
     }
 
     /// If the type is unique to a specific table (i.e. single-select dropdown, multi-select dropdown, child table), deletes the type and any associated rows/tables.
-    pub fn delete_for_table(self, trans: &Transaction) -> Result<(), error::Error> {
+    pub fn delete_for_table(self, trans: &Connection) -> Result<(), error::Error> {
         match self {
             Self::Primitive(_)
             | Self::Reference(_)
@@ -1,39 +1,162 @@
 use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
 use std::path::{Path};
 use std::sync::{Mutex,MutexGuard};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
 use rusqlite::fallible_streaming_iterator::FallibleStreamingIterator;
-use rusqlite::{Connection, DropBehavior, Result, Transaction, TransactionBehavior, params, Params, Row};
-use crate::backend::table_data;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Result, params, Params, Row};
+use rusqlite::types::ValueRef;
+use serde::{Deserialize, Serialize};
+use crate::backend::{data_type, obj_type, table, table_column, table_data};
 use crate::util::error;
 
 static DATABASE_PATH: Mutex<Option<String>> = Mutex::new(None);
+static ACTIVE_ACTION_COUNT: AtomicUsize = AtomicUsize::new(0);
+static CHECKPOINT_INTERVAL_MINUTES: AtomicU64 = AtomicU64::new(5);
+static LOCAL_COMMIT_COUNT: AtomicU64 = AtomicU64::new(0);
+static DATA_VERSION_WATCH: Mutex<Option<DataVersionWatch>> = Mutex::new(None);
+
+/// How often the database is checked for changes committed by other processes.
+const EXTERNAL_CHANGE_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 /// Data structure locking access to the database while a function performs an action.
-pub struct DbAction<'a> {
+/// The action is committed when it is dropped, unless it is finished with an error, in which case it is rolled back.
+/// A read-only action is always rolled back, as it has nothing to commit.
+pub struct DbAction {
     conn: Connection,
-    pub trans: Transaction<'a>
+    changes_at_begin: u64,
+    is_readonly: bool,
+    is_finished: Cell<bool>
+}
+
+impl Deref for DbAction {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        return &self.conn;
+    }
 }
 
-impl DbAction<'_> {
+impl DbAction {
     /// Convenience method to execute a query that returns multiple rows, then execute a function for each row.
     pub fn query_iterate<P: Params, F: FnMut(&Row<'_>) -> Result<(), error::Error>>(&self, sql: &str, p: P, f: &mut F) -> Result<(), error::Error> {
-        // Prepare a statement
-        let mut stmt = match self.trans.prepare(sql) {
-            Ok(s) => s,
-            Err(e) => { return Err(error::Error::RusqliteError(e)); }
-        };
+        return query_iterate(&self.conn, sql, p, f);
+    }
 
-        // Execute the statement to query rows
-        let mut rows = stmt.query(p)?;
-        loop {
-            let row = match rows.next()? {
-                Some(r) => r,
-                None => { break; }
-            };
-            f(row);
+    /// Records the time the database was modified, along with the audit log entry of the undoable action being executed on this thread, if the action changed anything.
+    fn record_changes(&self) -> Result<(), error::Error> {
+        if self.conn.total_changes() != self.changes_at_begin {
+            self.conn.execute("UPDATE METADATA_DOCUMENT SET MODIFIED_AT = julianday('now');", [])?;
+            if let Some(entry) = PENDING_AUDIT_ENTRY.with(|e| e.borrow_mut().take()) {
+                self.conn.prepare_cached("INSERT INTO METADATA_AUDIT_LOG (DIRECTION, ACTION_TYPE, DETAILS) VALUES (?1, ?2, ?3);")?
+                    .execute(params![entry.direction, entry.action_type, entry.details])?;
+            }
         }
         return Ok(());
     }
+
+    /// Finishes the action, committing it if the result is OK or rolling it back if the result is an error.
+    /// The action is borrowed rather than consumed, so that the result can be computed within the same expression, e.g. action.finish(f(&action)).
+    pub fn finish<T>(&self, result: Result<T, error::Error>) -> Result<T, error::Error> {
+        self.is_finished.set(true);
+        if self.is_readonly {
+            let _ = self.conn.execute_batch("ROLLBACK;");
+            return result;
+        }
+        match result {
+            Ok(value) => {
+                if let Err(e) = self.record_changes() {
+                    let _ = self.conn.execute_batch("ROLLBACK;");
+                    return Err(e);
+                }
+
+                // A deferred foreign key that is still violated fails the commit, leaving the transaction open to be rolled back
+                if let Err(e) = self.conn.execute_batch("COMMIT;") {
+                    let _ = self.conn.execute_batch("ROLLBACK;");
+                    return Err(e.into());
+                }
+                return Ok(value);
+            },
+            Err(e) => {
+                // The original error is more informative than any error from rolling back
+                let _ = self.conn.execute_batch("ROLLBACK;");
+                return Err(e);
+            }
+        }
+    }
+
+    /// Finishes the action by rolling it back regardless of the result, so that an action can be tried out without changing the database.
+    pub fn rollback<T>(&self, result: Result<T, error::Error>) -> Result<T, error::Error> {
+        self.is_finished.set(true);
+        self.conn.execute_batch("ROLLBACK;")?;
+        return result;
+    }
+}
+
+impl Drop for DbAction {
+    fn drop(&mut self) {
+        if !self.is_finished.get() {
+            // Commit the action, unless the thread is unwinding from a panic midway through the action
+            let end_cmd: &str = if self.is_readonly || std::thread::panicking() { 
+                "ROLLBACK;" 
+            } else { 
+                let _ = self.record_changes();
+                "COMMIT;" 
+            };
+            let _ = self.conn.execute_batch(end_cmd);
+        }
+        ACTIVE_ACTION_COUNT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Runs part of an ongoing action with foreign keys checked only once the function returns, rather than after each statement.
+/// This allows rows to be inserted before the rows they refer to, so long as every reference is satisfied by the time the function returns.
+/// Foreign keys are checked after each statement again once the function returns.
+pub fn with_deferred_fk<T, F: FnOnce(&Connection) -> Result<T, error::Error>>(trans: &Connection, f: F) -> Result<T, error::Error> {
+    trans.execute_batch("PRAGMA defer_foreign_keys = ON;")?;
+    let result = f(trans).and_then(|value| {
+        // Turning deferral back off forgets any violation that is still outstanding, so look for them first
+        let violation_count: i64 = trans.query_one("SELECT COUNT(*) FROM pragma_foreign_key_check;", [], |row| row.get(0))?;
+        if violation_count > 0 {
+            return Err(error::Error::AdhocError("Some rows refer to rows that do not exist."));
+        }
+        return Ok(value);
+    });
+    trans.execute_batch("PRAGMA defer_foreign_keys = OFF;")?;
+    return result;
+}
+
+/// Begins an action that modifies the database.
+/// The write lock is taken immediately, so that another process writing in the meantime fails the action up front rather than partway through.
+pub fn begin_db_action() -> Result<DbAction, error::Error> {
+    let conn = open()?;
+    conn.execute_batch("BEGIN IMMEDIATE;")?;
+    ACTIVE_ACTION_COUNT.fetch_add(1, Ordering::SeqCst);
+    let changes_at_begin = conn.total_changes();
+    return Ok(DbAction { 
+        conn, 
+        changes_at_begin,
+        is_readonly: false,
+        is_finished: Cell::new(false)
+    });
+}
+
+/// Begins an action that only reads from the database.
+/// The connection is set to refuse any modification, and the action is rolled back when it is dropped.
+pub fn begin_readonly_db_action() -> Result<DbAction, error::Error> {
+    let conn = open()?;
+    conn.execute_batch("PRAGMA query_only = ON; BEGIN DEFERRED;")?;
+    ACTIVE_ACTION_COUNT.fetch_add(1, Ordering::SeqCst);
+    let changes_at_begin = conn.total_changes();
+    return Ok(DbAction { 
+        conn, 
+        changes_at_begin,
+        is_readonly: true,
+        is_finished: Cell::new(false)
+    });
 }
 
 /// Initializes a new database at the given path.
@@ -72,6 +195,7 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
     INSERT INTO METADATA_TYPE (OID, MODE) VALUES (7, 0); -- Text (JSON)
     INSERT INTO METADATA_TYPE (OID, MODE) VALUES (8, 0); -- BLOB
     INSERT INTO METADATA_TYPE (OID, MODE) VALUES (9, 0); -- BLOB (displayed as image thumbnail)
+    INSERT INTO METADATA_TYPE (OID, MODE) VALUES (-1, 0); -- Text (JSON array of strings)
 
     -- METADATA_RPT_PARAMETER stores all parameters to a user-defined report
     CREATE TABLE METADATA_RPT_PARAMETER (
@@ -83,6 +207,12 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
         TYPE_OID INTEGER PRIMARY KEY,
         TRASH BOOLEAN NOT NULL DEFAULT 0,
         NAME TEXT NOT NULL,
+        MATERIALIZE_DISPLAY_VALUE BOOLEAN NOT NULL DEFAULT 0,
+            -- Whether the display value of each row is cached in a DISPLAY_VALUE_CACHE column of the table
+        FRIENDLY_VIEW_NAME TEXT,
+            -- The name of the view that presents the table with human-readable table and column names
+        DELETE_MODE TINYINT NOT NULL DEFAULT 0,
+            -- 0 if deleted rows are moved to the trash, 1 if they are deleted permanently
         FOREIGN KEY (TYPE_OID) REFERENCES METADATA_TYPE (OID) 
             ON UPDATE CASCADE
             ON DELETE CASCADE
@@ -91,6 +221,19 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
         REFERENCES METADATA_TABLE (TYPE_OID) 
             ON UPDATE CASCADE;
 
+    -- METADATA_TABLE_VIEW_STATE stores the page, sort, filter, and scroll position that each table was last viewed with
+    CREATE TABLE METADATA_TABLE_VIEW_STATE (
+        TABLE_OID INTEGER PRIMARY KEY REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        PAGE_NUM INTEGER NOT NULL DEFAULT 1,
+        PAGE_SIZE INTEGER NOT NULL DEFAULT 1000,
+        SORT_COLUMN_OID INTEGER,
+        SORT_ASCENDING BOOLEAN NOT NULL DEFAULT 1,
+        FILTER_TEXT TEXT,
+        SCROLL_TOP INTEGER NOT NULL DEFAULT 0
+    );
+
     -- METADATA_TABLE_INHERITANCE stores inheritance of columns from another table
     CREATE TABLE METADATA_TABLE_INHERITANCE (
         OID INTEGER PRIMARY KEY,
@@ -118,7 +261,15 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
         IS_NULLABLE TINYINT NOT NULL DEFAULT 1,
         IS_UNIQUE TINYINT NOT NULL DEFAULT 0,
         IS_PRIMARY_KEY TINYINT NOT NULL DEFAULT 0,
+        IS_PINNED TINYINT NOT NULL DEFAULT 0,
+            -- Whether the column stays frozen in place while the table is scrolled horizontally
+        AUTO_UPDATE TINYINT NOT NULL DEFAULT 0,
+            -- Whether a timestamp column is set to the current time whenever another cell in the row is edited
         DEFAULT_VALUE ANY,
+        DATE_BOUND TEXT,
+            -- The range of plausible values of a date or timestamp column, as JSON
+        DECIMAL_PLACES INTEGER,
+            -- The number of decimal places a number column is displayed with, or NULL to display the value as stored
         FOREIGN KEY (RPT_PARAMETER_OID) REFERENCES METADATA_RPT_PARAMETER (OID)
             ON UPDATE CASCADE,
         FOREIGN KEY (TABLE_OID) REFERENCES METADATA_TABLE (TYPE_OID)
@@ -129,6 +280,57 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
             ON DELETE SET DEFAULT
     );
 
+    -- METADATA_CELL_COMMENT stores notes that the user has attached to individual cells
+    CREATE TABLE METADATA_CELL_COMMENT (
+        TABLE_OID INTEGER NOT NULL REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        ROW_OID INTEGER NOT NULL,
+        COLUMN_OID INTEGER NOT NULL REFERENCES METADATA_TABLE_COLUMN (OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        COMMENT TEXT NOT NULL,
+        CREATED_AT REAL NOT NULL DEFAULT (julianday('now')),
+        PRIMARY KEY (TABLE_OID, ROW_OID, COLUMN_OID)
+    );
+
+    -- METADATA_ROW_TAG stores ad-hoc labels that the user has attached to rows
+    CREATE TABLE METADATA_ROW_TAG (
+        TABLE_OID INTEGER NOT NULL REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        ROW_OID INTEGER NOT NULL,
+        TAG TEXT NOT NULL,
+        PRIMARY KEY (TABLE_OID, ROW_OID, TAG)
+    );
+    CREATE INDEX IF NOT EXISTS METADATA_ROW_TAG_BY_TAG ON METADATA_ROW_TAG (TABLE_OID, TAG);
+
+    -- METADATA_TABLE_FORMAT_RULE stores conditions under which the rows of a table are displayed with a style
+    CREATE TABLE METADATA_TABLE_FORMAT_RULE (
+        OID INTEGER PRIMARY KEY,
+        TABLE_OID INTEGER NOT NULL REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        CONDITION_COLUMN_OID INTEGER NOT NULL REFERENCES METADATA_TABLE_COLUMN (OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        OPERATOR TEXT NOT NULL,
+            -- One of the variants of table::FormatRuleOperator
+        VALUE TEXT,
+        CSS_STYLE TEXT NOT NULL
+    );
+
+    -- METADATA_AUDIT_LOG stores a record of every action committed to the database
+    CREATE TABLE METADATA_AUDIT_LOG (
+        OID INTEGER PRIMARY KEY,
+        LOGGED_AT REAL NOT NULL DEFAULT (julianday('now')),
+        DIRECTION TEXT NOT NULL,
+            -- One of 'execute', 'undo', or 'redo'
+        ACTION_TYPE TEXT NOT NULL,
+        DETAILS TEXT NOT NULL
+            -- The OIDs that the action affected, as JSON
+    );
+
     -- METADATA_RPT_PARAMETER__REFERENCED stores adhoc parameters that link a row of a base table to [a column in] another table through some form of reference
     -- [Reference] column: N-to-1
     -- [Object] column: 1-to-1
@@ -186,7 +388,7 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
             ON DELETE CASCADE,
         RPT_OID INTEGER NOT NULL REFERENCES METADATA_RPT (OID)
             ON UPDATE CASCADE,
-        RPT_PARAMETER__REFERENCED__OID INTEGER NOT NULL REFERENCES METADATA_RPT_PARAMETER__REFERENCED (OID)
+        RPT_PARAMETER__REFERENCED__OID INTEGER NOT NULL REFERENCES METADATA_RPT_PARAMETER__REFERENCED (RPT_PARAMETER_OID)
             ON UPDATE CASCADE
     );
     
@@ -210,32 +412,466 @@ fn initialize_new_db_at_path<P: AsRef<Path>>(path: P) -> Result<(), error::Error
         SORT_ASCENDING BOOLEAN NOT NULL DEFAULT 0
     );
 
+    -- METADATA_DOCUMENT stores a single row describing the database file as a whole
+    CREATE TABLE METADATA_DOCUMENT (
+        OID INTEGER PRIMARY KEY CHECK (OID = 1),
+        TITLE TEXT,
+        DESCRIPTION TEXT,
+        AUTHOR TEXT,
+        CREATED_AT REAL NOT NULL DEFAULT (julianday('now')),
+        MODIFIED_AT REAL NOT NULL DEFAULT (julianday('now'))
+    );
+    INSERT INTO METADATA_DOCUMENT (OID) VALUES (1);
+
     COMMIT;
     ")?;
     return Ok(());
 }
 
+/// Adds a column to a metadata table of a database created by an older version, if the column does not already exist.
+fn migrate_add_column(conn: &Connection, table_name: &str, column_name: &str, column_def: &str) -> Result<(), error::Error> {
+    let column_exists: bool = conn.query_one(
+        "SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2);", 
+        params![table_name, column_name], 
+        |row| row.get(0)
+    )?;
+    if !column_exists {
+        let alter_cmd = format!("ALTER TABLE {table_name} ADD COLUMN {column_name} {column_def};");
+        conn.execute(&alter_cmd, [])?;
+    }
+    return Ok(());
+}
+
+/// Creates the table storing the last-viewed state of each table, for databases created before view states were saved.
+fn migrate_view_state_table(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute_batch("
+    CREATE TABLE IF NOT EXISTS METADATA_TABLE_VIEW_STATE (
+        TABLE_OID INTEGER PRIMARY KEY REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        PAGE_NUM INTEGER NOT NULL DEFAULT 1,
+        PAGE_SIZE INTEGER NOT NULL DEFAULT 1000,
+        SORT_COLUMN_OID INTEGER,
+        SORT_ASCENDING BOOLEAN NOT NULL DEFAULT 1,
+        FILTER_TEXT TEXT,
+        SCROLL_TOP INTEGER NOT NULL DEFAULT 0
+    );
+    ")?;
+    return Ok(());
+}
+
+/// Creates the table storing comments on cells, for databases created before cells could be commented on.
+fn migrate_cell_comment_table(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute_batch("
+    CREATE TABLE IF NOT EXISTS METADATA_CELL_COMMENT (
+        TABLE_OID INTEGER NOT NULL REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        ROW_OID INTEGER NOT NULL,
+        COLUMN_OID INTEGER NOT NULL REFERENCES METADATA_TABLE_COLUMN (OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        COMMENT TEXT NOT NULL,
+        CREATED_AT REAL NOT NULL DEFAULT (julianday('now')),
+        PRIMARY KEY (TABLE_OID, ROW_OID, COLUMN_OID)
+    );
+    ")?;
+    return Ok(());
+}
+
+/// Creates the table storing tags on rows, for databases created before rows could be tagged.
+fn migrate_row_tag_table(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute_batch("
+    CREATE TABLE IF NOT EXISTS METADATA_ROW_TAG (
+        TABLE_OID INTEGER NOT NULL REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        ROW_OID INTEGER NOT NULL,
+        TAG TEXT NOT NULL,
+        PRIMARY KEY (TABLE_OID, ROW_OID, TAG)
+    );
+    CREATE INDEX IF NOT EXISTS METADATA_ROW_TAG_BY_TAG ON METADATA_ROW_TAG (TABLE_OID, TAG);
+    ")?;
+    return Ok(());
+}
+
+/// Creates the table storing formatting rules, for databases created before rows could be formatted conditionally.
+fn migrate_format_rule_table(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute_batch("
+    CREATE TABLE IF NOT EXISTS METADATA_TABLE_FORMAT_RULE (
+        OID INTEGER PRIMARY KEY,
+        TABLE_OID INTEGER NOT NULL REFERENCES METADATA_TABLE (TYPE_OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        CONDITION_COLUMN_OID INTEGER NOT NULL REFERENCES METADATA_TABLE_COLUMN (OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        OPERATOR TEXT NOT NULL,
+            -- One of the variants of table::FormatRuleOperator
+        VALUE TEXT,
+        CSS_STYLE TEXT NOT NULL
+    );
+    ")?;
+    return Ok(());
+}
+
+/// Creates the table storing the audit log, for databases created before actions were logged.
+fn migrate_audit_log_table(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute_batch("
+    CREATE TABLE IF NOT EXISTS METADATA_AUDIT_LOG (
+        OID INTEGER PRIMARY KEY,
+        LOGGED_AT REAL NOT NULL DEFAULT (julianday('now')),
+        DIRECTION TEXT NOT NULL,
+            -- One of 'execute', 'undo', or 'redo'
+        ACTION_TYPE TEXT NOT NULL,
+        DETAILS TEXT NOT NULL
+            -- The OIDs that the action affected, as JSON
+    );
+    ")?;
+    return Ok(());
+}
+
+/// Adds the list of strings type to databases created before it existed.
+/// Its OID is negative because user-defined types created by older versions are numbered upward from the last primitive type.
+fn migrate_string_list_type(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute("INSERT OR IGNORE INTO METADATA_TYPE (OID, MODE) VALUES (-1, 0);", [])?;
+    return Ok(());
+}
+
+/// Creates the METADATA_DOCUMENT table and its single row in databases created before files could be given a title, description, and author.
+fn migrate_document_table(conn: &Connection) -> Result<(), error::Error> {
+    conn.execute_batch("
+    CREATE TABLE IF NOT EXISTS METADATA_DOCUMENT (
+        OID INTEGER PRIMARY KEY CHECK (OID = 1),
+        TITLE TEXT,
+        DESCRIPTION TEXT,
+        AUTHOR TEXT,
+        CREATED_AT REAL NOT NULL DEFAULT (julianday('now')),
+        MODIFIED_AT REAL NOT NULL DEFAULT (julianday('now'))
+    );
+    INSERT OR IGNORE INTO METADATA_DOCUMENT (OID) VALUES (1);
+    ")?;
+    return Ok(());
+}
+
+/// Adds an ORDERING column to any dropdown value tables created before dropdown values could be reordered.
+fn migrate_dropdown_ordering(conn: &Connection) -> Result<(), error::Error> {
+    // Find every dropdown value table without an ORDERING column
+    let mut column_type_oids: Vec<i64> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            t.OID 
+        FROM METADATA_TYPE t 
+        WHERE t.MODE IN (1, 2) 
+            AND EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'table' AND m.name = 'TABLE' || t.OID)
+            AND NOT EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || t.OID) c WHERE c.name = 'ORDERING');", 
+        [], 
+        &mut |row| {
+            column_type_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Add the column, initially ordering the values by OID
+    for column_type_oid in column_type_oids {
+        let alter_cmd = format!("
+        ALTER TABLE TABLE{column_type_oid} ADD COLUMN ORDERING INTEGER NOT NULL DEFAULT 0;
+        UPDATE TABLE{column_type_oid} SET ORDERING = OID;");
+        conn.execute_batch(&alter_cmd)?;
+    }
+    return Ok(());
+}
+
+/// Adds a unique index on the non-trashed values of any dropdown value tables created before duplicate dropdown values were disallowed.
+/// Tables that already contain duplicates are skipped, so that the duplicates can be cleaned up by the user.
+fn migrate_dropdown_unique_values(conn: &Connection) -> Result<(), error::Error> {
+    // Find every dropdown value table without the index
+    let mut column_type_oids: Vec<i64> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            t.OID 
+        FROM METADATA_TYPE t 
+        WHERE t.MODE IN (1, 2) 
+            AND EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'table' AND m.name = 'TABLE' || t.OID)
+            AND NOT EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'index' AND m.name = 'TABLE' || t.OID || '_UNIQUE_VALUE');", 
+        [], 
+        &mut |row| {
+            column_type_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Add the index to each table without duplicates
+    for column_type_oid in column_type_oids {
+        let has_duplicates_cmd = format!("SELECT EXISTS (SELECT 1 FROM TABLE{column_type_oid} WHERE TRASH = 0 GROUP BY VALUE HAVING COUNT(OID) > 1);");
+        if conn.query_one(&has_duplicates_cmd, [], |row| row.get::<_, bool>(0))? {
+            continue;
+        }
+        let index_cmd = format!("CREATE UNIQUE INDEX TABLE{column_type_oid}_UNIQUE_VALUE ON TABLE{column_type_oid} (VALUE) WHERE TRASH = 0;");
+        conn.execute(&index_cmd, [])?;
+    }
+    return Ok(());
+}
+
+/// Adds the trigger that deletes a child object along with the row that owns it to child object columns created before the trigger existed.
+fn migrate_child_object_triggers(conn: &Connection) -> Result<(), error::Error> {
+    // Find every child object column still stored in its table
+    let mut object_columns: Vec<(i64, i64, i64)> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            c.TABLE_OID,
+            c.OID,
+            c.TYPE_OID 
+        FROM METADATA_TABLE_COLUMN c 
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID 
+        WHERE t.MODE = 4 
+            AND EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || c.TABLE_OID) i WHERE i.name = 'COLUMN' || c.OID);", 
+        [], 
+        &mut |row| {
+            object_columns.push((row.get(0)?, row.get(1)?, row.get(2)?));
+            return Ok(());
+        }
+    )?;
+
+    // Create whichever triggers are missing
+    for (table_oid, column_oid, object_table_oid) in object_columns {
+        table_column::create_child_object_trigger(conn, table_oid, column_oid, object_table_oid)?;
+    }
+    return Ok(());
+}
+
+/// Builds the unique index enforcing the primary key of every table created before primary keys were enforced.
+/// Tables whose rows already share a primary key are skipped with a warning, so that the duplicates can be fixed by the user.
+fn migrate_primary_key_indexes(conn: &Connection) -> Result<(), error::Error> {
+    // Find every table without the index
+    let mut table_oids: Vec<i64> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            t.TYPE_OID 
+        FROM METADATA_TABLE t 
+        WHERE EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'table' AND m.name = 'TABLE' || t.TYPE_OID)
+            AND NOT EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'index' AND m.name = 'TABLE' || t.TYPE_OID || '_PRIMARY_KEY');", 
+        [], 
+        &mut |row| {
+            table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Build the index of each table that has a primary key
+    for table_oid in table_oids {
+        table::update_primary_key_index(conn, table_oid)?;
+    }
+    return Ok(());
+}
+
+/// Rebuilds the surrogate views of child tables created before the views kept the parent row of each row,
+/// without which the rows of any table with a child table column could not be loaded.
+fn migrate_child_surrogate_views(conn: &Connection) -> Result<(), error::Error> {
+    // Find every child table whose surrogate view leaves out the parent row
+    let mut table_oids: Vec<i64> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            t.TYPE_OID 
+        FROM METADATA_TABLE t 
+        WHERE EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || t.TYPE_OID) c WHERE c.name = 'PARENT_OID')
+            AND EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'view' AND m.name = 'TABLE' || t.TYPE_OID || '_SURROGATE')
+            AND NOT EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || t.TYPE_OID || '_SURROGATE') c WHERE c.name = 'PARENT_OID');", 
+        [], 
+        &mut |row| {
+            table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Rebuild the view of each table
+    for table_oid in table_oids {
+        table::update_surrogate_view(conn, table_oid)?;
+    }
+    return Ok(());
+}
+
+/// Recreates the METADATA_RPT_COLUMN__SUBREPORT table of databases where it referenced a column that METADATA_RPT_PARAMETER__REFERENCED does not have,
+/// which caused every delete cascading through METADATA_RPT_PARAMETER__REFERENCED to fail with a foreign key mismatch.
+/// Must be run while foreign keys are not enforced.
+fn migrate_subreport_reference(conn: &Connection) -> Result<(), error::Error> {
+    // Check whether the table still references the missing column
+    let is_mismatched: bool = conn.query_one(
+        "SELECT EXISTS (SELECT 1 FROM pragma_foreign_key_list('METADATA_RPT_COLUMN__SUBREPORT') WHERE \"table\" = 'METADATA_RPT_PARAMETER__REFERENCED' AND \"to\" = 'OID');", 
+        [], 
+        |row| row.get(0)
+    )?;
+    if !is_mismatched {
+        return Ok(());
+    }
+
+    // Copy the table into one with the corrected reference, then swap it in
+    conn.execute_batch("
+    CREATE TABLE METADATA_RPT_COLUMN__SUBREPORT_MIGRATED (
+        RPT_COLUMN_OID INTEGER PRIMARY KEY REFERENCES METADATA_RPT_COLUMN (OID)
+            ON UPDATE CASCADE
+            ON DELETE CASCADE,
+        RPT_OID INTEGER NOT NULL REFERENCES METADATA_RPT (OID)
+            ON UPDATE CASCADE,
+        RPT_PARAMETER__REFERENCED__OID INTEGER NOT NULL REFERENCES METADATA_RPT_PARAMETER__REFERENCED (RPT_PARAMETER_OID)
+            ON UPDATE CASCADE
+    );
+    INSERT INTO METADATA_RPT_COLUMN__SUBREPORT_MIGRATED (RPT_COLUMN_OID, RPT_OID, RPT_PARAMETER__REFERENCED__OID)
+    SELECT RPT_COLUMN_OID, RPT_OID, RPT_PARAMETER__REFERENCED__OID FROM METADATA_RPT_COLUMN__SUBREPORT;
+    DROP TABLE METADATA_RPT_COLUMN__SUBREPORT;
+    ALTER TABLE METADATA_RPT_COLUMN__SUBREPORT_MIGRATED RENAME TO METADATA_RPT_COLUMN__SUBREPORT;")?;
+    return Ok(());
+}
+
+/// Adds the ORDER_KEY column that determines the order in which the rows of a data table are displayed, initially ordering the rows by OID.
+/// An index is created on the column so that pages of rows can be read in order.
+pub fn add_order_key(conn: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    let alter_cmd = format!("
+    ALTER TABLE TABLE{table_oid} ADD COLUMN ORDER_KEY REAL;
+    UPDATE TABLE{table_oid} SET ORDER_KEY = OID;
+    CREATE INDEX IF NOT EXISTS TABLE{table_oid}_ORDER_KEY ON TABLE{table_oid} (ORDER_KEY);");
+    conn.execute_batch(&alter_cmd)?;
+    return Ok(());
+}
+
+/// Adds the columns that store the name and MIME type of each file to file and image columns created before files kept them.
+fn migrate_file_metadata_columns(conn: &Connection) -> Result<(), error::Error> {
+    // Find every file and image column still stored in its table
+    let mut file_columns: Vec<(i64, i64)> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            c.TABLE_OID,
+            c.OID 
+        FROM METADATA_TABLE_COLUMN c 
+        WHERE c.TYPE_OID IN (8, 9) 
+            AND EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || c.TABLE_OID) i WHERE i.name = 'COLUMN' || c.OID);", 
+        [], 
+        &mut |row| {
+            file_columns.push((row.get(0)?, row.get(1)?));
+            return Ok(());
+        }
+    )?;
+
+    // Add whichever of the columns is missing
+    for (table_oid, column_oid) in file_columns {
+        migrate_add_column(conn, &format!("TABLE{table_oid}"), &format!("COLUMN{column_oid}_NAME"), "TEXT")?;
+        migrate_add_column(conn, &format!("TABLE{table_oid}"), &format!("COLUMN{column_oid}_MIME"), "TEXT")?;
+    }
+    return Ok(());
+}
+
+/// Adds an ORDER_KEY column to any data tables created before rows were ordered independently of their OIDs.
+fn migrate_order_keys(conn: &Connection) -> Result<(), error::Error> {
+    // Find every data table without an ORDER_KEY column
+    let mut table_oids: Vec<i64> = Vec::new();
+    query_iterate(conn, 
+        "SELECT 
+            t.OID 
+        FROM METADATA_TYPE t 
+        WHERE t.MODE IN (3, 4, 5) 
+            AND EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'table' AND m.name = 'TABLE' || t.OID)
+            AND NOT EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || t.OID) c WHERE c.name = 'ORDER_KEY');", 
+        [], 
+        &mut |row| {
+            table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Add the column to each table
+    for table_oid in table_oids {
+        add_order_key(conn, table_oid)?;
+    }
+    return Ok(());
+}
+
 /// Closes any previous database connection, and opens a new one.
 pub fn init(path: String) -> Result<(), error::Error> {
     // Initialize the database if it did not already exist
     initialize_new_db_at_path(&path)?;
 
+    // Bring databases created by older versions up to date
+    {
+        let mut conn = Connection::open(&path)?;
+        let trans = conn.transaction()?;
+        migrate_add_column(&trans, "METADATA_TABLE", "MATERIALIZE_DISPLAY_VALUE", "BOOLEAN NOT NULL DEFAULT 0")?;
+        migrate_add_column(&trans, "METADATA_TABLE", "FRIENDLY_VIEW_NAME", "TEXT")?;
+        migrate_add_column(&trans, "METADATA_TABLE", "DELETE_MODE", "TINYINT NOT NULL DEFAULT 0")?;
+        migrate_add_column(&trans, "METADATA_TABLE_COLUMN", "IS_PINNED", "TINYINT NOT NULL DEFAULT 0")?;
+        migrate_add_column(&trans, "METADATA_TABLE_COLUMN", "AUTO_UPDATE", "TINYINT NOT NULL DEFAULT 0")?;
+        migrate_add_column(&trans, "METADATA_TABLE_COLUMN", "DATE_BOUND", "TEXT")?;
+        migrate_add_column(&trans, "METADATA_TABLE_COLUMN", "DECIMAL_PLACES", "INTEGER")?;
+        migrate_view_state_table(&trans)?;
+        migrate_cell_comment_table(&trans)?;
+        migrate_row_tag_table(&trans)?;
+        migrate_format_rule_table(&trans)?;
+        migrate_audit_log_table(&trans)?;
+        migrate_document_table(&trans)?;
+        migrate_string_list_type(&trans)?;
+        migrate_dropdown_ordering(&trans)?;
+        migrate_dropdown_unique_values(&trans)?;
+        migrate_order_keys(&trans)?;
+        migrate_file_metadata_columns(&trans)?;
+        migrate_subreport_reference(&trans)?;
+        migrate_child_object_triggers(&trans)?;
+        migrate_primary_key_indexes(&trans)?;
+        migrate_child_surrogate_views(&trans)?;
+        trans.commit()?;
+    }
+
+    // Forget any display values kept in memory from the previous database
+    table::clear_surrogate_cache();
+
     // Record the path to static variable
     let mut database_path = DATABASE_PATH.lock().unwrap();
     *database_path = Some(path);
     return Ok(());
 }
 
+/// Converts an SQLite error caused by the database file being inaccessible into a FileUnavailable error.
+fn map_unavailable_error(e: rusqlite::Error, path: &String) -> error::Error {
+    if let rusqlite::Error::SqliteFailure(ref err, _) = e {
+        match err.code {
+            rusqlite::ErrorCode::CannotOpen
+            | rusqlite::ErrorCode::SystemIoFailure
+            | rusqlite::ErrorCode::NotADatabase => {
+                return error::Error::FileUnavailable(path.clone());
+            },
+            _ => {}
+        }
+    }
+    return error::Error::RusqliteError(e);
+}
+
+/// Points the application at the new location of the database file, after it was moved while open.
+pub fn relocate(path: String) -> Result<(), error::Error> {
+    if !Path::new(&path).exists() {
+        return Err(error::Error::FileUnavailable(path));
+    }
+    return init(path);
+}
+
 /// Opens a connection to the database.
+/// If the file has been moved or deleted since it was opened, returns a FileUnavailable error rather than creating a new file.
 pub fn open() -> Result<Connection, error::Error> {
     let database_path = DATABASE_PATH.lock().unwrap();
     match *database_path {
         Some(ref path) => {
-            let conn = Connection::open(path)?;
+            if !Path::new(path).exists() {
+                return Err(error::Error::FileUnavailable(path.clone()));
+            }
+            let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+                .map_err(|e| map_unavailable_error(e, path))?;
             conn.execute_batch("
             PRAGMA foreign_keys = ON;
             PRAGMA journal_mode = WAL;
-            ")?;
+            ").map_err(|e| map_unavailable_error(e, path))?;
+
+            // Count every commit made by this process, so that they are not mistaken for changes made by another process
+            conn.commit_hook(Some(|| {
+                LOCAL_COMMIT_COUNT.fetch_add(1, Ordering::SeqCst);
+                return false;
+            }))?;
             return Ok(conn);
         },
         None => {
@@ -245,7 +881,7 @@ pub fn open() -> Result<Connection, error::Error> {
 }
 
 /// Convenience method to execute a query that returns multiple rows, then execute a function for each row.
-pub fn query_iterate<P: Params, F: FnMut(&Row<'_>) -> Result<(), error::Error>>(trans: &Transaction, sql: &str, p: P, f: &mut F) -> Result<(), error::Error> {
+pub fn query_iterate<P: Params, F: FnMut(&Row<'_>) -> Result<(), error::Error>>(trans: &Connection, sql: &str, p: P, f: &mut F) -> Result<(), error::Error> {
     // Prepare a statement
     let mut stmt = match trans.prepare(sql) {
         Ok(s) => s,
@@ -259,7 +895,1769 @@ pub fn query_iterate<P: Params, F: FnMut(&Row<'_>) -> Result<(), error::Error>>(
             Some(r) => r,
             None => { break; }
         };
-        f(row);
+        f(row)?;
+    }
+    return Ok(());
+}
+
+/// Convenience method to execute a query that returns multiple rows, then execute a function for each row.
+/// The prepared statement is cached on the connection, so this should be used for queries with constant SQL that are run many times on the same connection.
+pub fn query_iterate_cached<P: Params, F: FnMut(&Row<'_>) -> Result<(), error::Error>>(trans: &Connection, sql: &str, p: P, f: &mut F) -> Result<(), error::Error> {
+    // Prepare a statement, or reuse one that has already been prepared
+    let mut stmt = trans.prepare_cached(sql)?;
+
+    // Execute the statement to query rows
+    let mut rows = stmt.query(p)?;
+    loop {
+        let row = match rows.next()? {
+            Some(r) => r,
+            None => { break; }
+        };
+        f(row)?;
     }
     return Ok(());
-}
\ No newline at end of file
+}
+
+
+/// The maximum number of columns listed in the symbol table.
+const SYMBOL_TABLE_MAX_COLUMNS: i64 = 10000;
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A column as listed in the symbol table.
+pub struct SymbolColumn {
+    oid: i64,
+    name: String,
+    column_type: data_type::MetadataColumnType
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A table and its columns as listed in the symbol table.
+pub struct SymbolTableEntry {
+    oid: i64,
+    name: String,
+    columns: Vec<SymbolColumn>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// Every non-trashed table and column name in the database, used for autocompletion and linting of formulas.
+pub struct SymbolTable {
+    tables: Vec<SymbolTableEntry>,
+    is_truncated: bool
+}
+
+/// Gets the names and types of all non-trashed tables and their columns.
+pub fn symbol_table() -> Result<SymbolTable, error::Error> {
+    let action = begin_readonly_db_action()?;
+
+    let mut tables: Vec<SymbolTableEntry> = Vec::new();
+    let mut column_count: i64 = 0;
+    query_iterate(&action, 
+        "SELECT
+            tbl.TYPE_OID AS TABLE_OID,
+            tbl.NAME AS TABLE_NAME,
+            c.OID AS COLUMN_OID,
+            c.NAME AS COLUMN_NAME,
+            c.TYPE_OID,
+            typ.MODE
+        FROM METADATA_TABLE tbl
+        LEFT JOIN METADATA_TABLE_COLUMN c ON c.TABLE_OID = tbl.TYPE_OID AND c.TRASH = 0
+        LEFT JOIN METADATA_TYPE typ ON typ.OID = c.TYPE_OID
+        WHERE tbl.TRASH = 0
+        ORDER BY tbl.NAME, tbl.TYPE_OID, c.COLUMN_ORDERING
+        LIMIT ?1;", 
+        params![SYMBOL_TABLE_MAX_COLUMNS + 1], 
+        &mut |row| {
+            column_count += 1;
+            if column_count > SYMBOL_TABLE_MAX_COLUMNS {
+                return Ok(());
+            }
+
+            // Start a new entry whenever the table changes
+            let table_oid: i64 = row.get("TABLE_OID")?;
+            let is_new_table: bool = match tables.last() {
+                Some(entry) => entry.oid != table_oid,
+                None => true
+            };
+            if is_new_table {
+                tables.push(SymbolTableEntry { 
+                    oid: table_oid, 
+                    name: row.get("TABLE_NAME")?, 
+                    columns: Vec::new() 
+                });
+            }
+
+            // Add the column to the table's entry, if the table has any columns
+            match row.get::<_, Option<i64>>("COLUMN_OID")? {
+                Some(column_oid) => {
+                    let entry = tables.last_mut().unwrap();
+                    entry.columns.push(SymbolColumn { 
+                        oid: column_oid, 
+                        name: row.get("COLUMN_NAME")?, 
+                        column_type: data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?)
+                    });
+                },
+                None => {}
+            }
+            return Ok(());
+        }
+    )?;
+    return Ok(SymbolTable { 
+        tables, 
+        is_truncated: column_count > SYMBOL_TABLE_MAX_COLUMNS 
+    });
+}
+
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A user-defined type whose metadata does not line up with the tables that back it.
+pub struct OrphanedType {
+    type_oid: i64,
+    mode: Option<i64>,
+    has_backing_table: bool,
+    description: String
+}
+
+/// Cross-checks the type metadata against the table metadata, the column metadata, and the tables that actually exist in the database.
+fn find_orphaned_types_in(trans: &Connection) -> Result<Vec<OrphanedType>, error::Error> {
+    // Get the OID of every table that backs a type
+    let mut backing_table_oids: HashSet<i64> = HashSet::new();
+    query_iterate(trans, 
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name GLOB 'TABLE[0-9]*';", 
+        [], 
+        &mut |row| {
+            let table_name: String = row.get(0)?;
+            match table_name["TABLE".len()..].parse::<i64>() {
+                Ok(oid) => {
+                    backing_table_oids.insert(oid);
+                },
+                Err(_) => {
+                    // Auxiliary tables (e.g. TABLE{oid}_MULTISELECT) are checked alongside the table they belong to
+                }
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Check each user-defined type against its metadata and backing table
+    let mut orphaned_types: Vec<OrphanedType> = Vec::new();
+    query_iterate(trans, 
+        "SELECT
+            t.OID,
+            t.MODE,
+            tbl.TYPE_OID IS NOT NULL AS HAS_TABLE_METADATA,
+            EXISTS (SELECT 1 FROM METADATA_TABLE_COLUMN c WHERE c.TYPE_OID = t.OID) AS IS_USED_BY_COLUMN
+        FROM METADATA_TYPE t
+        LEFT JOIN METADATA_TABLE tbl ON tbl.TYPE_OID = t.OID
+        WHERE t.MODE <> 0;", 
+        [], 
+        &mut |row| {
+            let type_oid: i64 = row.get("OID")?;
+            let mode: i64 = row.get("MODE")?;
+            let has_table_metadata: bool = row.get("HAS_TABLE_METADATA")?;
+            let is_used_by_column: bool = row.get("IS_USED_BY_COLUMN")?;
+            let has_backing_table: bool = backing_table_oids.remove(&type_oid);
+
+            let description: Option<&str> = if !has_backing_table {
+                Some("The type has no table storing its values.")
+            } else {
+                match mode {
+                    1 | 2 if !is_used_by_column => Some("The dropdown is not used by any column."),
+                    3 | 4 | 5 if !has_table_metadata => Some("The table has no metadata."),
+                    5 if !is_used_by_column => Some("The child table is not attached to any column."),
+                    _ => None
+                }
+            };
+            match description {
+                Some(description) => {
+                    orphaned_types.push(OrphanedType { 
+                        type_oid, 
+                        mode: Some(mode), 
+                        has_backing_table, 
+                        description: String::from(description) 
+                    });
+                },
+                None => {}
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Any remaining backing tables have no type metadata at all
+    let mut untyped_table_oids: Vec<i64> = backing_table_oids.into_iter().collect();
+    untyped_table_oids.sort();
+    for type_oid in untyped_table_oids {
+        orphaned_types.push(OrphanedType { 
+            type_oid, 
+            mode: None, 
+            has_backing_table: true, 
+            description: String::from("The table has no type metadata.") 
+        });
+    }
+    return Ok(orphaned_types);
+}
+
+/// Finds every user-defined type whose metadata and backing tables are out of sync.
+pub fn find_orphaned_types() -> Result<Vec<OrphanedType>, error::Error> {
+    let conn = open()?;
+    return find_orphaned_types_in(&conn);
+}
+
+/// Removes the orphaned types that the user confirmed, along with any tables or views that back them, as part of an ongoing action.
+/// Types that are no longer orphaned are left alone.
+/// Returns the list of types that were removed.
+fn cleanup_orphaned_types_in(trans: &Connection, confirmed_type_oids: &Vec<i64>) -> Result<Vec<OrphanedType>, error::Error> {
+    let mut orphaned_types = find_orphaned_types_in(trans)?;
+    orphaned_types.retain(|orphaned_type| confirmed_type_oids.contains(&orphaned_type.type_oid));
+    for orphaned_type in orphaned_types.iter() {
+        let type_oid = orphaned_type.type_oid;
+
+        // Drop any views and tables backing the type
+        let drop_cmd = format!("
+        DROP VIEW IF EXISTS TABLE{type_oid}_FLAT;
+        DROP VIEW IF EXISTS TABLE{type_oid}_SURROGATE;
+        DROP TABLE IF EXISTS TABLE{type_oid}_MULTISELECT;
+        DROP TABLE IF EXISTS TABLE{type_oid};");
+        trans.execute_batch(&drop_cmd)?;
+
+        // Delete the type from the metadata
+        trans.execute(
+            "DELETE FROM METADATA_TYPE WHERE OID = ?1;", 
+            params![type_oid]
+        )?;
+    }
+    return Ok(orphaned_types);
+}
+
+
+/// Finds the tables storing dropdown values, and the relationship tables of multi-select dropdowns, that no column uses.
+/// These can be left behind when a column's type is changed away from a dropdown by an interrupted edit or an older version.
+/// Returns the OID in the name of each such table.
+pub fn find_unreferenced_value_tables() -> Result<Vec<i64>, error::Error> {
+    let conn = open()?;
+    return Ok(find_unreferenced_value_tables_in(&conn)?
+        .into_iter()
+        .map(|(type_oid, _)| type_oid)
+        .collect());
+}
+
+/// Finds the dropdown value tables that no column uses, as part of an ongoing action.
+/// Returns the OID in the name of each table, and whether it is the relationship table of a multi-select dropdown.
+fn find_unreferenced_value_tables_in(trans: &Connection) -> Result<Vec<(i64, bool)>, error::Error> {
+    // Get the mode of each dropdown type that is used by a column
+    let mut used_dropdown_modes: HashMap<i64, i64> = HashMap::new();
+    query_iterate(trans, 
+        "SELECT t.OID, t.MODE 
+        FROM METADATA_TYPE t 
+        WHERE t.MODE IN (1, 2) AND EXISTS (SELECT 1 FROM METADATA_TABLE_COLUMN c WHERE c.TYPE_OID = t.OID);", 
+        [], 
+        &mut |row| {
+            used_dropdown_modes.insert(row.get(0)?, row.get(1)?);
+            return Ok(());
+        }
+    )?;
+
+    // Check each table shaped like a dropdown's values or relationships against the types that are used
+    // Relationship tables are listed first, so that they are dropped before the value tables they reference
+    let mut unreferenced_tables: Vec<(i64, bool)> = Vec::new();
+    query_iterate(trans, 
+        "SELECT m.name 
+        FROM sqlite_master m 
+        WHERE m.type = 'table' AND m.name GLOB 'TABLE[0-9]*' 
+            AND (m.name GLOB '*_MULTISELECT' OR EXISTS (SELECT 1 FROM pragma_table_info(m.name) p WHERE p.name = 'ORDERING'))
+        ORDER BY m.name GLOB '*_MULTISELECT' DESC, m.name;", 
+        [], 
+        &mut |row| {
+            let table_name: String = row.get(0)?;
+            let is_multiselect: bool = table_name.ends_with("_MULTISELECT");
+            match table_name["TABLE".len()..].trim_end_matches("_MULTISELECT").parse::<i64>() {
+                Ok(type_oid) => {
+                    let is_used: bool = match (used_dropdown_modes.get(&type_oid), is_multiselect) {
+                        (Some(2), true) => true,
+                        (Some(_), false) => true,
+                        _ => false
+                    };
+                    if !is_used {
+                        unreferenced_tables.push((type_oid, is_multiselect));
+                    }
+                },
+                Err(_) => {}
+            }
+            return Ok(());
+        }
+    )?;
+    return Ok(unreferenced_tables);
+}
+
+/// Drops the dropdown value tables that the user confirmed and that no column uses, along with the metadata of their types, as part of an ongoing action.
+/// Returns the OID in the name of each table that was dropped.
+fn cleanup_unreferenced_value_tables_in(trans: &Connection, confirmed_table_oids: &Vec<i64>) -> Result<Vec<i64>, error::Error> {
+    let mut dropped_table_oids: Vec<i64> = Vec::new();
+    for (type_oid, is_multiselect) in find_unreferenced_value_tables_in(trans)? {
+        if !confirmed_table_oids.contains(&type_oid) {
+            continue;
+        }
+        if is_multiselect {
+            let drop_cmd = format!("DROP TABLE TABLE{type_oid}_MULTISELECT;");
+            trans.execute(&drop_cmd, [])?;
+        } else {
+            let drop_cmd = format!("DROP TABLE TABLE{type_oid};");
+            trans.execute(&drop_cmd, [])?;
+
+            // Delete the type from the metadata, unless some other kind of type has taken its OID
+            trans.execute(
+                "DELETE FROM METADATA_TYPE WHERE OID = ?1 AND MODE IN (1, 2);", 
+                params![type_oid]
+            )?;
+        }
+        if !dropped_table_oids.contains(&type_oid) {
+            dropped_table_oids.push(type_oid);
+        }
+    }
+    return Ok(dropped_table_oids);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A summary of the problems across every table of the database.
+pub struct HealthReport {
+    pub total_row_count: i64,
+    pub invalid_row_count: i64,
+    pub tables_without_primary_key_count: i64,
+    pub orphaned_reference_count: i64,
+    pub orphaned_type_count: i64,
+    /// A score from 0 to 100, where 100 means no problems were found.
+    pub score: i64
+}
+
+/// Counts the rows of a table that fail a nullability or uniqueness check, as part of an ongoing action.
+fn count_invalid_rows_in(trans: &Connection, table_oid: i64) -> Result<i64, error::Error> {
+    // Build a condition that is true for any row failing a check
+    let mut conditions: Vec<String> = Vec::new();
+    query_iterate(trans, 
+        "SELECT 
+            c.OID,
+            c.IS_NULLABLE,
+            c.IS_UNIQUE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0 AND t.MODE IN (0, 1, 3, 4);", 
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            if !row.get::<_, bool>("IS_NULLABLE")? {
+                conditions.push(format!("t.COLUMN{column_oid} IS NULL"));
+            }
+            if row.get::<_, bool>("IS_UNIQUE")? {
+                conditions.push(format!("EXISTS (SELECT 1 FROM TABLE{table_oid} u WHERE u.TRASH = 0 AND u.OID <> t.OID AND u.COLUMN{column_oid} = t.COLUMN{column_oid})"));
+            }
+            return Ok(());
+        }
+    )?;
+    if conditions.len() == 0 {
+        return Ok(0);
+    }
+
+    // Count the rows meeting the condition
+    let count_cmd = format!("SELECT COUNT(*) FROM TABLE{table_oid} t WHERE t.TRASH = 0 AND ({});", conditions.join(" OR "));
+    return Ok(trans.query_one(&count_cmd, [], |row| row.get(0))?);
+}
+
+/// Checks every table of the database and summarizes the problems found into a single report.
+/// The score is the percentage of rows without problems, less 5 points for each table without a primary key or orphaned type.
+pub fn health_report() -> Result<HealthReport, error::Error> {
+    let conn = open()?;
+
+    // Get every table that holds data, skipping any whose backing table is missing
+    let mut table_oids: Vec<i64> = Vec::new();
+    query_iterate(&conn, 
+        "SELECT TYPE_OID 
+        FROM METADATA_TABLE 
+        WHERE TRASH = 0 AND EXISTS (SELECT 1 FROM sqlite_master m WHERE m.type = 'table' AND m.name = 'TABLE' || TYPE_OID)
+        ORDER BY TYPE_OID;", 
+        [], 
+        &mut |row| {
+            table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Count the rows and problems of each table
+    let mut total_row_count: i64 = 0;
+    let mut invalid_row_count: i64 = 0;
+    let mut orphaned_reference_count: i64 = 0;
+    for table_oid in table_oids {
+        total_row_count += table::count_rows_in(&conn, table_oid)?;
+        invalid_row_count += count_invalid_rows_in(&conn, table_oid)?;
+        orphaned_reference_count += table_data::integrity_report_in(&conn, table_oid)?.len() as i64;
+    }
+    let tables_without_primary_key_count = table::tables_without_primary_key_in(&conn)?.len() as i64;
+    let orphaned_type_count = find_orphaned_types_in(&conn)?.len() as i64;
+
+    // Compute the score
+    let problem_row_count = (invalid_row_count + orphaned_reference_count).min(total_row_count);
+    let row_score = if total_row_count > 0 { 100 * (total_row_count - problem_row_count) / total_row_count } else { 100 };
+    let score = (row_score - 5 * (tables_without_primary_key_count + orphaned_type_count)).clamp(0, 100);
+    return Ok(HealthReport { 
+        total_row_count, 
+        invalid_row_count, 
+        tables_without_primary_key_count, 
+        orphaned_reference_count, 
+        orphaned_type_count, 
+        score 
+    });
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// The outcome of repairing the database.
+pub struct RepairReport {
+    removed_types: Vec<OrphanedType>,
+    dropped_value_table_oids: Vec<i64>,
+    rebuilt_surrogate_view_count: i64,
+    column_mismatches: Vec<table::ColumnMismatch>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all="camelCase")]
+/// The orphaned types and unused dropdown value tables that the user agreed to remove, as listed by a preview of the repair.
+pub struct RepairConfirmation {
+    removed_type_oids: Vec<i64>,
+    dropped_value_table_oids: Vec<i64>
+}
+
+/// Reports what repairing the database would do, without changing anything.
+/// The types and tables listed in the report are only removed once the user confirms them through repair.
+pub fn preview_repair() -> Result<RepairReport, error::Error> {
+    let action = begin_db_action()?;
+
+    // Confirm everything that the repair would remove, then discard the repair
+    let confirmation = RepairConfirmation {
+        removed_type_oids: find_orphaned_types_in(&action)?.iter().map(|t| t.type_oid).collect(),
+        dropped_value_table_oids: find_unreferenced_value_tables_in(&action)?.iter().map(|(type_oid, _)| *type_oid).collect()
+    };
+    return action.rollback(repair_in(&action, &confirmation));
+}
+
+/// Repairs the database by removing the orphaned types and unused dropdown value tables that the user confirmed and rebuilding every surrogate view, then reports any columns whose metadata does not match the physical tables.
+pub fn repair(confirmation: &RepairConfirmation) -> Result<RepairReport, error::Error> {
+    let action = begin_db_action()?;
+    return action.finish(repair_in(&action, confirmation));
+}
+
+/// Repairs the database, as part of an ongoing action.
+fn repair_in(trans: &Connection, confirmation: &RepairConfirmation) -> Result<RepairReport, error::Error> {
+    // Remove the confirmed orphaned types
+    let removed_types = cleanup_orphaned_types_in(trans, &confirmation.removed_type_oids)?;
+
+    // Drop the confirmed dropdown value tables left behind by columns that no longer use them
+    let dropped_value_table_oids = cleanup_unreferenced_value_tables_in(trans, &confirmation.dropped_value_table_oids)?;
+
+    // Rebuild the surrogate views of every remaining table
+    let rebuilt_surrogate_view_count = table::rebuild_all_surrogate_views_in(trans)?;
+
+    // Check the columns of every remaining table
+    let mut table_oids: Vec<i64> = Vec::new();
+    query_iterate(trans, 
+        "SELECT TYPE_OID FROM METADATA_TABLE ORDER BY TYPE_OID;", 
+        [], 
+        &mut |row| {
+            table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+    let mut column_mismatches: Vec<table::ColumnMismatch> = Vec::new();
+    for table_oid in table_oids {
+        column_mismatches.append(&mut table::verify_columns_in(trans, table_oid)?);
+    }
+    return Ok(RepairReport { 
+        removed_types, 
+        dropped_value_table_oids,
+        rebuilt_surrogate_view_count,
+        column_mismatches 
+    });
+}
+
+
+/// The maximum number of rows returned by an ad-hoc query.
+const READONLY_QUERY_MAX_ROWS: usize = 10000;
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// The result of an ad-hoc query, with every value converted to text.
+pub struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<Option<String>>>,
+    is_truncated: bool
+}
+
+/// Runs an ad-hoc SELECT statement against a read-only connection to the database.
+/// 
+/// User tables are named TABLE{oid} and their columns are named COLUMN{oid}, where the OIDs can be looked up in METADATA_TABLE and METADATA_TABLE_COLUMN respectively.
+/// Each table also has a TABLE{oid}_SURROGATE view, which maps the OID of each row to the row's display value.
+pub fn run_readonly_query(sql: &str) -> Result<QueryResult, error::Error> {
+    // Reject anything that does not start with SELECT or WITH
+    let leading_keyword: String = sql.trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if leading_keyword != "SELECT" && leading_keyword != "WITH" {
+        return Err(error::Error::AdhocError("Only SELECT statements can be run."));
+    }
+
+    // Open a connection that cannot modify the database
+    let conn = {
+        let database_path = DATABASE_PATH.lock().unwrap();
+        match *database_path {
+            Some(ref path) => Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?,
+            None => {
+                return Err(error::Error::AdhocError("No file is open!"));
+            }
+        }
+    };
+    conn.execute_batch("PRAGMA query_only = ON;")?;
+
+    // Double-check with SQLite that the statement does not write anything
+    let mut stmt = conn.prepare(sql)?;
+    if !stmt.readonly() {
+        return Err(error::Error::AdhocError("Only SELECT statements can be run."));
+    }
+
+    // Run the query, converting each value to text
+    let columns: Vec<String> = stmt.column_names().iter().map(|name| String::from(*name)).collect();
+    let column_count = columns.len();
+    let mut result_rows: Vec<Vec<Option<String>>> = Vec::new();
+    let mut is_truncated = false;
+    let mut rows = stmt.query([])?;
+    loop {
+        let row = match rows.next()? {
+            Some(r) => r,
+            None => { break; }
+        };
+        if result_rows.len() >= READONLY_QUERY_MAX_ROWS {
+            is_truncated = true;
+            break;
+        }
+
+        let mut result_row: Vec<Option<String>> = Vec::with_capacity(column_count);
+        for i in 0..column_count {
+            result_row.push(match row.get_ref(i)? {
+                ValueRef::Null => None,
+                ValueRef::Integer(n) => Some(n.to_string()),
+                ValueRef::Real(n) => Some(n.to_string()),
+                ValueRef::Text(s) => Some(String::from_utf8_lossy(s).into_owned()),
+                ValueRef::Blob(b) => Some(format!("<{} bytes>", b.len()))
+            });
+        }
+        result_rows.push(result_row);
+    }
+    return Ok(QueryResult { 
+        columns, 
+        rows: result_rows, 
+        is_truncated 
+    });
+}
+
+
+/// Converts a user-defined name into a name for a friendly view, which does not collide with any other table or view in the database.
+fn get_unique_friendly_name(trans: &Connection, name: &str, fallback_name: String) -> Result<String, error::Error> {
+    // Strip any characters that cannot be displayed
+    let sanitized_name: String = name.chars().filter(|c| !c.is_control()).collect::<String>().trim().to_string();
+    let base_name = if sanitized_name.is_empty() || sanitized_name.to_ascii_uppercase().starts_with("SQLITE_") { fallback_name } else { sanitized_name };
+
+    // Append a number to the name until it is unique
+    let mut candidate_name = base_name.clone();
+    let mut suffix: i64 = 1;
+    while trans.query_one(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = ?1 COLLATE NOCASE);", 
+        params![candidate_name], 
+        |row| row.get::<_, bool>(0)
+    )? {
+        suffix += 1;
+        candidate_name = format!("{base_name} ({suffix})");
+    }
+    return Ok(candidate_name);
+}
+
+/// Quotes a name so that it can be used as an SQL identifier.
+fn quote_identifier(name: &str) -> String {
+    return format!("\"{}\"", name.replace('"', "\"\""));
+}
+
+/// Drops the friendly view of a table, if it has one.
+pub fn drop_friendly_view(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    match trans.query_one(
+        "SELECT FRIENDLY_VIEW_NAME FROM METADATA_TABLE WHERE TYPE_OID = ?1;", 
+        params![table_oid], 
+        |row| row.get::<_, Option<String>>(0)
+    ).optional()?.flatten() {
+        Some(view_name) => {
+            let drop_cmd = format!("DROP VIEW IF EXISTS {};", quote_identifier(&view_name));
+            trans.execute(&drop_cmd, [])?;
+            trans.execute("UPDATE METADATA_TABLE SET FRIENDLY_VIEW_NAME = NULL WHERE TYPE_OID = ?1;", params![table_oid])?;
+        },
+        None => {}
+    }
+    return Ok(());
+}
+
+/// Creates or recreates the friendly view of a table.
+/// The friendly view is named after the table, and each of its columns is named after a column of the table, so that ad-hoc SQL can refer to them by human-readable names.
+pub fn update_friendly_view(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    drop_friendly_view(trans, table_oid)?;
+
+    // Trashed tables do not get friendly views
+    let table_name: String = match trans.query_one(
+        "SELECT NAME FROM METADATA_TABLE WHERE TYPE_OID = ?1 AND TRASH = 0;", 
+        params![table_oid], 
+        |row| row.get::<_, String>(0)
+    ).optional()? {
+        Some(name) => name,
+        None => {
+            return Ok(());
+        }
+    };
+
+    // Build up the list of columns, each aliased to its human-readable name
+    let mut used_column_names: HashSet<String> = HashSet::new();
+    used_column_names.insert(String::from("OID"));
+    let mut select_cols: Vec<String> = vec![String::from("t.OID AS \"OID\"")];
+    query_iterate(trans, 
+        "SELECT 
+            c.OID,
+            c.NAME,
+            c.TYPE_OID,
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0
+        ORDER BY c.COLUMN_ORDERING, c.OID;", 
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            let column_type = data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?);
+            let column_expr: String = match column_type {
+                data_type::MetadataColumnType::Primitive(data_type::Primitive::Date) => format!("DATE(t.COLUMN{column_oid}, 'julianday')"),
+                data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => format!("STRFTIME('%FT%TZ', t.COLUMN{column_oid}, 'julianday')"),
+                data_type::MetadataColumnType::Primitive(_) => format!("t.COLUMN{column_oid}"),
+                data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => format!("(SELECT v.VALUE FROM TABLE{column_type_oid} v WHERE v.OID = t.COLUMN{column_oid})"),
+                data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => format!("(SELECT GROUP_CONCAT(b.VALUE) FROM TABLE{column_type_oid}_MULTISELECT a INNER JOIN TABLE{column_type_oid} b ON b.OID = a.VALUE_OID WHERE a.ROW_OID = t.OID)"),
+                data_type::MetadataColumnType::Reference(referenced_table_oid)
+                | data_type::MetadataColumnType::ChildObject(referenced_table_oid) => format!("(SELECT s.DISPLAY_VALUE FROM TABLE{referenced_table_oid}_SURROGATE s WHERE s.OID = t.COLUMN{column_oid})"),
+                data_type::MetadataColumnType::ChildTable(_) => {
+                    // Child tables get friendly views of their own
+                    return Ok(());
+                }
+            };
+
+            // Make sure that the column name is unique within the view
+            let column_name: String = row.get("NAME")?;
+            let column_name: String = column_name.chars().filter(|c| !c.is_control()).collect::<String>().trim().to_string();
+            let base_column_name = if column_name.is_empty() { format!("Column {column_oid}") } else { column_name };
+            let mut candidate_column_name = base_column_name.clone();
+            let mut suffix: i64 = 1;
+            while used_column_names.contains(&candidate_column_name.to_uppercase()) {
+                suffix += 1;
+                candidate_column_name = format!("{base_column_name} ({suffix})");
+            }
+            used_column_names.insert(candidate_column_name.to_uppercase());
+
+            select_cols.push(format!("{column_expr} AS {}", quote_identifier(&candidate_column_name)));
+            return Ok(());
+        }
+    )?;
+
+    // Create the view
+    let view_name = get_unique_friendly_name(trans, &table_name, format!("Table {table_oid}"))?;
+    let create_cmd = format!("CREATE VIEW {} AS SELECT {} FROM TABLE{table_oid} t WHERE t.TRASH = 0;", quote_identifier(&view_name), select_cols.join(", "));
+    trans.execute(&create_cmd, [])?;
+    trans.execute("UPDATE METADATA_TABLE SET FRIENDLY_VIEW_NAME = ?1 WHERE TYPE_OID = ?2;", params![view_name, table_oid])?;
+    return Ok(());
+}
+
+/// Creates or recreates the friendly view of every table.
+pub fn create_friendly_views() -> Result<(), error::Error> {
+    let action = begin_db_action()?;
+    return action.finish(create_friendly_views_in(&action));
+}
+
+/// Creates or recreates the friendly view of every table, as part of an ongoing action.
+fn create_friendly_views_in(trans: &Connection) -> Result<(), error::Error> {
+    let mut table_oids: Vec<i64> = Vec::new();
+    query_iterate(trans, 
+        "SELECT TYPE_OID FROM METADATA_TABLE ORDER BY TYPE_OID;", 
+        [], 
+        &mut |row| {
+            table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Drop every view first, so that views are named in order of OID regardless of which existed before
+    for table_oid in table_oids.iter() {
+        drop_friendly_view(trans, *table_oid)?;
+    }
+    for table_oid in table_oids.iter() {
+        update_friendly_view(trans, *table_oid)?;
+    }
+    return Ok(());
+}
+
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+/// A description of the structure of the database, excluding the data itself.
+pub struct Schema {
+    pub tables: Vec<SchemaTable>,
+    pub reports: Vec<SchemaReport>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+/// A description of a table, object type, or child table.
+pub struct SchemaTable {
+    pub oid: i64,
+    pub name: String,
+    pub mode: i64,
+    pub parent_table_oid: Option<i64>,
+    pub master_table_oids: Vec<i64>,
+    pub columns: Vec<SchemaColumn>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+/// A description of a column of a table.
+pub struct SchemaColumn {
+    pub oid: i64,
+    pub name: String,
+    pub column_type: data_type::MetadataColumnType,
+    pub column_style: String,
+    pub column_ordering: i64,
+    pub is_nullable: bool,
+    pub is_unique: bool,
+    pub is_primary_key: bool,
+    pub default_value: Option<serde_json::Value>,
+    pub dropdown_values: Option<Vec<String>>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+/// A description of a report.
+pub struct SchemaReport {
+    pub oid: i64,
+    pub name: String,
+    pub base_table_oid: i64,
+    pub columns: Vec<SchemaReportColumn>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+/// A description of a column of a report.
+pub struct SchemaReportColumn {
+    pub oid: i64,
+    pub name: String,
+    pub column_style: String,
+    pub column_ordering: i64,
+    pub formula: Option<String>
+}
+
+/// Converts a value of a column with no fixed type into a JSON value.
+fn value_to_json(value: ValueRef<'_>) -> Option<serde_json::Value> {
+    return match value {
+        ValueRef::Null => None,
+        ValueRef::Integer(n) => Some(serde_json::Value::from(n)),
+        ValueRef::Real(n) => Some(serde_json::Value::from(n)),
+        ValueRef::Text(s) => Some(serde_json::Value::from(String::from_utf8_lossy(s).into_owned())),
+        ValueRef::Blob(_) => None
+    };
+}
+
+/// Reads the structure of every table and report in the database that is not in the trash, ordered by OID.
+fn get_schema_in(trans: &Connection) -> Result<Schema, error::Error> {
+    // Get the tables
+    let mut tables: Vec<SchemaTable> = Vec::new();
+    query_iterate(trans, 
+        "SELECT 
+            tbl.TYPE_OID,
+            tbl.NAME,
+            typ.MODE,
+            tbl.PARENT_TABLE_OID
+        FROM METADATA_TABLE tbl
+        INNER JOIN METADATA_TYPE typ ON typ.OID = tbl.TYPE_OID
+        WHERE tbl.TRASH = 0
+        ORDER BY tbl.TYPE_OID, tbl.NAME;", 
+        [], 
+        &mut |row| {
+            tables.push(SchemaTable {
+                oid: row.get("TYPE_OID")?,
+                name: row.get("NAME")?,
+                mode: row.get("MODE")?,
+                parent_table_oid: row.get("PARENT_TABLE_OID")?,
+                master_table_oids: Vec::new(),
+                columns: Vec::new()
+            });
+            return Ok(());
+        }
+    )?;
+
+    for table in tables.iter_mut() {
+        // Get the tables that the table inherits from
+        query_iterate(trans, 
+            "SELECT MASTER_TABLE_OID FROM METADATA_TABLE_INHERITANCE WHERE INHERITOR_TABLE_OID = ?1 AND TRASH = 0 ORDER BY MASTER_TABLE_OID;", 
+            params![table.oid], 
+            &mut |row| {
+                table.master_table_oids.push(row.get(0)?);
+                return Ok(());
+            }
+        )?;
+
+        // Get the columns of the table
+        query_iterate(trans, 
+            "SELECT 
+                c.OID,
+                c.NAME,
+                c.TYPE_OID,
+                t.MODE,
+                c.COLUMN_CSS_STYLE,
+                c.COLUMN_ORDERING,
+                c.IS_NULLABLE,
+                c.IS_UNIQUE,
+                c.IS_PRIMARY_KEY,
+                c.DEFAULT_VALUE
+            FROM METADATA_TABLE_COLUMN c
+            INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+            WHERE c.TABLE_OID = ?1 AND c.TRASH = 0
+            ORDER BY c.OID, c.NAME;", 
+            params![table.oid], 
+            &mut |row| {
+                table.columns.push(SchemaColumn {
+                    oid: row.get("OID")?,
+                    name: row.get("NAME")?,
+                    column_type: data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?),
+                    column_style: row.get::<_, Option<String>>("COLUMN_CSS_STYLE")?.unwrap_or_default(),
+                    column_ordering: row.get("COLUMN_ORDERING")?,
+                    is_nullable: row.get("IS_NULLABLE")?,
+                    is_unique: row.get("IS_UNIQUE")?,
+                    is_primary_key: row.get("IS_PRIMARY_KEY")?,
+                    default_value: value_to_json(row.get_ref("DEFAULT_VALUE")?),
+                    dropdown_values: None
+                });
+                return Ok(());
+            }
+        )?;
+
+        // Get the values of any dropdown columns
+        for column in table.columns.iter_mut() {
+            match column.column_type {
+                data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid)
+                | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+                    let mut dropdown_values: Vec<String> = Vec::new();
+                    let select_cmd = format!("SELECT VALUE FROM TABLE{column_type_oid} WHERE TRASH = 0 ORDER BY ORDERING, OID;");
+                    query_iterate(trans, 
+                        &select_cmd, 
+                        [], 
+                        &mut |row| {
+                            dropdown_values.push(row.get(0)?);
+                            return Ok(());
+                        }
+                    )?;
+                    column.dropdown_values = Some(dropdown_values);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    // Get the reports
+    let mut reports: Vec<SchemaReport> = Vec::new();
+    query_iterate(trans, 
+        "SELECT 
+            r.RPT_OID,
+            r.NAME,
+            r.BASE_TABLE_OID
+        FROM METADATA_RPT__REPORT r
+        INNER JOIN METADATA_RPT m ON m.OID = r.RPT_OID
+        WHERE m.TRASH = 0
+        ORDER BY r.RPT_OID, r.NAME;", 
+        [], 
+        &mut |row| {
+            reports.push(SchemaReport {
+                oid: row.get("RPT_OID")?,
+                name: row.get("NAME")?,
+                base_table_oid: row.get("BASE_TABLE_OID")?,
+                columns: Vec::new()
+            });
+            return Ok(());
+        }
+    )?;
+    for report in reports.iter_mut() {
+        // Get the columns of the report
+        query_iterate(trans, 
+            "SELECT 
+                c.OID,
+                c.NAME,
+                c.CSS_COLUMN_STYLE,
+                c.COLUMN_ORDERING,
+                f.FORMULA
+            FROM METADATA_RPT_COLUMN c
+            LEFT JOIN METADATA_RPT_COLUMN__FORMULA f ON f.RPT_COLUMN_OID = c.OID
+            WHERE c.RPT_OID = ?1 AND c.TRASH = 0
+            ORDER BY c.OID, c.NAME;", 
+            params![report.oid], 
+            &mut |row| {
+                report.columns.push(SchemaReportColumn {
+                    oid: row.get("OID")?,
+                    name: row.get("NAME")?,
+                    column_style: row.get::<_, Option<String>>("CSS_COLUMN_STYLE")?.unwrap_or_default(),
+                    column_ordering: row.get("COLUMN_ORDERING")?,
+                    formula: row.get("FORMULA")?
+                });
+                return Ok(());
+            }
+        )?;
+    }
+
+    return Ok(Schema {
+        tables,
+        reports
+    });
+}
+
+/// Exports the structure of the database as JSON, excluding the data itself.
+/// Everything is ordered by OID, so that the output only changes when the structure of the database changes.
+pub fn export_schema_json() -> Result<String, error::Error> {
+    let action = begin_db_action()?;
+    let schema = action.finish(get_schema_in(&action))?;
+    return Ok(serde_json::to_string_pretty(&schema)?);
+}
+
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A part of a schema that was not applied, because it conflicts with the existing structure of the database.
+pub struct SchemaConflict {
+    pub table_name: String,
+    pub column_name: Option<String>,
+    pub description: String
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A summary of the changes made by applying a schema to the database.
+pub struct SchemaApplyReport {
+    pub created_table_oids: Vec<i64>,
+    pub created_column_oids: Vec<i64>,
+    pub conflicts: Vec<SchemaConflict>
+}
+
+impl SchemaApplyReport {
+    /// Records a conflict between the schema and the existing database.
+    fn conflict(&mut self, table_name: &str, column_name: Option<&str>, description: &str) {
+        self.conflicts.push(SchemaConflict {
+            table_name: String::from(table_name),
+            column_name: column_name.map(|s| String::from(s)),
+            description: String::from(description)
+        });
+    }
+}
+
+/// Applies a schema exported by export_schema_json to the database, creating any tables, columns, dropdown values, and inheritance that do not already exist.
+/// Tables and columns are matched to existing ones by name, and the OIDs in the schema are remapped to the OIDs of the matched or created tables.
+/// Existing tables and columns are never altered. If they do not match the schema, a conflict is reported instead.
+/// Reports are not applied.
+pub fn apply_schema_json(json: &str) -> Result<SchemaApplyReport, error::Error> {
+    let schema: Schema = serde_json::from_str(json)?;
+    let action = begin_db_action()?;
+    return action.finish(with_deferred_fk(&action, |trans| apply_schema_in(trans, &schema)));
+}
+
+/// Applies a schema to the database, as part of an ongoing action.
+fn apply_schema_in(trans: &Connection, schema: &Schema) -> Result<SchemaApplyReport, error::Error> {
+    let existing_schema = get_schema_in(trans)?;
+    let mut report = SchemaApplyReport {
+        created_table_oids: Vec::new(),
+        created_column_oids: Vec::new(),
+        conflicts: Vec::new()
+    };
+    let mut table_oid_map: HashMap<i64, i64> = HashMap::new();
+
+    // Match or create each table that is not a child table, with master tables before the tables that inherit from them
+    let mut pending_tables: Vec<&SchemaTable> = schema.tables.iter()
+        .filter(|t| t.parent_table_oid.is_none())
+        .collect();
+    while !pending_tables.is_empty() {
+        let table = match pending_tables.iter().position(|t| t.master_table_oids.iter().all(|m| table_oid_map.contains_key(m))) {
+            Some(i) => pending_tables.remove(i),
+            None => {
+                // The remaining tables inherit from tables that were not applied
+                for table in pending_tables.iter() {
+                    report.conflict(&table.name, None, "The table inherits from a table that was not applied.");
+                }
+                break;
+            }
+        };
+        let mut master_table_oids: Vec<i64> = table.master_table_oids.iter().map(|m| table_oid_map[m]).collect();
+
+        match existing_schema.tables.iter().find(|t| t.parent_table_oid.is_none() && t.name == table.name) {
+            Some(existing_table) => {
+                // Check that the existing table matches
+                if existing_table.mode != table.mode {
+                    report.conflict(&table.name, None, "A table with the same name already exists, but is of a different kind.");
+                    continue;
+                }
+                let mut existing_master_table_oids = existing_table.master_table_oids.clone();
+                existing_master_table_oids.sort();
+                master_table_oids.sort();
+                if existing_master_table_oids != master_table_oids {
+                    report.conflict(&table.name, None, "A table with the same name already exists, but inherits from different tables.");
+                }
+                table_oid_map.insert(table.oid, existing_table.oid);
+            },
+            None => {
+                // Create the table
+                let table_oid = match table.mode {
+                    4 => obj_type::create_in(trans, table.name.clone(), &master_table_oids)?,
+                    _ => table::create_in(trans, table.name.clone(), &master_table_oids)?
+                };
+                report.created_table_oids.push(table_oid);
+                table_oid_map.insert(table.oid, table_oid);
+            }
+        }
+    }
+
+    // Apply the columns of each table, which maps any child tables as their columns are applied
+    let mut applied_table_oids: HashSet<i64> = HashSet::new();
+    loop {
+        let table = match schema.tables.iter().find(|t| table_oid_map.contains_key(&t.oid) && !applied_table_oids.contains(&t.oid)) {
+            Some(t) => t,
+            None => { break; }
+        };
+        applied_table_oids.insert(table.oid);
+
+        let table_oid = table_oid_map[&table.oid];
+        let existing_table = existing_schema.tables.iter().find(|t| t.oid == table_oid);
+        let mut columns: Vec<&SchemaColumn> = table.columns.iter().collect();
+        columns.sort_by_key(|c| (c.column_ordering, c.oid));
+        for column in columns {
+            apply_schema_column_in(trans, table, column, table_oid, existing_table, &mut table_oid_map, &mut report)?;
+        }
+    }
+    for table in schema.tables.iter().filter(|t| t.parent_table_oid.is_some() && !applied_table_oids.contains(&t.oid)) {
+        report.conflict(&table.name, None, "The child table belongs to a column that was not applied.");
+    }
+
+    return Ok(report);
+}
+
+/// Matches or creates a column described by a schema, as part of applying the schema.
+fn apply_schema_column_in(trans: &Connection, table: &SchemaTable, column: &SchemaColumn, table_oid: i64, existing_table: Option<&SchemaTable>, table_oid_map: &mut HashMap<i64, i64>, report: &mut SchemaApplyReport) -> Result<(), error::Error> {
+    // Remap any table referenced by the column type
+    let column_type = match &column.column_type {
+        data_type::MetadataColumnType::Reference(referenced_table_oid) => match table_oid_map.get(referenced_table_oid) {
+            Some(new_table_oid) => data_type::MetadataColumnType::Reference(*new_table_oid),
+            None => {
+                report.conflict(&table.name, Some(&column.name), "The column references a table that was not applied.");
+                return Ok(());
+            }
+        },
+        data_type::MetadataColumnType::ChildObject(referenced_table_oid) => match table_oid_map.get(referenced_table_oid) {
+            Some(new_table_oid) => data_type::MetadataColumnType::ChildObject(*new_table_oid),
+            None => {
+                report.conflict(&table.name, Some(&column.name), "The column references an object type that was not applied.");
+                return Ok(());
+            }
+        },
+        other => other.clone()
+    };
+
+    let column_oid: i64 = match existing_table.and_then(|t| t.columns.iter().find(|c| c.name == column.name)) {
+        Some(existing_column) => {
+            // Check that the existing column matches
+            let is_same_type = match &column_type {
+                data_type::MetadataColumnType::Primitive(_)
+                | data_type::MetadataColumnType::Reference(_)
+                | data_type::MetadataColumnType::ChildObject(_) => column_type == existing_column.column_type,
+                _ => column_type.get_type_mode() == existing_column.column_type.get_type_mode()
+            };
+            if !is_same_type {
+                report.conflict(&table.name, Some(&column.name), "A column with the same name already exists, but has a different type.");
+                return Ok(());
+            }
+            if column.is_nullable != existing_column.is_nullable || column.is_unique != existing_column.is_unique || column.is_primary_key != existing_column.is_primary_key {
+                report.conflict(&table.name, Some(&column.name), "A column with the same name already exists, but has different constraints.");
+            }
+
+            // Map the child table onto the existing one
+            match (&column.column_type, &existing_column.column_type) {
+                (data_type::MetadataColumnType::ChildTable(child_table_oid), data_type::MetadataColumnType::ChildTable(existing_child_table_oid)) => {
+                    table_oid_map.insert(*child_table_oid, *existing_child_table_oid);
+                },
+                _ => {}
+            }
+            existing_column.oid
+        },
+        None => {
+            // Create the column
+            let column_oid = table_column::create_in(trans, table_oid, &column.name, column_type, None, &column.column_style, column.is_nullable, column.is_unique, column.is_primary_key)?;
+            report.created_column_oids.push(column_oid);
+
+            // Set the default value of the column
+            let default_value: Option<rusqlite::types::Value> = match &column.default_value {
+                Some(serde_json::Value::Bool(b)) => Some(rusqlite::types::Value::Integer(if *b { 1 } else { 0 })),
+                Some(serde_json::Value::Number(n)) => match n.as_i64() {
+                    Some(i) => Some(rusqlite::types::Value::Integer(i)),
+                    None => n.as_f64().map(|f| rusqlite::types::Value::Real(f))
+                },
+                Some(serde_json::Value::String(s)) => Some(rusqlite::types::Value::Text(s.clone())),
+                _ => None
+            };
+            let default_value_validation = match &default_value {
+                Some(rusqlite::types::Value::Text(s)) => table_column::validate_default_value(&column.column_type, Some(s.as_str())),
+                _ => Ok(())
+            };
+            match default_value_validation {
+                Ok(_) => {
+                    if default_value.is_some() {
+                        trans.execute("UPDATE METADATA_TABLE_COLUMN SET DEFAULT_VALUE = ?1 WHERE OID = ?2;", params![default_value, column_oid])?;
+                    }
+                },
+                Err(e) => {
+                    let description: String = e.into();
+                    report.conflict(&table.name, Some(&column.name), &description);
+                }
+            }
+
+            // Map the child table onto the one that was created
+            match &column.column_type {
+                data_type::MetadataColumnType::ChildTable(child_table_oid) => {
+                    let new_child_table_oid: i64 = trans.query_one(
+                        "SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", 
+                        params![column_oid], 
+                        |row| row.get(0)
+                    )?;
+                    table_oid_map.insert(*child_table_oid, new_child_table_oid);
+                },
+                _ => {}
+            }
+            column_oid
+        }
+    };
+
+    // Add any dropdown values that are missing
+    match &column.dropdown_values {
+        Some(dropdown_values) => {
+            table_column::import_dropdown_values_in(trans, column_oid, dropdown_values.clone())?;
+        },
+        None => {}
+    }
+    return Ok(());
+}
+
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A record of an action that was committed to the database.
+pub struct AuditLogEntry {
+    oid: i64,
+    logged_at: String,
+    direction: String,
+    action_type: String,
+    details: String
+}
+
+/// An audit log entry waiting for the action it describes to commit a change.
+struct PendingAuditEntry {
+    direction: &'static str,
+    action_type: String,
+    details: String
+}
+
+thread_local! {
+    /// The audit log entry of the undoable action being executed on this thread.
+    /// It is written in the same transaction as the first change that the action commits, so that the log cannot disagree with the data.
+    static PENDING_AUDIT_ENTRY: RefCell<Option<PendingAuditEntry>> = const { RefCell::new(None) };
+}
+
+/// Sets the audit log entry to record along with the first change committed on this thread.
+pub fn set_pending_audit_entry(direction: &'static str, action_type: String, details: String) {
+    PENDING_AUDIT_ENTRY.with(|e| {
+        *e.borrow_mut() = Some(PendingAuditEntry { direction, action_type, details });
+    });
+}
+
+/// Discards the audit log entry waiting to be recorded on this thread, if the action it describes did not commit a change.
+pub fn clear_pending_audit_entry() {
+    PENDING_AUDIT_ENTRY.with(|e| {
+        *e.borrow_mut() = None;
+    });
+}
+
+/// Gets a page of the audit log, most recent first.
+pub fn get_audit_log(limit: i64, offset: i64) -> Result<Vec<AuditLogEntry>, error::Error> {
+    let conn = open()?;
+    let mut entries: Vec<AuditLogEntry> = Vec::new();
+    query_iterate(&conn, 
+        "SELECT 
+            OID, 
+            STRFTIME('%FT%TZ', LOGGED_AT, 'julianday') AS LOGGED_AT, 
+            DIRECTION, 
+            ACTION_TYPE, 
+            DETAILS 
+        FROM METADATA_AUDIT_LOG 
+        ORDER BY OID DESC 
+        LIMIT ?1 OFFSET ?2;", 
+        params![limit, offset], 
+        &mut |row| {
+            entries.push(AuditLogEntry {
+                oid: row.get("OID")?,
+                logged_at: row.get("LOGGED_AT")?,
+                direction: row.get("DIRECTION")?,
+                action_type: row.get("ACTION_TYPE")?,
+                details: row.get("DETAILS")?
+            });
+            return Ok(());
+        }
+    )?;
+    return Ok(entries);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// The title, description, and author of the database file, along with when it was created and last modified.
+pub struct DocumentMetadata {
+    title: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    created_at: String,
+    modified_at: String
+}
+
+/// Gets the title, description, and author of the database file.
+pub fn get_document_metadata() -> Result<DocumentMetadata, error::Error> {
+    let conn = open()?;
+    return Ok(conn.query_one(
+        "SELECT 
+            TITLE, 
+            DESCRIPTION, 
+            AUTHOR, 
+            STRFTIME('%FT%TZ', CREATED_AT, 'julianday') AS CREATED_AT, 
+            STRFTIME('%FT%TZ', MODIFIED_AT, 'julianday') AS MODIFIED_AT 
+        FROM METADATA_DOCUMENT 
+        WHERE OID = 1;", 
+        [], 
+        |row| {
+            Ok(DocumentMetadata {
+                title: row.get("TITLE")?,
+                description: row.get("DESCRIPTION")?,
+                author: row.get("AUTHOR")?,
+                created_at: row.get("CREATED_AT")?,
+                modified_at: row.get("MODIFIED_AT")?
+            })
+        }
+    )?);
+}
+
+/// Sets the title, description, and author of the database file.
+pub fn set_document_metadata(title: Option<String>, description: Option<String>, author: Option<String>) -> Result<(), error::Error> {
+    let action = begin_db_action()?;
+    let result = action.execute(
+        "UPDATE METADATA_DOCUMENT SET TITLE = ?1, DESCRIPTION = ?2, AUTHOR = ?3 WHERE OID = 1;", 
+        params![title, description, author]
+    );
+    return action.finish(result.map(|_| ()).map_err(|e| e.into()));
+}
+
+/// Sets how many minutes pass between automatic checkpoints of the write-ahead log.
+/// Setting the interval to 0 disables automatic checkpoints.
+pub fn set_checkpoint_interval_minutes(minutes: u64) {
+    CHECKPOINT_INTERVAL_MINUTES.store(minutes, Ordering::Relaxed);
+}
+
+/// Copies as much of the write-ahead log into the database file as can be done without waiting on other connections.
+/// Returns whether a checkpoint was run, which it is not if no file is open or an action is midway through being performed.
+pub fn checkpoint_wal() -> Result<bool, error::Error> {
+    if ACTIVE_ACTION_COUNT.load(Ordering::SeqCst) > 0 {
+        return Ok(false);
+    }
+    let conn = match open() {
+        Ok(c) => c,
+        Err(error::Error::AdhocError(_)) => {
+            return Ok(false);
+        },
+        Err(e) => {
+            return Err(e);
+        }
+    };
+    query_iterate(&conn, "PRAGMA wal_checkpoint(PASSIVE);", [], &mut |row| {
+        log::debug!("Checkpointed WAL ({} of {} frames).", row.get::<_, i64>(2)?, row.get::<_, i64>(1)?);
+        return Ok(());
+    })?;
+    return Ok(true);
+}
+
+/// A connection kept open to watch for commits made by other connections to the database.
+struct DataVersionWatch {
+    path: String,
+    conn: Connection,
+    data_version: i64,
+    local_commit_count: u64
+}
+
+/// Gets the data version of the database, which changes whenever a connection other than the watching connection commits a change.
+pub fn data_version() -> Result<i64, error::Error> {
+    let mut watch = DATA_VERSION_WATCH.lock().unwrap();
+    return Ok(watch_in(&mut watch)?.data_version);
+}
+
+/// Gets the connection watching the open database, replacing it if a different database was opened since.
+fn watch_in(watch: &mut Option<DataVersionWatch>) -> Result<&mut DataVersionWatch, error::Error> {
+    let path: String = match *DATABASE_PATH.lock().unwrap() {
+        Some(ref path) => path.clone(),
+        None => {
+            return Err(error::Error::AdhocError("No file is open!"));
+        }
+    };
+    if watch.as_ref().map_or(true, |w| w.path != path) {
+        let conn = open()?;
+        let data_version: i64 = conn.query_one("PRAGMA data_version;", [], |row| row.get(0))?;
+        *watch = Some(DataVersionWatch { 
+            path, 
+            conn, 
+            data_version, 
+            local_commit_count: LOCAL_COMMIT_COUNT.load(Ordering::SeqCst) 
+        });
+    } else if let Some(w) = watch.as_mut() {
+        w.data_version = w.conn.query_one("PRAGMA data_version;", [], |row| row.get(0))?;
+    }
+    return Ok(watch.as_mut().unwrap());
+}
+
+/// Checks whether another process has committed a change to the database since the last check.
+/// Changes are assumed to be made by this process if it committed an action since the last check.
+pub fn poll_external_change() -> Result<bool, error::Error> {
+    let mut watch = DATA_VERSION_WATCH.lock().unwrap();
+    let (prior_data_version, prior_local_commit_count) = match watch.as_ref() {
+        Some(w) => (Some(w.data_version), w.local_commit_count),
+        None => (None, 0)
+    };
+    let w = watch_in(&mut watch)?;
+    let local_commit_count = LOCAL_COMMIT_COUNT.load(Ordering::SeqCst);
+    let is_changed = prior_data_version.map_or(false, |v| v != w.data_version) && prior_local_commit_count == local_commit_count;
+    w.local_commit_count = local_commit_count;
+    return Ok(is_changed);
+}
+
+/// Checks for changes committed to the database by other processes, calling a function whenever one is found, forever.
+/// This blocks the thread it is called on.
+pub fn run_external_change_watcher<F: Fn()>(on_change: F) {
+    loop {
+        std::thread::sleep(EXTERNAL_CHANGE_POLL_INTERVAL);
+        match poll_external_change() {
+            Ok(true) => {
+                on_change();
+            },
+            Ok(false) 
+            | Err(error::Error::AdhocError(_)) => {
+                // Nothing changed, or no file is open
+            },
+            Err(e) => {
+                let msg: String = e.into();
+                log::debug!("Could not check for external changes: {msg}");
+            }
+        }
+    }
+}
+
+/// Runs checkpoints of the write-ahead log at the configured interval, forever.
+/// This blocks the thread it is called on.
+pub fn run_checkpoint_timer() {
+    let mut minutes_elapsed: u64 = 0;
+    loop {
+        std::thread::sleep(Duration::from_secs(60));
+        minutes_elapsed += 1;
+
+        let interval = CHECKPOINT_INTERVAL_MINUTES.load(Ordering::Relaxed);
+        if interval == 0 || minutes_elapsed < interval {
+            continue;
+        }
+        match checkpoint_wal() {
+            Ok(true) => {
+                minutes_elapsed = 0;
+            },
+            Ok(false) => {
+                // Try again next minute
+            },
+            Err(e) => {
+                let msg: String = e.into();
+                log::warn!("Automatic checkpoint failed: {msg}");
+                minutes_elapsed = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_util;
+
+    /// Creates a table outside of any action for the tests to write to.
+    fn create_scratch_table() {
+        open().unwrap().execute_batch("CREATE TABLE SCRATCH (VALUE INTEGER);").unwrap();
+    }
+
+    /// Counts the rows in the scratch table.
+    fn count_scratch_rows() -> i64 {
+        return open().unwrap().query_one("SELECT COUNT(*) FROM SCRATCH;", [], |row| row.get(0)).unwrap();
+    }
+
+    /// Writes a row to the scratch table, as part of an ongoing action.
+    fn insert_scratch_row_in(trans: &Connection, value: i64) -> Result<(), error::Error> {
+        trans.execute("INSERT INTO SCRATCH (VALUE) VALUES (?1);", params![value])?;
+        return Ok(());
+    }
+
+    /// Writes two rows through nested helpers, then fails, as part of an ongoing action.
+    fn insert_scratch_rows_then_fail_in(trans: &Connection) -> Result<(), error::Error> {
+        insert_scratch_row_in(trans, 1)?;
+        insert_scratch_row_in(trans, 2)?;
+        return Err(error::Error::AdhocError("Failed on purpose."));
+    }
+
+    #[test]
+    fn finished_action_commits() {
+        let _db = test_util::open_test_db();
+        create_scratch_table();
+
+        let action = begin_db_action().unwrap();
+        action.finish(insert_scratch_row_in(&action, 1)).unwrap();
+        assert_eq!(count_scratch_rows(), 1);
+    }
+
+    #[test]
+    fn dropped_action_commits() {
+        let _db = test_util::open_test_db();
+        create_scratch_table();
+
+        // An action that is dropped without being finished commits what it wrote
+        {
+            let action = begin_db_action().unwrap();
+            insert_scratch_row_in(&action, 1).unwrap();
+        }
+        assert_eq!(count_scratch_rows(), 1);
+
+        // A read-only action cannot write at all
+        let action = begin_readonly_db_action().unwrap();
+        assert!(insert_scratch_row_in(&action, 2).is_err());
+        drop(action);
+        assert_eq!(count_scratch_rows(), 1);
+    }
+
+    #[test]
+    fn action_finished_with_error_rolls_back() {
+        let _db = test_util::open_test_db();
+        create_scratch_table();
+
+        let action = begin_db_action().unwrap();
+        let result = action.finish(insert_scratch_row_in(&action, 1).and_then(|_| Err::<(), _>(error::Error::AdhocError("Failed on purpose."))));
+        assert!(result.is_err());
+        assert_eq!(count_scratch_rows(), 0);
+    }
+
+    #[test]
+    fn nested_helpers_roll_back_together() {
+        let _db = test_util::open_test_db();
+        create_scratch_table();
+
+        let action = begin_db_action().unwrap();
+        assert!(action.finish(insert_scratch_rows_then_fail_in(&action)).is_err());
+        assert_eq!(count_scratch_rows(), 0);
+
+        let action = begin_db_action().unwrap();
+        action.finish(insert_scratch_row_in(&action, 1).and_then(|_| insert_scratch_row_in(&action, 2))).unwrap();
+        assert_eq!(count_scratch_rows(), 2);
+    }
+
+    #[test]
+    fn only_commits_by_other_processes_are_external_changes() {
+        let db = test_util::open_test_db();
+        create_scratch_table();
+        poll_external_change().unwrap();
+
+        // Commits made through any connection of this process are local, including ones outside of an action
+        let mut conn = open().unwrap();
+        let trans = conn.transaction().unwrap();
+        insert_scratch_row_in(&trans, 1).unwrap();
+        trans.commit().unwrap();
+        insert_scratch_row_in(&conn, 2).unwrap();
+        assert!(!poll_external_change().unwrap());
+
+        // A connection that was not opened through this module stands in for another process
+        let other_conn = Connection::open(&db.path).unwrap();
+        insert_scratch_row_in(&other_conn, 3).unwrap();
+        assert!(poll_external_change().unwrap());
+        assert!(!poll_external_change().unwrap());
+    }
+
+    /// Gets the time the database was last modified.
+    fn get_modified_at() -> f64 {
+        return open().unwrap().query_one("SELECT MODIFIED_AT FROM METADATA_DOCUMENT;", [], |row| row.get(0)).unwrap();
+    }
+
+    #[test]
+    fn committed_edits_record_modification_time() {
+        let _db = test_util::open_test_db();
+        create_scratch_table();
+
+        // An action that changes nothing leaves the modification time alone
+        let before = get_modified_at();
+        std::thread::sleep(Duration::from_millis(10));
+        let action = begin_db_action().unwrap();
+        action.finish(Ok(())).unwrap();
+        assert_eq!(get_modified_at(), before);
+
+        // An action that changes something updates it
+        let action = begin_db_action().unwrap();
+        action.finish(insert_scratch_row_in(&action, 1)).unwrap();
+        let after_action = get_modified_at();
+        assert!(after_action > before);
+
+        // So do edits that are not recorded as actions
+        std::thread::sleep(Duration::from_millis(10));
+        let table_oid = table::create(String::from("Monsters"), &vec![]).unwrap();
+        let after_create = get_modified_at();
+        assert!(after_create > after_action);
+        std::thread::sleep(Duration::from_millis(10));
+        let row_oid = table_data::push(table_oid).unwrap();
+        table_data::move_trash(table_oid, row_oid).unwrap();
+        assert!(get_modified_at() > after_create);
+    }
+
+    #[test]
+    fn repair_only_removes_confirmed_types() {
+        let _db = test_util::open_test_db();
+
+        // Leave behind a dropdown that no column uses
+        let type_oid: i64 = {
+            let conn = open().unwrap();
+            conn.execute("INSERT INTO METADATA_TYPE (MODE) VALUES (1);", []).unwrap();
+            let type_oid = conn.last_insert_rowid();
+            conn.execute(&format!("CREATE TABLE TABLE{type_oid} (OID INTEGER PRIMARY KEY, TRASH TINYINT NOT NULL DEFAULT 0, VALUE TEXT NOT NULL, ORDERING INTEGER NOT NULL DEFAULT 0);"), []).unwrap();
+            type_oid
+        };
+        let type_exists = || open().unwrap()
+            .query_one("SELECT EXISTS (SELECT 1 FROM METADATA_TYPE WHERE OID = ?1) AND EXISTS (SELECT 1 FROM sqlite_master WHERE name = ?2);", params![type_oid, format!("TABLE{type_oid}")], |row| row.get::<_, bool>(0))
+            .unwrap();
+
+        // Previewing the repair reports the dropdown without removing it
+        let preview = preview_repair().unwrap();
+        assert_eq!(preview.removed_types.iter().map(|t| t.type_oid).collect::<Vec<i64>>(), vec![type_oid]);
+        assert!(type_exists());
+
+        // Repairing without confirming the dropdown leaves it alone
+        let report = repair(&RepairConfirmation { removed_type_oids: Vec::new(), dropped_value_table_oids: Vec::new() }).unwrap();
+        assert!(report.removed_types.is_empty());
+        assert!(report.dropped_value_table_oids.is_empty());
+        assert!(type_exists());
+
+        // Confirming the dropdown removes it
+        repair(&RepairConfirmation { removed_type_oids: vec![type_oid], dropped_value_table_oids: vec![type_oid] }).unwrap();
+        assert!(!type_exists());
+    }
+
+    #[test]
+    fn subreport_reference_is_migrated() {
+        let db = test_util::open_test_db();
+        let get_referenced_column = || open().unwrap()
+            .query_one("SELECT \"to\" FROM pragma_foreign_key_list('METADATA_RPT_COLUMN__SUBREPORT') WHERE \"table\" = 'METADATA_RPT_PARAMETER__REFERENCED';", [], |row| row.get::<_, String>(0))
+            .unwrap();
+        assert_eq!(get_referenced_column(), "RPT_PARAMETER_OID");
+
+        // Simulate a database created while the table referenced a missing column
+        Connection::open(&db.path).unwrap().execute_batch("
+        DROP TABLE METADATA_RPT_COLUMN__SUBREPORT;
+        CREATE TABLE METADATA_RPT_COLUMN__SUBREPORT (
+            RPT_COLUMN_OID INTEGER PRIMARY KEY REFERENCES METADATA_RPT_COLUMN (OID),
+            RPT_OID INTEGER NOT NULL REFERENCES METADATA_RPT (OID),
+            RPT_PARAMETER__REFERENCED__OID INTEGER NOT NULL REFERENCES METADATA_RPT_PARAMETER__REFERENCED (OID)
+        );").unwrap();
+        assert_eq!(get_referenced_column(), "OID");
+
+        // Reopening the database corrects the reference
+        init(db.path.to_string_lossy().into_owned()).unwrap();
+        assert_eq!(get_referenced_column(), "RPT_PARAMETER_OID");
+    }
+
+    #[test]
+    fn audit_entry_is_committed_with_the_first_change() {
+        let _db = test_util::open_test_db();
+        create_scratch_table();
+        let count_entries = || open().unwrap().query_one("SELECT COUNT(*) FROM METADATA_AUDIT_LOG;", [], |row| row.get::<_, i64>(0)).unwrap();
+        set_pending_audit_entry("execute", String::from("insertScratchRow"), String::from("{}"));
+
+        // Actions that change nothing or are rolled back leave the entry pending
+        let action = begin_db_action().unwrap();
+        action.finish(Ok(())).unwrap();
+        let action = begin_db_action().unwrap();
+        assert!(action.finish(insert_scratch_rows_then_fail_in(&action)).is_err());
+        assert_eq!(count_entries(), 0);
+
+        // The first action to commit a change records the entry, and only once
+        for value in 0..2 {
+            let action = begin_db_action().unwrap();
+            action.finish(insert_scratch_row_in(&action, value)).unwrap();
+        }
+        assert_eq!(count_entries(), 1);
+        let (direction, action_type): (String, String) = open().unwrap()
+            .query_one("SELECT DIRECTION, ACTION_TYPE FROM METADATA_AUDIT_LOG;", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(direction, "execute");
+        assert_eq!(action_type, "insertScratchRow");
+    }
+
+    #[test]
+    fn primary_key_indexes_are_built_on_open() {
+        let db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+        let has_index = |table_oid: i64| open().unwrap()
+            .query_one("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'index' AND name = ?1);", params![format!("TABLE{table_oid}_PRIMARY_KEY")], |row| row.get::<_, bool>(0))
+            .unwrap();
+
+        // Simulate two tables created before primary keys were enforced, one of which has rows sharing a key
+        let mut table_oids: Vec<i64> = Vec::new();
+        for (table_name, second_key) in [("Unique", "B"), ("Duplicated", "A")] {
+            let table_oid = table::create(String::from(table_name), &vec![]).unwrap();
+            let column_oid = table_column::create(table_oid, "Key", text_type.clone(), None, "", true, false, true, None).unwrap();
+            let conn = open().unwrap();
+            table::drop_primary_key_index(&conn, table_oid).unwrap();
+            conn.execute(&format!("INSERT INTO TABLE{table_oid} (OID, COLUMN{column_oid}) VALUES (1, 'A'), (2, ?1);"), params![second_key]).unwrap();
+            table_oids.push(table_oid);
+        }
+        assert!(!has_index(table_oids[0]));
+
+        // Reopening the database builds the index wherever the existing rows allow it
+        init(db.path.to_string_lossy().into_owned()).unwrap();
+        assert!(has_index(table_oids[0]));
+        assert!(!has_index(table_oids[1]));
+    }
+
+    #[test]
+    fn order_keys_are_backfilled_on_open() {
+        let db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Rows"), &vec![]).unwrap();
+
+        // Simulate a table created before rows had an ORDER_KEY
+        Connection::open(&db.path).unwrap().execute_batch(&format!("
+        DROP INDEX TABLE{table_oid}_ORDER_KEY;
+        ALTER TABLE TABLE{table_oid} DROP COLUMN ORDER_KEY;
+        INSERT INTO TABLE{table_oid} (OID) VALUES (1), (4), (9);")).unwrap();
+
+        // Reopening the database gives each row the ORDER_KEY of its OID
+        init(db.path.to_string_lossy().into_owned()).unwrap();
+        let mut order_keys: Vec<(i64, f64)> = Vec::new();
+        query_iterate(&open().unwrap(), &format!("SELECT OID, ORDER_KEY FROM TABLE{table_oid} ORDER BY OID;"), [], &mut |row| {
+            order_keys.push((row.get(0)?, row.get(1)?));
+            return Ok(());
+        }).unwrap();
+        assert_eq!(order_keys, vec![(1, 1.0), (4, 4.0), (9, 9.0)]);
+    }
+
+    #[test]
+    fn query_iterate_returns_callback_errors() {
+        let _db = test_util::open_test_db();
+        let sql = "SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3;";
+
+        // Fail on the second row, through both the free function and an action, and stop there
+        for use_action in [false, true] {
+            let mut visited_values: Vec<i64> = Vec::new();
+            let mut f = |row: &Row<'_>| {
+                let value: i64 = row.get(0)?;
+                visited_values.push(value);
+                if value == 2 {
+                    return Err(error::Error::AdhocError("The second row failed."));
+                }
+                return Ok(());
+            };
+            let result = if use_action {
+                begin_readonly_db_action().unwrap().query_iterate(sql, [], &mut f)
+            } else {
+                query_iterate(&open().unwrap(), sql, [], &mut f)
+            };
+            assert!(matches!(result, Err(error::Error::AdhocError(_))));
+            assert_eq!(visited_values, vec![1, 2]);
+        }
+    }
+
+    #[test]
+    fn child_surrogate_views_are_migrated() {
+        let db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Orders"), &vec![]).unwrap();
+        let column_oid = table_column::create(table_oid, "Items", data_type::MetadataColumnType::ChildTable(0), None, "", true, false, false, None).unwrap();
+        let child_oid: i64 = open().unwrap().query_one("SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", params![column_oid], |row| row.get(0)).unwrap();
+        let has_parent_oid = || open().unwrap()
+            .query_one("SELECT EXISTS (SELECT 1 FROM pragma_table_info(?1) WHERE name = 'PARENT_OID');", params![format!("TABLE{child_oid}_SURROGATE")], |row| row.get::<_, bool>(0))
+            .unwrap();
+        assert!(has_parent_oid());
+
+        // Simulate a view created before it kept the parent row
+        Connection::open(&db.path).unwrap().execute_batch(&format!("
+        DROP VIEW TABLE{child_oid}_SURROGATE;
+        CREATE VIEW TABLE{child_oid}_SURROGATE AS SELECT t.OID, '— NO PRIMARY KEY —' AS DISPLAY_VALUE, '{{}}' AS JSON_DISPLAY_VALUE FROM TABLE{child_oid} t;")).unwrap();
+        assert!(!has_parent_oid());
+
+        // Reopening the database rebuilds the view
+        init(db.path.to_string_lossy().into_owned()).unwrap();
+        assert!(has_parent_oid());
+    }
+
+    #[test]
+    fn deferred_foreign_keys_are_checked_at_the_end() {
+        let _db = test_util::open_test_db();
+        open().unwrap().execute_batch("
+        CREATE TABLE SCRATCH_PARENT (OID INTEGER PRIMARY KEY);
+        CREATE TABLE SCRATCH_CHILD (OID INTEGER PRIMARY KEY, PARENT_OID INTEGER REFERENCES SCRATCH_PARENT (OID));").unwrap();
+        let insert_child_in = |trans: &Connection, oid: i64| trans.execute("INSERT INTO SCRATCH_CHILD (OID, PARENT_OID) VALUES (?1, ?1);", params![oid]).map_err(error::Error::from);
+        let count_children = || open().unwrap().query_one("SELECT COUNT(*) FROM SCRATCH_CHILD;", [], |row| row.get::<_, i64>(0)).unwrap();
+
+        // A row can be inserted before the row it references
+        let action = begin_db_action().unwrap();
+        let result = with_deferred_fk(&action, |trans| {
+            insert_child_in(trans, 1)?;
+            trans.execute("INSERT INTO SCRATCH_PARENT (OID) VALUES (1);", [])?;
+            return Ok(());
+        });
+        action.finish(result).unwrap();
+        assert_eq!(count_children(), 1);
+
+        // Once the function returns, references are checked immediately again
+        let action = begin_db_action().unwrap();
+        with_deferred_fk(&action, |_| Ok(())).unwrap();
+        assert!(insert_child_in(&action, 2).is_err());
+        action.rollback(Ok(())).unwrap();
+
+        // A reference that is never satisfied still fails the action
+        let action = begin_db_action().unwrap();
+        let result = with_deferred_fk(&action, |trans| insert_child_in(trans, 3));
+        assert!(action.finish(result).is_err());
+        assert_eq!(count_children(), 1);
+    }
+
+    #[test]
+    fn string_list_type_is_added_on_open() {
+        let db = test_util::open_test_db();
+        let has_type = || open().unwrap().query_one("SELECT EXISTS (SELECT 1 FROM METADATA_TYPE WHERE OID = -1 AND MODE = 0);", [], |row| row.get::<_, bool>(0)).unwrap();
+        assert!(has_type());
+
+        // Simulate a database created before the type existed
+        Connection::open(&db.path).unwrap().execute("DELETE FROM METADATA_TYPE WHERE OID = -1;", []).unwrap();
+        assert!(!has_type());
+
+        // Reopening the database adds the type
+        init(db.path.to_string_lossy().into_owned()).unwrap();
+        assert!(has_type());
+    }
+}
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Instant;
 
-use rusqlite::{OptionalExtension, Statement, ToSql, Transaction, params};
+use rusqlite::{OptionalExtension, ToSql, Connection, params};
 use tauri::ipc::Channel;
 use serde::{Serialize, Deserialize};
 use crate::backend::{data_type, db, table, table_data};
@@ -9,8 +10,17 @@ use crate::util::error;
 
 /// Creates a new table.
 pub fn create(name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(create_in(&action, name, master_table_oid_list));
+    log::debug!("Created object type (OID {:?}) in {:?}.", result.as_ref().ok(), start.elapsed());
+    return result;
+}
+
+/// Creates a new object type, as part of an ongoing action.
+pub fn create_in(trans: &Connection, name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, error::Error> {
+    // Make sure the name can be used
+    table::validate_name_in(trans, &name, None)?;
 
     // Add metadata for the table
     trans.execute("INSERT INTO METADATA_TYPE (MODE) VALUES (4);", [])?;
@@ -27,6 +37,7 @@ pub fn create(name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, err
         TRASH INTEGER NOT NULL DEFAULT 0
     ) STRICT;");
     trans.execute(&create_table_cmd, [])?;
+    db::add_order_key(&trans, table_oid)?;
 
     // Add inheritance from each master table
     for master_table_oid in master_table_oid_list.iter() {
@@ -44,13 +55,87 @@ pub fn create(name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, err
     // Update the surrogate view
     table::update_surrogate_view(&trans, table_oid.clone())?;
 
-    // Commit the transaction
-    trans.commit()?;
+    // Create the flattened view
+    update_flattened_views(&trans, table_oid.clone())?;
+    db::update_friendly_view(&trans, table_oid)?;
+
     return Ok(table_oid);
 }
 
 
 
+/// Removes the inheritance of an object type from one of its master types.
+/// This is only allowed while the inheritor has no rows, as each row would otherwise lose its link to the master type's data.
+pub fn remove_inheritance(inheritor_oid: i64, master_oid: i64) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(remove_inheritance_in(&action, inheritor_oid, master_oid));
+}
+
+/// Removes the inheritance of an object type from one of its master types, as part of an ongoing action.
+fn remove_inheritance_in(trans: &Connection, inheritor_oid: i64, master_oid: i64) -> Result<(), error::Error> {
+    // Make sure that no rows would be orphaned from their supertype data
+    let count_cmd: String = format!("SELECT COUNT(*) FROM TABLE{inheritor_oid};");
+    let row_count: i64 = trans.query_one(&count_cmd, [], |row| row.get(0))?;
+    if row_count > 0 {
+        return Err(error::Error::AdhocError("Cannot remove inheritance from an object type that has existing rows, as they would lose their link to the master type's data."));
+    }
+
+    // Drop any views that select through the link before it is dropped
+    drop_flattened_views(&trans, inheritor_oid)?;
+
+    // Flag the inheritance as trash
+    trans.execute(
+        "UPDATE METADATA_TABLE_INHERITANCE SET TRASH = 1 WHERE INHERITOR_TABLE_OID = ?1 AND MASTER_TABLE_OID = ?2;",
+        params![inheritor_oid, master_oid]
+    )?;
+
+    // Drop the column that references a row in the master table, along with the primary key index that covers it
+    table::drop_primary_key_index(&trans, inheritor_oid)?;
+    let alter_table_cmd: String = format!("ALTER TABLE TABLE{inheritor_oid} DROP COLUMN MASTER{master_oid}_OID;");
+    trans.execute(&alter_table_cmd, [])?;
+
+    // Update the primary key index, and the surrogate and flattened views
+    table::update_primary_key_index(&trans, inheritor_oid)?;
+    table::update_surrogate_view(&trans, inheritor_oid)?;
+    update_flattened_views(&trans, inheritor_oid)?;
+
+    return Ok(());
+}
+
+/// Adds inheritance of an object type from a master type, restoring a previously-removed inheritance if there is one.
+pub fn add_inheritance(inheritor_oid: i64, master_oid: i64) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(add_inheritance_in(&action, inheritor_oid, master_oid));
+}
+
+/// Adds inheritance of an object type from a master type, restoring a previously-removed inheritance if there is one, as part of an ongoing action.
+fn add_inheritance_in(trans: &Connection, inheritor_oid: i64, master_oid: i64) -> Result<(), error::Error> {
+    // Unflag the previous inheritance as trash, or insert new metadata for it
+    let restored_count: usize = trans.execute(
+        "UPDATE METADATA_TABLE_INHERITANCE SET TRASH = 0 WHERE INHERITOR_TABLE_OID = ?1 AND MASTER_TABLE_OID = ?2;",
+        params![inheritor_oid, master_oid]
+    )?;
+    if restored_count == 0 {
+        trans.execute(
+            "INSERT INTO METADATA_TABLE_INHERITANCE (INHERITOR_TABLE_OID, MASTER_TABLE_OID) VALUES (?1, ?2);",
+            params![inheritor_oid, master_oid]
+        )?;
+    }
+
+    // Add a column to the table that references a row in the master list
+    let alter_table_cmd: String = format!("ALTER TABLE TABLE{inheritor_oid} ADD COLUMN MASTER{master_oid}_OID INTEGER NOT NULL REFERENCES TABLE{master_oid} (OID) ON UPDATE CASCADE ON DELETE CASCADE;");
+    trans.execute(&alter_table_cmd, [])?;
+
+    // Update the primary key index, and the surrogate and flattened views
+    table::update_primary_key_index(&trans, inheritor_oid)?;
+    table::update_surrogate_view(&trans, inheritor_oid)?;
+    update_flattened_views(&trans, inheritor_oid)?;
+
+    return Ok(());
+}
+
+
+
 #[derive(Serialize, Clone)]
 pub struct BasicMetadata {
     oid: i64,
@@ -155,24 +240,37 @@ pub fn send_metadata_list(obj_type_oid: Option<i64>, obj_type_channel: Channel<B
 }
 
 
+/// Gets the number of direct subtypes of each object type that has any.
+pub fn subtype_counts() -> Result<HashMap<i64, i64>, error::Error> {
+    let action = db::begin_readonly_db_action()?;
 
-pub fn send_obj_data(obj_type_oid: i64, obj_row_oid: i64, obj_data_channel: Channel<table_data::RowCell>) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
-
-    let mut subtypes: HashMap<i64, i64> = HashMap::new();
-    subtypes.insert(obj_type_oid, obj_row_oid);
-    let mut max_level: i64 = 0;
-    let mut max_level_subtype: Vec<i64> = vec![obj_type_oid];
+    let mut counts: HashMap<i64, i64> = HashMap::new();
+    db::query_iterate(&action, 
+        "SELECT
+            MASTER_TABLE_OID,
+            COUNT(INHERITOR_TABLE_OID) AS SUBTYPE_COUNT
+        FROM METADATA_TABLE_INHERITANCE
+        WHERE TRASH = 0
+        GROUP BY MASTER_TABLE_OID", 
+        [], 
+        &mut |row| {
+            counts.insert(row.get("MASTER_TABLE_OID")?, row.get("SUBTYPE_COUNT")?);
+            return Ok(());
+        }
+    )?;
+    return Ok(counts);
+}
 
-    // Query a list of all subtypes of the given type
-    let mut subtype_statement = trans.prepare(
+/// Queries every subtype of the given type, returning the level, master type OID, and type OID of each.
+fn query_subtypes(trans: &Connection, obj_type_oid: i64) -> Result<Vec<(i64, i64, i64)>, error::Error> {
+    let mut subtypes: Vec<(i64, i64, i64)> = Vec::new();
+    db::query_iterate(trans, 
         "WITH RECURSIVE SUBTYPE_QUERY (LEVEL, MASTER_TYPE_OID, TYPE_OID) AS (
                 SELECT
                     1 AS LEVEL,
                     u.MASTER_TABLE_OID AS MASTER_TYPE_OID,
                     u.INHERITOR_TABLE_OID AS TYPE_OID
-                FROM METADATA_TABLE_INHERITANCE u ON 
+                FROM METADATA_TABLE_INHERITANCE u
                 WHERE u.TRASH = 0 AND u.MASTER_TABLE_OID = ?1
                 UNION
                 SELECT
@@ -188,25 +286,145 @@ pub fn send_obj_data(obj_type_oid: i64, obj_row_oid: i64, obj_data_channel: Chan
                 LEVEL,
                 MASTER_TYPE_OID,
                 TYPE_OID
-            FROM SUBTYPE_QUERY"
-    )?;
-    let subtype_rows = subtype_statement.query_map(
+            FROM SUBTYPE_QUERY", 
         params![obj_type_oid], 
-        |row| {
+        &mut |row| {
             let level: i64 = row.get("LEVEL")?;
             let master_type_oid: i64 = row.get("MASTER_TYPE_OID")?;
             let type_oid: i64 = row.get("TYPE_OID")?;
-            return Ok((level, master_type_oid, type_oid));
+            subtypes.push((level, master_type_oid, type_oid));
+            return Ok(());
         }
     )?;
+    return Ok(subtypes);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A row of an object type that is linked to by more than one row of its subtypes.
+pub struct IntegrityIssue {
+    type_oid: i64,
+    row_oid: i64,
+    subtype_oids: Vec<i64>
+}
+
+/// Finds every object in a type hierarchy that belongs to more than one subtype branch.
+/// Branches that meet again further down, as when a type inherits from two subtypes of the same type, belong to the same object and are not reported.
+/// Issues are ordered by type OID, then row OID.
+pub fn check_integrity(root_oid: i64) -> Result<Vec<IntegrityIssue>, error::Error> {
+    let action = db::begin_readonly_db_action()?;
+
+    // Group the direct subtypes of each type in the hierarchy
+    let mut direct_subtypes: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for (_, master_type_oid, type_oid) in query_subtypes(&action, root_oid)? {
+        let subtype_oids = direct_subtypes.entry(master_type_oid).or_insert_with(Vec::new);
+        if !subtype_oids.contains(&type_oid) {
+            subtype_oids.push(type_oid);
+        }
+    }
+    for subtype_oids in direct_subtypes.values_mut() {
+        subtype_oids.sort();
+    }
+
+    // For each type, find rows that are linked to by more than one subtype row
+    let mut issues: Vec<IntegrityIssue> = Vec::new();
+    for (master_type_oid, subtype_oids) in direct_subtypes.iter() {
+        let union_cmd: String = subtype_oids.iter()
+            .map(|subtype_oid| format!("SELECT MASTER{master_type_oid}_OID AS ROW_OID, {subtype_oid} AS SUBTYPE_OID, OID AS SUBTYPE_ROW_OID FROM TABLE{subtype_oid}"))
+            .collect::<Vec<String>>()
+            .join(" UNION ALL ");
+        let select_cmd: String = format!("
+            SELECT
+                ROW_OID,
+                SUBTYPE_OID,
+                SUBTYPE_ROW_OID
+            FROM ({union_cmd})
+            WHERE ROW_OID IN (SELECT ROW_OID FROM ({union_cmd}) GROUP BY ROW_OID HAVING COUNT(*) > 1)
+            ORDER BY ROW_OID, SUBTYPE_OID, SUBTYPE_ROW_OID"
+        );
+        let mut branches_by_row: BTreeMap<i64, Vec<(i64, i64)>> = BTreeMap::new();
+        db::query_iterate(&action, 
+            &select_cmd, 
+            [], 
+            &mut |row| {
+                branches_by_row.entry(row.get("ROW_OID")?)
+                    .or_insert_with(Vec::new)
+                    .push((row.get("SUBTYPE_OID")?, row.get("SUBTYPE_ROW_OID")?));
+                return Ok(());
+            }
+        )?;
+
+        // Merge the branches that lead to a common row further down, then report any row left in more than one branch
+        for (row_oid, branches) in branches_by_row {
+            let mut merged_branches: Vec<(Vec<i64>, HashSet<(i64, i64)>)> = Vec::new();
+            for (subtype_oid, subtype_row_oid) in branches {
+                let mut subtype_oids: Vec<i64> = vec![subtype_oid];
+                let mut visited: HashSet<(i64, i64)> = collect_descendant_rows(&action, &direct_subtypes, subtype_oid, subtype_row_oid)?;
+                merged_branches.retain(|(other_subtype_oids, other_visited)| {
+                    if other_visited.is_disjoint(&visited) {
+                        return true;
+                    }
+                    subtype_oids.extend(other_subtype_oids.iter());
+                    visited.extend(other_visited.iter());
+                    return false;
+                });
+                merged_branches.push((subtype_oids, visited));
+            }
+            if merged_branches.len() > 1 {
+                let mut subtype_oids: Vec<i64> = merged_branches.into_iter().flat_map(|(subtype_oids, _)| subtype_oids).collect();
+                subtype_oids.sort();
+                issues.push(IntegrityIssue { 
+                    type_oid: *master_type_oid, 
+                    row_oid, 
+                    subtype_oids
+                });
+            }
+        }
+    }
+    issues.sort_by_key(|issue| (issue.type_oid, issue.row_oid));
+    return Ok(issues);
+}
+
+/// Collects a row of a type, along with every row of its subtypes that extends it, directly or indirectly.
+/// Each row is only visited once, so that rows reached through more than one path are not walked again.
+fn collect_descendant_rows(trans: &Connection, direct_subtypes: &BTreeMap<i64, Vec<i64>>, type_oid: i64, row_oid: i64) -> Result<HashSet<(i64, i64)>, error::Error> {
+    let mut visited: HashSet<(i64, i64)> = HashSet::new();
+    let mut unvisited: Vec<(i64, i64)> = vec![(type_oid, row_oid)];
+    while let Some((current_type_oid, current_row_oid)) = unvisited.pop() {
+        if !visited.insert((current_type_oid, current_row_oid)) {
+            continue;
+        }
+        if let Some(subtype_oids) = direct_subtypes.get(&current_type_oid) {
+            for subtype_oid in subtype_oids {
+                let select_cmd = format!("SELECT OID FROM TABLE{subtype_oid} WHERE MASTER{current_type_oid}_OID = ?1;");
+                db::query_iterate(trans, &select_cmd, params![current_row_oid], &mut |row| {
+                    unvisited.push((*subtype_oid, row.get(0)?));
+                    return Ok(());
+                })?;
+            }
+        }
+    }
+    return Ok(visited);
+}
+
+
+pub fn send_obj_data(obj_type_oid: i64, obj_row_oid: i64, obj_data_channel: Channel<table_data::RowCell>) -> Result<(), error::Error> {
+    let action = db::begin_readonly_db_action()?;
+
+    let mut subtypes: HashMap<i64, i64> = HashMap::new();
+    subtypes.insert(obj_type_oid, obj_row_oid);
+    let mut max_level: i64 = 0;
+    let mut max_level_subtype: Vec<i64> = vec![obj_type_oid];
+
+    // Query a list of all subtypes of the given type
+    let subtype_rows = query_subtypes(&action, obj_type_oid)?;
 
     // Find each table with a row associated with the obj_row_oid in the original object table
-    for subtype_row_result in subtype_rows {
-        let (level, master_type_oid, inheritor_type_oid) = subtype_row_result.unwrap();
+    for (level, master_type_oid, inheritor_type_oid) in subtype_rows {
         if !subtypes.contains_key(&inheritor_type_oid) && subtypes.contains_key(&master_type_oid) {
             let master_row_oid: i64 = subtypes[&master_type_oid];
             let select_from_type_table_cmd: String = format!("SELECT OID FROM TABLE{inheritor_type_oid} WHERE MASTER{master_type_oid}_OID = ?1");
-            match trans.query_one(&select_from_type_table_cmd, params![master_row_oid], |row| row.get(0)).optional()? {
+            match action.query_one(&select_from_type_table_cmd, params![master_row_oid], |row| row.get(0)).optional()? {
                 Some(inheritor_row_oid) => {
                     subtypes.insert(inheritor_type_oid, inheritor_row_oid);
 
@@ -232,4 +450,204 @@ pub fn send_obj_data(obj_type_oid: i64, obj_row_oid: i64, obj_data_channel: Chan
     // Send the columns and values of the row
     table_data::send_table_row(final_obj_type_oid, final_obj_row_oid, obj_data_channel)?;
     return Ok(());
-}
\ No newline at end of file
+}
+
+/// Gets the OIDs of an object type and all of its subtypes, if the table is an object type.
+fn get_self_and_subtype_oids(trans: &Connection, table_oid: i64) -> Result<Vec<i64>, error::Error> {
+    let mut type_oids: Vec<i64> = Vec::new();
+    db::query_iterate(trans, 
+        "WITH RECURSIVE SUBTYPE_QUERY (TYPE_OID) AS (
+            SELECT
+                typ.OID AS TYPE_OID
+            FROM METADATA_TYPE typ
+            WHERE typ.OID = ?1 AND typ.MODE = 4
+            UNION
+            SELECT
+                u.INHERITOR_TABLE_OID AS TYPE_OID
+            FROM SUBTYPE_QUERY s
+            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.MASTER_TABLE_OID = s.TYPE_OID
+            WHERE u.TRASH = 0
+        )
+        SELECT TYPE_OID FROM SUBTYPE_QUERY", 
+        params![table_oid], 
+        &mut |row| {
+            type_oids.push(row.get("TYPE_OID")?);
+            return Ok(());
+        }
+    )?;
+    return Ok(type_oids);
+}
+
+/// Drops the flattened views of an object type and all of its subtypes.
+/// This must be done before dropping any column that the views select from.
+pub fn drop_flattened_views(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    for type_oid in get_self_and_subtype_oids(trans, table_oid)? {
+        let drop_view_cmd: String = format!("DROP VIEW IF EXISTS TABLE{type_oid}_FLAT;");
+        trans.execute(&drop_view_cmd, [])?;
+    }
+    return Ok(());
+}
+
+/// Rebuilds the flattened views of an object type and all of its subtypes.
+/// If the table is not an object type, this does nothing.
+pub fn update_flattened_views(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    for type_oid in get_self_and_subtype_oids(trans, table_oid)? {
+        // Drop the previous view
+        let drop_view_cmd: String = format!("DROP VIEW IF EXISTS TABLE{type_oid}_FLAT;");
+        trans.execute(&drop_view_cmd, [])?;
+
+        // Join the type's table to the tables of all its supertypes
+        let (_, select_tbls_cmd) = table_data::construct_supertype_clauses(trans, type_oid)?;
+
+        // Select every column from the type and its supertypes that is physically stored in a table
+        let mut select_cols_cmd: String = String::from("t.OID");
+        for (column_oid, column_source_table_oid) in get_flattened_columns(trans, type_oid)? {
+            let source_alias: String = if column_source_table_oid == type_oid { String::from("t") } else { format!("m{column_source_table_oid}") };
+            select_cols_cmd = format!("{select_cols_cmd}, {source_alias}.COLUMN{column_oid} AS COLUMN{column_oid}");
+        }
+
+        // Create the view
+        let create_view_cmd: String = format!("
+            CREATE VIEW TABLE{type_oid}_FLAT 
+            AS 
+            SELECT {select_cols_cmd} 
+            {select_tbls_cmd} 
+            WHERE t.TRASH = 0"
+        );
+        trans.execute(&create_view_cmd, [])?;
+    }
+    return Ok(());
+}
+
+/// Gets the OID and source table OID of each column of an object type and its supertypes that is physically stored in a table.
+fn get_flattened_columns(trans: &Connection, obj_type_oid: i64) -> Result<Vec<(i64, i64)>, error::Error> {
+    let mut columns: Vec<(i64, i64)> = Vec::new();
+    db::query_iterate(trans,
+        "WITH RECURSIVE SUPERTYPE_QUERY (TYPE_OID) AS (
+            SELECT
+                ?1
+            UNION
+            SELECT
+                u.MASTER_TABLE_OID AS TYPE_OID
+            FROM SUPERTYPE_QUERY s
+            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.INHERITOR_TABLE_OID = s.TYPE_OID
+            WHERE u.TRASH = 0
+        )
+        SELECT 
+            c.OID,
+            c.TABLE_OID
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID IN (SELECT * FROM SUPERTYPE_QUERY) AND c.TRASH = 0 AND t.MODE NOT IN (2, 5)
+        ORDER BY c.COLUMN_ORDERING;",
+        params![obj_type_oid], 
+        &mut |row| {
+            columns.push((row.get("OID")?, row.get("TABLE_OID")?));
+            return Ok(());
+        }
+    )?;
+    return Ok(columns);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A single value from the flattened view of an object type.
+pub struct FlatCell {
+    row_oid: i64,
+    column_oid: i64,
+    value: Option<String>
+}
+
+/// Sends every value in the flattened view of an object type through the given channel.
+pub fn send_flattened_data(obj_type_oid: i64, flat_channel: Channel<FlatCell>) -> Result<(), error::Error> {
+    let action = db::begin_readonly_db_action()?;
+
+    // Build a query against the flattened view
+    let columns: Vec<(i64, i64)> = get_flattened_columns(&action, obj_type_oid)?;
+    let mut select_cols_cmd: String = String::from("OID");
+    for (column_oid, _) in columns.iter() {
+        select_cols_cmd = format!("{select_cols_cmd}, CAST(COLUMN{column_oid} AS TEXT) AS COLUMN{column_oid}");
+    }
+    let select_cmd: String = format!("SELECT {select_cols_cmd} FROM TABLE{obj_type_oid}_FLAT ORDER BY OID;");
+
+    // Send each value to the frontend
+    db::query_iterate(&action, 
+        &select_cmd, 
+        [], 
+        &mut |row| {
+            let row_oid: i64 = row.get("OID")?;
+            for (column_oid, _) in columns.iter() {
+                flat_channel.send(FlatCell { 
+                    row_oid, 
+                    column_oid: column_oid.clone(), 
+                    value: row.get(&*format!("COLUMN{column_oid}"))? 
+                })?;
+            }
+            return Ok(());
+        }
+    )?;
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{table_column, test_util};
+
+    #[test]
+    fn flattened_view_joins_supertype_columns() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+
+        // Create a type with a subtype, each with a column of its own
+        let master_oid = create(String::from("Creature"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(master_oid, "Name", text_type.clone(), None, "", true, false, false, None).unwrap();
+        let inheritor_oid = create(String::from("Dragon"), &vec![master_oid]).unwrap();
+        let hoard_column_oid = table_column::create(inheritor_oid, "Hoard", text_type, None, "", true, false, false, None).unwrap();
+
+        // Insert a row of the subtype, along with its supertype data
+        let conn = db::open().unwrap();
+        conn.execute(&format!("INSERT INTO TABLE{master_oid} (OID, COLUMN{name_column_oid}) VALUES (1, 'Smaug');"), []).unwrap();
+        conn.execute(&format!("INSERT INTO TABLE{inheritor_oid} (OID, MASTER{master_oid}_OID, COLUMN{hoard_column_oid}) VALUES (1, 1, 'Gold');"), []).unwrap();
+
+        // Read the row back through the flattened view
+        let (name, hoard): (String, String) = conn.query_one(
+            &format!("SELECT COLUMN{name_column_oid}, COLUMN{hoard_column_oid} FROM TABLE{inheritor_oid}_FLAT WHERE OID = 1;"), 
+            [], 
+            |row| Ok((row.get(0)?, row.get(1)?))
+        ).unwrap();
+        assert_eq!(name, "Smaug");
+        assert_eq!(hoard, "Gold");
+
+        // A type without supertypes is flattened to itself
+        let count: i64 = conn.query_one(&format!("SELECT COUNT(*) FROM TABLE{master_oid}_FLAT;"), [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn integrity_check_allows_diamond_inheritance() {
+        let _db = test_util::open_test_db();
+
+        // Create a type with two subtypes, and a type inheriting from both of them
+        let vehicle_oid = create(String::from("Vehicle"), &vec![]).unwrap();
+        let boat_oid = create(String::from("Boat"), &vec![vehicle_oid]).unwrap();
+        let car_oid = create(String::from("Car"), &vec![vehicle_oid]).unwrap();
+        let amphibian_oid = create(String::from("Amphibian"), &vec![boat_oid, car_oid]).unwrap();
+
+        // Vehicle 1 is an amphibian, reaching it through both subtypes, while vehicle 2 is both a boat and a car without being an amphibian
+        db::open().unwrap().execute_batch(&format!("
+        INSERT INTO TABLE{vehicle_oid} (OID) VALUES (1), (2);
+        INSERT INTO TABLE{boat_oid} (OID, MASTER{vehicle_oid}_OID) VALUES (1, 1), (2, 2);
+        INSERT INTO TABLE{car_oid} (OID, MASTER{vehicle_oid}_OID) VALUES (1, 1), (2, 2);
+        INSERT INTO TABLE{amphibian_oid} (OID, MASTER{boat_oid}_OID, MASTER{car_oid}_OID) VALUES (1, 1, 1);")).unwrap();
+
+        // Only the vehicle whose branches never meet is reported
+        let issues = check_integrity(vehicle_oid).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].type_oid, vehicle_oid);
+        assert_eq!(issues[0].row_oid, 2);
+        let mut expected_subtype_oids = vec![boat_oid, car_oid];
+        expected_subtype_oids.sort();
+        assert_eq!(issues[0].subtype_oids, expected_subtype_oids);
+    }
+}
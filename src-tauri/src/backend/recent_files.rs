@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::util::error;
+
+/// The most database files remembered as recently opened.
+const MAX_RECENT_FILES: usize = 10;
+
+/// The name of the file in the app config directory that stores the recently opened database files.
+const RECENT_FILES_FILE_NAME: &str = "recent_files.json";
+
+/// Reads the list of recently opened database files, most recent first.
+fn read(config_dir: &Path) -> Result<Vec<String>, error::Error> {
+    let list_path: PathBuf = config_dir.join(RECENT_FILES_FILE_NAME);
+    if !list_path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = fs::read_to_string(&list_path)?;
+    return Ok(serde_json::from_str(&contents)?);
+}
+
+/// Writes the list of recently opened database files, most recent first.
+fn write(config_dir: &Path, recent_files: &Vec<String>) -> Result<(), error::Error> {
+    fs::create_dir_all(config_dir)?;
+    fs::write(config_dir.join(RECENT_FILES_FILE_NAME), serde_json::to_string(recent_files)?)?;
+    return Ok(());
+}
+
+/// Records a database file as the most recently opened.
+pub fn add(config_dir: &Path, path: String) -> Result<(), error::Error> {
+    let mut recent_files = read(config_dir)?;
+    recent_files.retain(|recent_path| *recent_path != path);
+    recent_files.insert(0, path);
+    recent_files.truncate(MAX_RECENT_FILES);
+    return write(config_dir, &recent_files);
+}
+
+/// Gets the recently opened database files, most recent first.
+/// Files that no longer exist are forgotten.
+pub fn get(config_dir: &Path) -> Result<Vec<String>, error::Error> {
+    let mut recent_files = read(config_dir)?;
+    let recent_file_count = recent_files.len();
+    recent_files.retain(|recent_path| Path::new(recent_path).exists());
+    if recent_files.len() != recent_file_count {
+        write(config_dir, &recent_files)?;
+    }
+    return Ok(recent_files);
+}
+
+/// Forgets every recently opened database file.
+pub fn clear(config_dir: &Path) -> Result<(), error::Error> {
+    return write(config_dir, &Vec::new());
+}
@@ -2,7 +2,7 @@ use std::cell::Ref;
 use std::collections::HashMap;
 use std::sync::mpsc::channel;
 use rusqlite::fallible_streaming_iterator::FallibleStreamingIterator;
-use rusqlite::{params, Row, Error as RusqliteError, OptionalExtension};
+use rusqlite::{params, Connection, Row, Error as RusqliteError, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 use crate::backend::{data_type, db, table};
@@ -10,9 +10,12 @@ use crate::util::error;
 
 /// Creates a report.
 pub fn create(report_name: &str, base_table_oid: i64) -> Result<i64, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(create_in(&action, report_name, base_table_oid));
+}
 
+/// Creates a report, as part of an ongoing action.
+fn create_in(trans: &Connection, report_name: &str, base_table_oid: i64) -> Result<i64, error::Error> {
     // Create the metadata for the subreport
     trans.execute(
         "INSERT INTO METADATA_RPT DEFAULT VALUES;",
@@ -29,26 +32,47 @@ pub fn create(report_name: &str, base_table_oid: i64) -> Result<i64, error::Erro
 
 /// Flags a report as trash.
 pub fn move_trash(report_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(move_trash_in(&action, report_oid));
+}
 
+/// Flags a report as trash, as part of an ongoing action.
+fn move_trash_in(trans: &Connection, report_oid: i64) -> Result<(), error::Error> {
     // Flag the report as trash
     trans.execute("UPDATE METADATA_RPT SET TRASH = 1 WHERE OID = ?1;", params![report_oid])?;
 
-    // Commit and return
-    trans.commit()?;
     return Ok(());
 }
 
 /// Unflags a table as trash.
 pub fn unmove_trash(report_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(unmove_trash_in(&action, report_oid));
+}
 
+/// Unflags a table as trash, as part of an ongoing action.
+fn unmove_trash_in(trans: &Connection, report_oid: i64) -> Result<(), error::Error> {
     // Flag the table as trash
     trans.execute("UPDATE METADATA_RPT SET TRASH = 0 WHERE OID = ?1;", params![report_oid])?;
 
-    // Commit and return
-    trans.commit()?;
     return Ok(());
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{table, test_util};
+
+    #[test]
+    fn created_reports_are_kept() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Rows"), &vec![]).unwrap();
+
+        // The report is still there once the action is over
+        let report_oid = create("Summary", table_oid).unwrap();
+        let report_name: String = db::open().unwrap()
+            .query_one("SELECT NAME FROM METADATA_RPT__REPORT WHERE RPT_OID = ?1;", params![report_oid], |row| row.get(0))
+            .unwrap();
+        assert_eq!(report_name, "Summary");
+    }
+}
@@ -2,7 +2,7 @@ use std::cell::Ref;
 use std::collections::HashMap;
 use std::sync::mpsc::channel;
 use rusqlite::fallible_streaming_iterator::FallibleStreamingIterator;
-use rusqlite::{params, Row, Error as RusqliteError, OptionalExtension};
+use rusqlite::{params, Connection, Row, Error as RusqliteError, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 use crate::backend::{data_type, db, table};
@@ -26,9 +26,12 @@ pub struct Metadata {
 /// Create a column based on a formula.
 /// This may include columns that are just a static reference to a column in a table.
 pub fn create_formula(report_oid: i64, column_name: &str, column_ordering: Option<i64>, column_style: &str, column_formula: &str) -> Result<i64, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(create_formula_in(&action, report_oid, column_name, column_ordering, column_style, column_formula));
+}
 
+/// Create a column based on a formula, as part of an ongoing action.
+fn create_formula_in(trans: &Connection, report_oid: i64, column_name: &str, column_ordering: Option<i64>, column_style: &str, column_formula: &str) -> Result<i64, error::Error> {
     let column_ordering: i64 = match column_ordering {
         Some(o) => {
             // If an explicit ordering was given, shift every column to its right by 1 in order to make space
@@ -67,9 +70,12 @@ pub fn create_formula(report_oid: i64, column_name: &str, column_ordering: Optio
 
 /// Creates a column that is a report on rows linked to a row in the original report.
 pub fn create_subreport(report_oid: i64, column_name: &str, column_ordering: Option<i64>, column_style: &str, base_parameter_oid: i64) -> Result<(i64, i64), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(create_subreport_in(&action, report_oid, column_name, column_ordering, column_style, base_parameter_oid));
+}
 
+/// Creates a column that is a report on rows linked to a row in the original report, as part of an ongoing action.
+fn create_subreport_in(trans: &Connection, report_oid: i64, column_name: &str, column_ordering: Option<i64>, column_style: &str, base_parameter_oid: i64) -> Result<(i64, i64), error::Error> {
     let column_ordering: i64 = match column_ordering {
         Some(o) => {
             // If an explicit ordering was given, shift every column to its right by 1 in order to make space
@@ -112,27 +118,29 @@ pub fn create_subreport(report_oid: i64, column_name: &str, column_ordering: Opt
 
 /// Flags a column as being trash.
 pub fn move_trash(rpt_oid: i64, column_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(move_trash_in(&action, rpt_oid, column_oid));
+}
 
+/// Flags a column as being trash, as part of an ongoing action.
+fn move_trash_in(trans: &Connection, rpt_oid: i64, column_oid: i64) -> Result<(), error::Error> {
     // Flag the report as trash
     trans.execute("UPDATE METADATA_RPT_COLUMN SET TRASH = 1 WHERE OID = ?1;", params![column_oid])?;
 
-    // Commit and return
-    trans.commit()?;
     return Ok(());
 }
 
 /// Unflags a column as being trash.
 pub fn unmove_trash(table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(unmove_trash_in(&action, table_oid, column_oid));
+}
 
+/// Unflags a column as being trash, as part of an ongoing action.
+fn unmove_trash_in(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
     // Unflag the report as trash
     trans.execute("UPDATE METADATA_RPT_COLUMN SET TRASH = 0 WHERE OID = ?1;", params![column_oid])?;
 
-    // Commit and return
-    trans.commit()?;
     return Ok(());
 }
 
@@ -400,4 +408,4 @@ pub fn send_type_metadata_list(column_type: data_type::MetadataColumnType, type_
         }
     )?;
     return Ok(());
-}
\ No newline at end of file
+}
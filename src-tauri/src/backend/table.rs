@@ -1,11 +1,15 @@
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::i32::MAX;
+use std::num::NonZeroUsize;
 use std::ops::Index;
+use std::sync::Mutex;
+use std::time::Instant;
+use lru::LruCache;
 use rusqlite::fallible_streaming_iterator::FallibleStreamingIterator;
-use rusqlite::{Error as RusqliteError, Row, Transaction, params};
-use serde::Serialize;
+use rusqlite::{Error as RusqliteError, OptionalExtension, Connection, params};
+use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
-use crate::backend::{data_type, db, table};
+use crate::backend::{data_type, db, obj_type, table, table_column};
 use crate::util::error;
 
 
@@ -15,8 +19,17 @@ use crate::util::error;
 
 /// Creates a new table.
 pub fn create(name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(create_in(&action, name, master_table_oid_list));
+    log::debug!("Created table (OID {:?}) in {:?}.", result.as_ref().ok(), start.elapsed());
+    return result;
+}
+
+/// Creates a new table, as part of an ongoing action.
+pub fn create_in(trans: &Connection, name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, error::Error> {
+    // Make sure the name can be used
+    validate_name_in(trans, &name, None)?;
 
     // Add metadata for the table
     trans.execute("INSERT INTO METADATA_TYPE (MODE) VALUES (3);", [])?;
@@ -33,6 +46,7 @@ pub fn create(name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, err
         TRASH INTEGER NOT NULL DEFAULT 0
     ) STRICT;");
     trans.execute(&create_table_cmd, [])?;
+    db::add_order_key(trans, table_oid)?;
 
     // Add inheritance from each master table
     for master_table_oid in master_table_oid_list.iter() {
@@ -49,12 +63,171 @@ pub fn create(name: String, master_table_oid_list: &Vec<i64>) -> Result<i64, err
     
     // Update the surrogate view
     update_surrogate_view(&trans, table_oid.clone())?;
+    db::update_friendly_view(&trans, table_oid)?;
 
-    // Commit the transaction
-    trans.commit()?;
     return Ok(table_oid);
 }
 
+/// Creates a copy of a table under a new name, including its columns, dropdown values, and child tables.
+/// If include_data is true, the rows of the table, its multi-select dropdowns, and its child tables are copied as well.
+/// Reference columns of the copy point to the same tables as the original, and child objects are not copied, as each belongs to a single row.
+/// Returns the OID of the copy.
+pub fn duplicate(table_oid: i64, new_name: String, include_data: bool) -> Result<i64, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(duplicate_in(&action, table_oid, new_name, include_data));
+    log::debug!("Duplicated TABLE{table_oid} (OID {:?}) in {:?}.", result.as_ref().ok(), start.elapsed());
+    return result;
+}
+
+/// Creates a copy of a table under a new name, as part of an ongoing action.
+fn duplicate_in(trans: &Connection, table_oid: i64, new_name: String, include_data: bool) -> Result<i64, error::Error> {
+    // Inherit from the same master tables as the original
+    let mut master_table_oid_list: Vec<i64> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT MASTER_TABLE_OID FROM METADATA_TABLE_INHERITANCE WHERE INHERITOR_TABLE_OID = ?1 AND TRASH = 0 ORDER BY MASTER_TABLE_OID;", 
+        params![table_oid], 
+        &mut |row| {
+            master_table_oid_list.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+    if include_data && master_table_oid_list.len() > 0 {
+        return Err(error::Error::AdhocError("The data of a table that inherits from another table cannot be copied, as each row would share its inherited values with the original."));
+    }
+
+    // Create the copy and fill it in
+    let new_table_oid = create_in(trans, new_name, &master_table_oid_list)?;
+    duplicate_contents_in(trans, table_oid, new_table_oid, include_data, false)?;
+    return Ok(new_table_oid);
+}
+
+/// Copies the columns (and optionally the data) of one table into another, as part of an ongoing action.
+/// Child tables are copied recursively after the rows of their parent table.
+fn duplicate_contents_in(trans: &Connection, source_table_oid: i64, target_table_oid: i64, include_data: bool, is_child_table: bool) -> Result<(), error::Error> {
+    // Collect the columns of the original table
+    let mut source_columns: Vec<(i64, String, data_type::MetadataColumnType, String, bool, bool, bool)> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT 
+            c.OID,
+            c.NAME,
+            c.TYPE_OID,
+            t.MODE,
+            c.COLUMN_CSS_STYLE,
+            c.IS_NULLABLE,
+            c.IS_UNIQUE,
+            c.IS_PRIMARY_KEY
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0
+        ORDER BY c.COLUMN_ORDERING, c.OID;", 
+        params![source_table_oid], 
+        &mut |row| {
+            source_columns.push((
+                row.get("OID")?,
+                row.get("NAME")?,
+                data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?),
+                row.get("COLUMN_CSS_STYLE")?,
+                row.get("IS_NULLABLE")?,
+                row.get("IS_UNIQUE")?,
+                row.get("IS_PRIMARY_KEY")?
+            ));
+            return Ok(());
+        }
+    )?;
+
+    // Recreate each column in the copy, keeping track of how to copy over the data
+    let mut copied_columns: Vec<(String, String)> = vec![(String::from("OID"), String::from("OID")), (String::from("TRASH"), String::from("TRASH")), (String::from("ORDER_KEY"), String::from("ORDER_KEY"))];
+    if is_child_table {
+        copied_columns.push((String::from("PARENT_OID"), String::from("PARENT_OID")));
+    }
+    let mut copied_multiselects: Vec<(i64, i64)> = Vec::new();
+    let mut copied_child_tables: Vec<(i64, i64)> = Vec::new();
+    for (column_oid, column_name, column_type, column_style, is_nullable, is_unique, is_primary_key) in source_columns {
+        // Dropdowns and child tables get their own new types, everything else keeps the type of the original
+        let new_column_type = match &column_type {
+            data_type::MetadataColumnType::SingleSelectDropdown(_) => data_type::MetadataColumnType::SingleSelectDropdown(0),
+            data_type::MetadataColumnType::MultiSelectDropdown(_) => data_type::MetadataColumnType::MultiSelectDropdown(0),
+            data_type::MetadataColumnType::ChildTable(_) => data_type::MetadataColumnType::ChildTable(0),
+            t => t.clone()
+        };
+        let new_column_oid = table_column::create_in(trans, target_table_oid, &column_name, new_column_type, None, &column_style, is_nullable, is_unique, is_primary_key)?;
+
+        // Copy over the rest of the column's metadata
+        trans.execute(
+            "UPDATE METADATA_TABLE_COLUMN
+            SET 
+                COLUMN_ORDERING = s.COLUMN_ORDERING,
+                IS_PINNED = s.IS_PINNED,
+                AUTO_UPDATE = s.AUTO_UPDATE,
+                DEFAULT_VALUE = s.DEFAULT_VALUE,
+                DECIMAL_PLACES = s.DECIMAL_PLACES
+            FROM (SELECT COLUMN_ORDERING, IS_PINNED, AUTO_UPDATE, DEFAULT_VALUE, DECIMAL_PLACES FROM METADATA_TABLE_COLUMN WHERE OID = ?1) AS s
+            WHERE METADATA_TABLE_COLUMN.OID = ?2;", 
+            params![column_oid, new_column_oid]
+        )?;
+        let new_column_type_oid: i64 = trans.query_one(
+            "SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", 
+            params![new_column_oid], 
+            |row| row.get(0)
+        )?;
+
+        match column_type {
+            data_type::MetadataColumnType::Primitive(prim) => {
+                copied_columns.push((format!("COLUMN{column_oid}"), format!("COLUMN{new_column_oid}")));
+                if prim.is_file() {
+                    copied_columns.push((format!("COLUMN{column_oid}_NAME"), format!("COLUMN{new_column_oid}_NAME")));
+                    copied_columns.push((format!("COLUMN{column_oid}_MIME"), format!("COLUMN{new_column_oid}_MIME")));
+                }
+            },
+            data_type::MetadataColumnType::Reference(_) => {
+                copied_columns.push((format!("COLUMN{column_oid}"), format!("COLUMN{new_column_oid}")));
+            },
+            data_type::MetadataColumnType::ChildObject(_) => {
+                // Each child object belongs to a single row, so it is not shared with the copy
+            },
+            data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
+                // Copy the dropdown values, keeping their OIDs so that the copied data still points at the right values
+                let copy_values_cmd = format!("INSERT INTO TABLE{new_column_type_oid} (OID, TRASH, VALUE, ORDERING) SELECT OID, TRASH, VALUE, ORDERING FROM TABLE{column_type_oid};");
+                trans.execute(&copy_values_cmd, [])?;
+                copied_columns.push((format!("COLUMN{column_oid}"), format!("COLUMN{new_column_oid}")));
+            },
+            data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+                // Copy the dropdown values, keeping their OIDs so that the copied selections still point at the right values
+                let copy_values_cmd = format!("INSERT INTO TABLE{new_column_type_oid} (OID, TRASH, VALUE, ORDERING) SELECT OID, TRASH, VALUE, ORDERING FROM TABLE{column_type_oid};");
+                trans.execute(&copy_values_cmd, [])?;
+                copied_multiselects.push((column_type_oid, new_column_type_oid));
+            },
+            data_type::MetadataColumnType::ChildTable(column_type_oid) => {
+                copied_child_tables.push((column_type_oid, new_column_type_oid));
+            }
+        }
+    }
+
+    // Copy the rows of the table
+    if include_data {
+        let source_cols: Vec<&str> = copied_columns.iter().map(|(s, _)| s.as_str()).collect();
+        let target_cols: Vec<&str> = copied_columns.iter().map(|(_, t)| t.as_str()).collect();
+        let copy_rows_cmd = format!("INSERT INTO TABLE{target_table_oid} ({}) SELECT {} FROM TABLE{source_table_oid};", target_cols.join(", "), source_cols.join(", "));
+        trans.execute(&copy_rows_cmd, [])?;
+
+        for (column_type_oid, new_column_type_oid) in copied_multiselects.iter() {
+            let copy_selections_cmd = format!("INSERT INTO TABLE{new_column_type_oid}_MULTISELECT (ROW_OID, VALUE_OID) SELECT ROW_OID, VALUE_OID FROM TABLE{column_type_oid}_MULTISELECT;");
+            trans.execute(&copy_selections_cmd, [])?;
+        }
+    }
+
+    // Copy the child tables, now that the rows they belong to exist
+    for (column_type_oid, new_column_type_oid) in copied_child_tables {
+        duplicate_contents_in(trans, column_type_oid, new_column_type_oid, include_data, true)?;
+    }
+
+    // Recompute the display values of the copy
+    update_surrogate_view(trans, target_table_oid)?;
+    refresh_display_value_cache(trans, target_table_oid, None)?;
+    return Ok(());
+}
+
 
 
 
@@ -76,8 +249,100 @@ impl Ord for TableDependency {
     }
 }
 
+/// Rebuilds the surrogate view of every table, such as after the views fell out of sync with the tables.
+/// Returns the number of views rebuilt.
+pub fn rebuild_all_surrogate_views() -> Result<i64, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(rebuild_all_surrogate_views_in(&action));
+}
+
+/// Rebuilds the surrogate view of every table, as part of an ongoing action.
+pub fn rebuild_all_surrogate_views_in(trans: &Connection) -> Result<i64, error::Error> {
+    let start = Instant::now();
+
+    // Find every table and the tables whose surrogate views its own surrogate view is built from
+    let mut dependencies: HashMap<i64, HashSet<i64>> = HashMap::new();
+    db::query_iterate(trans, 
+        "SELECT TYPE_OID FROM METADATA_TABLE WHERE TRASH = 0;", 
+        [], 
+        &mut |row| {
+            dependencies.insert(row.get(0)?, HashSet::new());
+            return Ok(());
+        }
+    )?;
+    db::query_iterate(trans, 
+        "SELECT 
+            c.TABLE_OID,
+            c.TYPE_OID
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TRASH = 0 AND c.IS_PRIMARY_KEY = 1 AND t.MODE IN (3, 4, 5) AND c.TABLE_OID <> c.TYPE_OID;", 
+        [], 
+        &mut |row| {
+            let table_oid: i64 = row.get("TABLE_OID")?;
+            let referenced_table_oid: i64 = row.get("TYPE_OID")?;
+            if let Some(table_dependencies) = dependencies.get_mut(&table_oid) {
+                table_dependencies.insert(referenced_table_oid);
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Drop every surrogate view
+    for table_oid in dependencies.keys() {
+        let drop_view_cmd = format!("DROP VIEW IF EXISTS TABLE{table_oid}_SURROGATE;");
+        trans.execute(&drop_view_cmd, [])?;
+    }
+
+    // Recreate each surrogate view once the views it is built from have been recreated
+    let mut rebuilt_table_oids: HashSet<i64> = HashSet::new();
+    while rebuilt_table_oids.len() < dependencies.len() {
+        let ready_table_oids: Vec<i64> = dependencies.iter()
+            .filter(|(table_oid, table_dependencies)| {
+                !rebuilt_table_oids.contains(table_oid) 
+                && table_dependencies.iter().all(|dep| rebuilt_table_oids.contains(dep) || !dependencies.contains_key(dep))
+            })
+            .map(|(table_oid, _)| *table_oid)
+            .collect();
+        if ready_table_oids.is_empty() {
+            return Err(error::Error::AdhocError("There is an infinite loop of primary keys that reference each other!"));
+        }
+        for table_oid in ready_table_oids {
+            create_surrogate_view(trans, table_oid)?;
+            refresh_display_value_cache(trans, table_oid, None)?;
+            rebuilt_table_oids.insert(table_oid);
+        }
+    }
+
+    log::debug!("Rebuilt {} surrogate views in {:?}.", rebuilt_table_oids.len(), start.elapsed());
+    return Ok(rebuilt_table_oids.len() as i64);
+}
+
+/// Makes sure that the surrogate view of a table exists, rebuilding it if it went missing.
+pub fn ensure_surrogate_view_in(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    let view_name = format!("TABLE{table_oid}_SURROGATE");
+    let view_exists: bool = trans.prepare_cached("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'view' AND name = ?1);")?
+        .query_one(params![view_name], |row| row.get(0))?;
+    if view_exists {
+        return Ok(());
+    }
+
+    // Rebuild the missing view
+    log::warn!("The surrogate view of TABLE{table_oid} is missing, so it will be rebuilt.");
+    return match create_surrogate_view(trans, table_oid) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            let msg: String = e.into();
+            log::warn!("Could not rebuild the surrogate view of TABLE{table_oid}: {msg}");
+            Err(error::Error::AdhocError("The display values of a referenced table are missing and could not be rebuilt. Repair the database to rebuild them."))
+        }
+    };
+}
+
 /// Update the surrogate view for the table.
-pub fn update_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(), error::Error> {
+pub fn update_surrogate_view(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    let start = Instant::now();
+
     // Drop the surrogate view and build up a directed graph of dependencies between the primary keys
     let empty_chain: Vec<i64> = Vec::new();
     let dependencies = drop_surrogate_view(trans, table_oid, &empty_chain)?;
@@ -96,24 +361,347 @@ pub fn update_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(),
         match heap.pop() {
             Some(dep) => {
                 create_surrogate_view(trans, dep.table_oid)?;
+                refresh_display_value_cache(trans, dep.table_oid, None)?;
             },
             None => {
                 break;
             }
         }
     }
+    log::debug!("Updated surrogate views for TABLE{table_oid} and its dependents in {:?}.", start.elapsed());
+    return Ok(());
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// What happens to the rows of a table when they are deleted.
+pub enum DeleteMode {
+    /// Rows are moved to the trash, and can be recovered.
+    Soft,
+    /// Rows are deleted permanently, and can only be recovered by undoing the deletion.
+    Hard
+}
+
+/// Gets what happens to the rows of a table when they are deleted.
+pub fn get_delete_mode(table_oid: i64) -> Result<DeleteMode, error::Error> {
+    let conn = db::open()?;
+    let delete_mode: i64 = conn.query_one(
+        "SELECT DELETE_MODE FROM METADATA_TABLE WHERE TYPE_OID = ?1;", 
+        params![table_oid], 
+        |row| row.get(0)
+    )?;
+    return Ok(if delete_mode == 1 { DeleteMode::Hard } else { DeleteMode::Soft });
+}
+
+/// Sets what happens to the rows of a table when they are deleted.
+pub fn set_delete_mode(table_oid: i64, delete_mode: DeleteMode) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    let delete_mode_bit: i64 = if delete_mode == DeleteMode::Hard { 1 } else { 0 };
+    action.execute(
+        "UPDATE METADATA_TABLE SET DELETE_MODE = ?1 WHERE TYPE_OID = ?2;", 
+        params![delete_mode_bit, table_oid]
+    )?;
+    return Ok(());
+}
+
+/// Checks whether the display value of each row of the table is cached in a DISPLAY_VALUE_CACHE column.
+pub fn is_display_value_materialized(trans: &Connection, table_oid: i64) -> Result<bool, error::Error> {
+    return Ok(trans.prepare_cached("SELECT MATERIALIZE_DISPLAY_VALUE FROM METADATA_TABLE WHERE TYPE_OID = ?1;")?
+        .query_row(params![table_oid], |row| row.get::<_, bool>(0))
+        .optional()?
+        .unwrap_or(false));
+}
+
+/// Drops the unique index enforcing the primary key of a table, if it has one.
+pub fn drop_primary_key_index(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    let drop_cmd = format!("DROP INDEX IF EXISTS TABLE{table_oid}_PRIMARY_KEY;");
+    trans.execute(&drop_cmd, [])?;
+    return Ok(());
+}
+
+/// Rebuilds the unique index enforcing the primary key of a table across all of its primary key columns that are stored in the table itself.
+/// If the existing data already has rows sharing a primary key, the index is not created, and the rows are only flagged when the table is read.
+pub fn update_primary_key_index(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    drop_primary_key_index(trans, table_oid)?;
+
+    // Find the primary key columns stored as a column of the table (i.e. not multi-select dropdowns or child tables)
+    let mut key_columns: Vec<String> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT c.OID 
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0 AND c.IS_PRIMARY_KEY = 1 AND t.MODE IN (0, 1, 3, 4)
+        ORDER BY c.COLUMN_ORDERING, c.OID;", 
+        params![table_oid], 
+        &mut |row| {
+            key_columns.push(format!("COLUMN{}", row.get::<_, i64>(0)?));
+            return Ok(());
+        }
+    )?;
+    if key_columns.len() == 0 {
+        return Ok(());
+    }
+
+    // The key of an object type continues into the key of its supertypes, which cannot be covered by an index on this table,
+    // so index the links to the supertype rows alongside the table's own key columns instead
+    db::query_iterate(trans, 
+        "SELECT MASTER_TABLE_OID FROM METADATA_TABLE_INHERITANCE WHERE INHERITOR_TABLE_OID = ?1 AND TRASH = 0 ORDER BY MASTER_TABLE_OID;", 
+        params![table_oid], 
+        &mut |row| {
+            key_columns.push(format!("MASTER{}_OID", row.get::<_, i64>(0)?));
+            return Ok(());
+        }
+    )?;
+
+    // Create the index, unless existing rows already violate it
+    let create_cmd = format!("CREATE UNIQUE INDEX TABLE{table_oid}_PRIMARY_KEY ON TABLE{table_oid} ({}) WHERE TRASH = 0;", key_columns.join(", "));
+    match trans.execute(&create_cmd, []) {
+        Ok(_) => {},
+        Err(RusqliteError::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+            log::warn!("Rows of TABLE{table_oid} share a primary key, so it will not be enforced until they are fixed.");
+        },
+        Err(e) => {
+            return Err(e.into());
+        }
+    }
+    return Ok(());
+}
+
+/// The number of display values kept in memory unless configured otherwise.
+const DEFAULT_SURROGATE_CACHE_SIZE: usize = 10000;
+
+/// Display values of rows that were recently looked up, keyed by table OID and row OID.
+/// The cache is created on first use, and is absent while its size is configured as 0.
+static SURROGATE_CACHE: Mutex<Option<LruCache<(i64, i64), Option<String>>>> = Mutex::new(None);
+
+/// The number of display values kept in memory.
+static SURROGATE_CACHE_SIZE: Mutex<usize> = Mutex::new(DEFAULT_SURROGATE_CACHE_SIZE);
+
+/// Runs a function on the cache of display values, if caching is turned on.
+fn with_surrogate_cache<F: FnOnce(&mut LruCache<(i64, i64), Option<String>>)>(f: F) {
+    let mut cache = SURROGATE_CACHE.lock().unwrap();
+    if cache.is_none() {
+        match NonZeroUsize::new(*SURROGATE_CACHE_SIZE.lock().unwrap()) {
+            Some(size) => {
+                *cache = Some(LruCache::new(size));
+            },
+            None => {
+                return;
+            }
+        }
+    }
+    f(cache.as_mut().unwrap());
+}
+
+/// Sets the number of display values kept in memory, discarding the least recently used values if the cache shrinks.
+/// A size of 0 turns off caching.
+pub fn set_surrogate_cache_size(size: usize) {
+    *SURROGATE_CACHE_SIZE.lock().unwrap() = size;
+    let mut cache = SURROGATE_CACHE.lock().unwrap();
+    match NonZeroUsize::new(size) {
+        Some(size) => {
+            if let Some(c) = cache.as_mut() {
+                c.resize(size);
+            }
+        },
+        None => {
+            *cache = None;
+        }
+    }
+}
+
+/// Discards every display value kept in memory.
+pub fn clear_surrogate_cache() {
+    if let Some(c) = SURROGATE_CACHE.lock().unwrap().as_mut() {
+        c.clear();
+    }
+}
+
+/// Discards the display values kept in memory for a row of a table, or for every row if no row OID is provided.
+/// The display values of every table that refers to the table, directly or through another table, are discarded as well,
+/// as they may be built from the discarded display values.
+pub fn invalidate_surrogate_cache(trans: &Connection, table_oid: i64, row_oid: Option<i64>) -> Result<(), error::Error> {
+    if SURROGATE_CACHE.lock().unwrap().as_ref().map_or(true, |c| c.is_empty()) {
+        return Ok(());
+    }
+
+    // Find every table whose display values may be built from those of this table
+    let mut dependent_table_oids: HashSet<i64> = HashSet::new();
+    db::query_iterate(trans, 
+        "WITH RECURSIVE DEPENDENT_TABLE (TABLE_OID) AS (
+            SELECT ?1
+            UNION
+            SELECT c.TABLE_OID FROM METADATA_TABLE_COLUMN c INNER JOIN DEPENDENT_TABLE d ON d.TABLE_OID = c.TYPE_OID
+            UNION
+            SELECT i.INHERITOR_TABLE_OID FROM METADATA_TABLE_INHERITANCE i INNER JOIN DEPENDENT_TABLE d ON d.TABLE_OID = i.MASTER_TABLE_OID
+        )
+        SELECT TABLE_OID FROM DEPENDENT_TABLE WHERE TABLE_OID <> ?1;", 
+        params![table_oid], 
+        &mut |row| {
+            dependent_table_oids.insert(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+
+    // Discard the affected display values
+    with_surrogate_cache(|cache| {
+        let stale_keys: Vec<(i64, i64)> = cache.iter()
+            .map(|(key, _)| *key)
+            .filter(|(cached_table_oid, cached_row_oid)| {
+                dependent_table_oids.contains(cached_table_oid) 
+                || (*cached_table_oid == table_oid && row_oid.map_or(true, |r| r == *cached_row_oid))
+            })
+            .collect();
+        for key in stale_keys.iter() {
+            cache.pop(key);
+        }
+    });
+    return Ok(());
+}
+
+/// Gets the display value of a single row of a table, as shown wherever the row is referenced.
+pub fn get_surrogate_display(table_oid: i64, row_oid: i64) -> Result<Option<String>, error::Error> {
+    // Check for a display value kept in memory
+    let mut cached_display_value: Option<Option<String>> = None;
+    with_surrogate_cache(|cache| {
+        cached_display_value = cache.get(&(table_oid, row_oid)).cloned();
+    });
+    if let Some(display_value) = cached_display_value {
+        return Ok(display_value);
+    }
+
+    // Otherwise, look up the display value from the surrogate view
+    let conn = db::open()?;
+    let select_cmd = format!("SELECT DISPLAY_VALUE FROM TABLE{table_oid}_SURROGATE WHERE OID = ?1;");
+    let display_value = conn.query_one(&select_cmd, params![row_oid], |row| row.get::<_, Option<String>>(0))
+        .optional()?
+        .flatten();
+    with_surrogate_cache(|cache| {
+        cache.put((table_oid, row_oid), display_value.clone());
+    });
+    return Ok(display_value);
+}
+
+/// The most rows whose display values can be looked up at once, which stays below SQLite's limit on the number of bound parameters.
+const MAX_SURROGATE_LOOKUP: usize = 999;
+
+/// Gets the display values of several rows of a table at once, keyed by row OID.
+/// Rows that do not exist or have no display value are left out.
+pub fn get_surrogate_displays(table_oid: i64, row_oids: Vec<i64>) -> Result<HashMap<i64, String>, error::Error> {
+    let mut display_values: HashMap<i64, String> = HashMap::new();
+    if row_oids.len() == 0 {
+        return Ok(display_values);
+    }
+    if row_oids.len() > MAX_SURROGATE_LOOKUP {
+        return Err(error::Error::AdhocError("Too many rows were requested at once."));
+    }
+
+    // Use any display values kept in memory
+    let mut uncached_row_oids: Vec<i64> = row_oids;
+    with_surrogate_cache(|cache| {
+        uncached_row_oids.retain(|row_oid| {
+            return match cache.get(&(table_oid, *row_oid)) {
+                Some(Some(display_value)) => {
+                    display_values.insert(*row_oid, display_value.clone());
+                    false
+                },
+                Some(None) => false,
+                None => true
+            };
+        });
+    });
+    if uncached_row_oids.len() == 0 {
+        return Ok(display_values);
+    }
+
+    // Look up the remaining display values from the surrogate view
+    let conn = db::open()?;
+    let placeholders: Vec<&str> = uncached_row_oids.iter().map(|_| "?").collect();
+    let select_cmd = format!("SELECT OID, DISPLAY_VALUE FROM TABLE{table_oid}_SURROGATE WHERE OID IN ({});", placeholders.join(", "));
+    let mut looked_up_display_values: HashMap<i64, Option<String>> = HashMap::new();
+    db::query_iterate(&conn, &select_cmd, rusqlite::params_from_iter(uncached_row_oids.iter()), 
+        &mut |row| {
+            looked_up_display_values.insert(row.get(0)?, row.get(1)?);
+            return Ok(());
+        }
+    )?;
+
+    // Keep the display values in memory for later lookups
+    with_surrogate_cache(|cache| {
+        for (row_oid, display_value) in looked_up_display_values.iter() {
+            cache.put((table_oid, *row_oid), display_value.clone());
+        }
+    });
+    for (row_oid, display_value) in looked_up_display_values.into_iter() {
+        if let Some(display_value) = display_value {
+            display_values.insert(row_oid, display_value);
+        }
+    }
+    return Ok(display_values);
+}
+
+/// Recomputes the cached display values of a table from its surrogate view, if the table caches its display values,
+/// and discards any of its display values kept in memory.
+/// If a row OID is provided, only the display value of that row is recomputed.
+pub fn refresh_display_value_cache(trans: &Connection, table_oid: i64, row_oid: Option<i64>) -> Result<(), error::Error> {
+    invalidate_surrogate_cache(trans, table_oid, row_oid)?;
+    if !is_display_value_materialized(trans, table_oid)? {
+        return Ok(());
+    }
+    let update_cmd = format!("
+    UPDATE TABLE{table_oid} 
+    SET DISPLAY_VALUE_CACHE = (SELECT s.DISPLAY_VALUE FROM TABLE{table_oid}_SURROGATE s WHERE s.OID = TABLE{table_oid}.OID)
+    WHERE ?1 IS NULL OR OID = ?1;");
+    trans.execute(&update_cmd, params![row_oid])?;
+    return Ok(());
+}
+
+/// Turns on or off the caching of display values for a table.
+/// 
+/// While on, the display value of each row is stored in a DISPLAY_VALUE_CACHE column of the table, which columns referencing the table read directly instead of evaluating the surrogate view.
+/// The cache is recomputed whenever a row of the table is edited or the table's primary key changes.
+/// However, it is not recomputed when a row of another table that this table's primary key references is edited, so the display values may be stale until the table is next edited or caching is toggled.
+pub fn set_display_value_materialized(table_oid: i64, materialize: bool) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_display_value_materialized_in(&action, table_oid, materialize));
+}
+
+/// Turns on or off the caching of display values for a table, as part of an ongoing action.
+fn set_display_value_materialized_in(trans: &Connection, table_oid: i64, materialize: bool) -> Result<(), error::Error> {
+    if is_display_value_materialized(trans, table_oid)? == materialize {
+        return Ok(());
+    }
+
+    if materialize {
+        // Add the cache column to the table
+        let alter_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN DISPLAY_VALUE_CACHE TEXT;");
+        trans.execute(&alter_cmd, [])?;
+
+        // Flag the table as caching its display values
+        trans.execute("UPDATE METADATA_TABLE SET MATERIALIZE_DISPLAY_VALUE = 1 WHERE TYPE_OID = ?1;", params![table_oid])?;
+
+        // Populate the cache
+        refresh_display_value_cache(trans, table_oid, None)?;
+    } else {
+        // Unflag the table
+        trans.execute("UPDATE METADATA_TABLE SET MATERIALIZE_DISPLAY_VALUE = 0 WHERE TYPE_OID = ?1;", params![table_oid])?;
+
+        // Drop the cache column from the table
+        let alter_cmd = format!("ALTER TABLE TABLE{table_oid} DROP COLUMN DISPLAY_VALUE_CACHE;");
+        trans.execute(&alter_cmd, [])?;
+    }
     return Ok(());
 }
 
 /// Drops the surrogate view for the specified table, as well as the surrogate views for any table referencing it in its primary key.
-fn drop_surrogate_view(trans: &Transaction, table_oid: i64, above_table_oid: &Vec<i64>) -> Result<HashMap<i64, i32>, error::Error> {
+fn drop_surrogate_view(trans: &Connection, table_oid: i64, above_table_oid: &Vec<i64>) -> Result<HashMap<i64, i32>, error::Error> {
     let mut found_dependencies: HashMap<i64, i32> = HashMap::new();
     found_dependencies.insert(table_oid, 0);
     let mut above_table_oid = above_table_oid.clone();
     above_table_oid.push(table_oid);
 
     // Query to find all tables dependent on the one being dropped
-    for dependent_table_oid_result in trans.prepare("SELECT TABLE_OID FROM METADATA_TABLE_COLUMN WHERE TYPE_OID = ?1 AND IS_PRIMARY_KEY = 1")?
+    for dependent_table_oid_result in trans.prepare_cached("SELECT TABLE_OID FROM METADATA_TABLE_COLUMN WHERE TYPE_OID = ?1 AND IS_PRIMARY_KEY = 1")?
         .query_and_then(
             params![table_oid], 
             |row| {
@@ -155,7 +743,7 @@ fn drop_surrogate_view(trans: &Transaction, table_oid: i64, above_table_oid: &Ve
     return Ok(found_dependencies);
 }
 
-fn create_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(), error::Error> {
+fn create_surrogate_view(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
     let mut select_tbls_cmd: String = format!("FROM TABLE{table_oid} t");
     struct PrimaryKey {
         single_expr: String,
@@ -165,7 +753,7 @@ fn create_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(), erro
     let mut tbl_count: i64 = 1;
 
     // Iterate over all columns of the table, building up the table's view
-    db::query_iterate(trans, 
+    db::query_iterate_cached(trans, 
         "SELECT
             c.OID,
             c.NAME,
@@ -211,6 +799,12 @@ fn create_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(), erro
                                 json_expr: format!("'{json_column_name}: ' || CASE WHEN t.COLUMN{column_oid} IS NOT NULL THEN CAST(t.COLUMN{column_oid} AS TEXT) ELSE 'null' END")
                             });
                         },
+                        data_type::Primitive::StringList => {
+                            select_display_value.push(PrimaryKey { 
+                                single_expr: format!("(SELECT '[' || GROUP_CONCAT(j.value) || ']' FROM json_each(t.COLUMN{column_oid}) j)"), 
+                                json_expr: format!("'{json_column_name}: ' || COALESCE(t.COLUMN{column_oid}, 'null')")
+                            });
+                        },
                         data_type::Primitive::Date => {
                             select_display_value.push(PrimaryKey { 
                                 single_expr: format!("DATE(t.COLUMN{column_oid}, 'unixepoch')"), 
@@ -281,12 +875,18 @@ fn create_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(), erro
         String::from("'— NO PRIMARY KEY —'")
     };
 
+    // Keep the link from each row of a child table to its parent row, which the display value of the parent row is built from
+    let has_parent_oid: bool = trans.prepare_cached("SELECT EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || ?1) WHERE name = 'PARENT_OID');")?
+        .query_one(params![table_oid], |row| row.get(0))?;
+    let select_parent_oid: &str = if has_parent_oid { "t.PARENT_OID," } else { "" };
+
     // Create the new surrogate view
     let create_view_cmd: String = format!("
         CREATE VIEW TABLE{table_oid}_SURROGATE 
         AS 
         SELECT
             t.OID,
+            {select_parent_oid}
             CASE
                 WHEN t.TRASH = 0 THEN {standard_display_value}
                 ELSE '— DELETED —'
@@ -297,42 +897,180 @@ fn create_surrogate_view(trans: &Transaction, table_oid: i64) -> Result<(), erro
             END AS JSON_DISPLAY_VALUE
         {select_tbls_cmd}"
     );
-    println!("{}", create_view_cmd);
+    log::debug!("Creating surrogate view for TABLE{table_oid}: {create_view_cmd}");
     trans.execute(&create_view_cmd, params![])?;
     return Ok(());
 }
 
+/// Checks that a name can be given to a table or object type, as part of an ongoing action.
+/// The name cannot be blank, cannot look like the internal name of a table (e.g. "TABLE5"), and cannot already be used by another table or object type.
+pub fn validate_name_in(trans: &Connection, name: &str, table_oid: Option<i64>) -> Result<(), error::Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(error::Error::AdhocError("The name of a table cannot be blank."));
+    }
+
+    // Reject names of the form TABLE{oid}, which are used internally and given to dropdown and child tables
+    let upper_name = name.to_ascii_uppercase();
+    if let Some(suffix) = upper_name.strip_prefix("TABLE") {
+        if !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) {
+            return Err(error::Error::AdhocError("The name of a table cannot be TABLE followed by a number, as such names are reserved for internal use."));
+        }
+    }
+
+    // Reject names already in use by another user table or object type
+    let duplicate_count: i64 = trans.query_one(
+        "SELECT COUNT(*) 
+        FROM METADATA_TABLE tbl
+        INNER JOIN METADATA_TYPE typ ON typ.OID = tbl.TYPE_OID
+        WHERE typ.MODE IN (3, 4) AND tbl.TRASH = 0 AND TRIM(tbl.NAME) = ?1 COLLATE NOCASE AND (?2 IS NULL OR tbl.TYPE_OID <> ?2);", 
+        params![name, table_oid], 
+        |row| row.get(0)
+    )?;
+    if duplicate_count > 0 {
+        return Err(error::Error::AdhocError("Another table or object type already has that name."));
+    }
+    return Ok(());
+}
+
+/// Renames a table.
+/// Returns the previous name of the table.
+pub fn rename(table_oid: i64, name: String) -> Result<String, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(rename_in(&action, table_oid, name));
+}
+
+/// Renames a table, as part of an ongoing action.
+fn rename_in(trans: &Connection, table_oid: i64, name: String) -> Result<String, error::Error> {
+    // Make sure the name can be used
+    validate_name_in(trans, &name, Some(table_oid))?;
+
+    // Change the name in the metadata
+    let prior_name: String = trans.query_one(
+        "SELECT NAME FROM METADATA_TABLE WHERE TYPE_OID = ?1;", 
+        params![table_oid], 
+        |row| row.get(0)
+    )?;
+    trans.execute(
+        "UPDATE METADATA_TABLE SET NAME = ?1 WHERE TYPE_OID = ?2;", 
+        params![name, table_oid]
+    )?;
+
+    // Rename the friendly view to match
+    db::update_friendly_view(trans, table_oid)?;
+    return Ok(prior_name);
+}
+
+/// Makes exactly the given columns the primary key of a table, rebuilding the surrogate views once.
+/// Each of the columns must not allow NULL values.
+/// Returns the columns that were previously the primary key.
+pub fn set_primary_key(table_oid: i64, column_oids: Vec<i64>) -> Result<Vec<i64>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_primary_key_in(&action, table_oid, &column_oids, true));
+}
+
+/// Makes the columns that were previously the primary key of a table its primary key again, without checking them.
+/// Returns the columns that were the primary key before they were restored.
+pub fn restore_primary_key(table_oid: i64, column_oids: Vec<i64>) -> Result<Vec<i64>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_primary_key_in(&action, table_oid, &column_oids, false));
+}
+
+/// Makes exactly the given columns the primary key of a table, as part of an ongoing action.
+fn set_primary_key_in(trans: &Connection, table_oid: i64, column_oids: &Vec<i64>, validate: bool) -> Result<Vec<i64>, error::Error> {
+    // Get the current primary key, and check that each of the given columns can be part of it
+    let mut prior_column_oids: Vec<i64> = Vec::new();
+    let mut remaining_column_oids: HashSet<i64> = column_oids.iter().cloned().collect();
+    db::query_iterate(trans, 
+        "SELECT OID, IS_NULLABLE, IS_PRIMARY_KEY FROM METADATA_TABLE_COLUMN WHERE TABLE_OID = ?1 AND TRASH = 0 ORDER BY OID;", 
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            if row.get::<_, bool>("IS_PRIMARY_KEY")? {
+                prior_column_oids.push(column_oid);
+            }
+            if remaining_column_oids.remove(&column_oid) && validate && row.get::<_, bool>("IS_NULLABLE")? {
+                return Err(error::Error::AdhocError("A column that allows NULL values cannot be part of the primary key."));
+            }
+            return Ok(());
+        }
+    )?;
+    if remaining_column_oids.len() > 0 {
+        return Err(error::Error::AdhocError("A column of the primary key does not belong to the table."));
+    }
+
+    // Flag exactly the given columns as the primary key
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET IS_PRIMARY_KEY = 0 WHERE TABLE_OID = ?1 AND IS_PRIMARY_KEY = 1;", 
+        params![table_oid]
+    )?;
+    for column_oid in column_oids.iter() {
+        trans.execute(
+            "UPDATE METADATA_TABLE_COLUMN SET IS_PRIMARY_KEY = 1 WHERE OID = ?1;", 
+            params![column_oid]
+        )?;
+    }
+
+    // Rebuild the surrogate views and the primary key index
+    update_surrogate_view(trans, table_oid)?;
+    update_primary_key_index(trans, table_oid)?;
+    return Ok(prior_column_oids);
+}
+
 /// Flags a table as trash.
 pub fn move_trash(table_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(move_trash_in(&action, table_oid));
+}
 
+/// Flags a table as trash, as part of an ongoing action.
+pub fn move_trash_in(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
     // Flag the table as trash
     trans.execute("UPDATE METADATA_TABLE SET TRASH = 1 WHERE TYPE_OID = ?1;", params![table_oid])?;
 
-    // Commit and return
-    trans.commit()?;
+    // Drop the friendly view of the table
+    db::drop_friendly_view(trans, table_oid)?;
+
     return Ok(());
 }
 
+/// Counts the rows of a table that are not in the trash.
+pub fn count_rows_in(trans: &Connection, table_oid: i64) -> Result<i64, error::Error> {
+    let count_cmd = format!("SELECT COUNT(*) FROM TABLE{table_oid} WHERE TRASH = 0;");
+    return Ok(trans.query_one(&count_cmd, [], |row| row.get(0))?);
+}
+
 /// Unflags a table as trash.
 pub fn unmove_trash(table_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(unmove_trash_in(&action, table_oid));
+}
 
+/// Unflags a table as trash, as part of an ongoing action.
+fn unmove_trash_in(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
     // Flag the table as trash
     trans.execute("UPDATE METADATA_TABLE SET TRASH = 0 WHERE TYPE_OID = ?1;", params![table_oid])?;
 
-    // Commit and return
-    trans.commit()?;
+    // Recreate the friendly view of the table
+    db::update_friendly_view(trans, table_oid)?;
+
     return Ok(());
 }
 
 /// Deletes the table with the given OID and all associated local columns.
 /// Generally, this function should only be called after the table has been flagged as trash for reasonably long enough that the user could undo it if they wanted to.
 pub fn delete(table_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(delete_in(&action, table_oid));
+    log::debug!("Deleted TABLE{table_oid} in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Deletes the table with the given OID and all associated local columns, as part of an ongoing action.
+fn delete_in(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    // Drop the friendly view of the table
+    db::drop_friendly_view(trans, table_oid)?;
 
     // Drop data from the table
     let drop_cmd: String = format!("DROP TABLE IF EXISTS TABLE{table_oid};");
@@ -358,6 +1096,12 @@ pub fn delete(table_oid: i64) -> Result<(), error::Error> {
         )?;
     }
 
+    // Clear the saved view state of the table
+    trans.execute(
+        "DELETE FROM METADATA_TABLE_VIEW_STATE WHERE TABLE_OID = ?1;", 
+        params![table_oid]
+    )?;
+
     // Drop any of the table's single-select dropdown value tables
     // TODO
 
@@ -423,6 +1167,115 @@ pub fn send_metadata_list(table_channel: Channel<BasicMetadata>) -> Result<(), e
     return Ok(());
 }
 
+/// Gets the tables and object types that have no primary key column, and so are shown as "— NO PRIMARY KEY —" wherever they are referenced.
+pub fn tables_without_primary_key() -> Result<Vec<BasicMetadata>, error::Error> {
+    let conn = db::open()?;
+    return tables_without_primary_key_in(&conn);
+}
+
+/// Gets the tables and object types that have no primary key column, as part of an ongoing action.
+pub fn tables_without_primary_key_in(trans: &Connection) -> Result<Vec<BasicMetadata>, error::Error> {
+    let mut tables: Vec<BasicMetadata> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT 
+            tbl.OID, 
+            tbl.NAME 
+        FROM METADATA_TABLE tbl
+        INNER JOIN METADATA_TYPE typ ON typ.OID = tbl.OID
+        WHERE tbl.TRASH = 0 AND typ.MODE IN (3, 4)
+            AND NOT EXISTS (SELECT 1 FROM METADATA_TABLE_COLUMN c WHERE c.TABLE_OID = tbl.OID AND c.TRASH = 0 AND c.IS_PRIMARY_KEY = 1)
+        ORDER BY tbl.NAME ASC;", [], 
+        &mut |row| {
+            tables.push(BasicMetadata {  
+                oid: row.get::<_, i64>(0)?,
+                name: row.get::<_, String>(1)?,
+            });
+            return Ok(());
+        }
+    )?;
+    return Ok(tables);
+}
+
+
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A column whose metadata does not line up with the physical columns of its table.
+pub struct ColumnMismatch {
+    pub table_oid: i64,
+    pub column_oid: i64,
+    pub has_metadata: bool,
+    pub has_physical_column: bool
+}
+
+/// Compares the column metadata of a table against the physical columns of the table, as part of an ongoing action.
+pub fn verify_columns_in(trans: &Connection, table_oid: i64) -> Result<Vec<ColumnMismatch>, error::Error> {
+    // Get the OID of every physical data column in the table
+    let mut physical_column_oids: HashSet<i64> = HashSet::new();
+    db::query_iterate(trans, 
+        "SELECT name FROM pragma_table_info(?1) WHERE name GLOB 'COLUMN[0-9]*';", 
+        params![format!("TABLE{table_oid}")], 
+        &mut |row| {
+            let column_name: String = row.get(0)?;
+            match column_name["COLUMN".len()..].parse::<i64>() {
+                Ok(column_oid) => {
+                    physical_column_oids.insert(column_oid);
+                },
+                Err(_) => {
+                    // Auxiliary columns (e.g. COLUMN{oid}_NAME for files) are managed alongside the column they belong to
+                }
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Check every column that should be stored physically in the table
+    let mut mismatches: Vec<ColumnMismatch> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT 
+            c.OID,
+            c.TRASH
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND t.MODE NOT IN (2, 5)
+        ORDER BY c.OID;", 
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            let is_trash: bool = row.get("TRASH")?;
+            let has_physical_column = physical_column_oids.remove(&column_oid);
+            if !is_trash && !has_physical_column {
+                mismatches.push(ColumnMismatch { 
+                    table_oid, 
+                    column_oid, 
+                    has_metadata: true, 
+                    has_physical_column: false 
+                });
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Any remaining physical columns have no metadata
+    let mut unlisted_column_oids: Vec<i64> = physical_column_oids.into_iter().collect();
+    unlisted_column_oids.sort();
+    for column_oid in unlisted_column_oids {
+        mismatches.push(ColumnMismatch { 
+            table_oid, 
+            column_oid, 
+            has_metadata: false, 
+            has_physical_column: true 
+        });
+    }
+    return Ok(mismatches);
+}
+
+/// Compares the column metadata of a table against the physical columns of the table.
+pub fn verify_columns(table_oid: i64) -> Result<Vec<ColumnMismatch>, error::Error> {
+    let conn = db::open()?;
+    return verify_columns_in(&conn, table_oid);
+}
+
 
 
 #[derive(Serialize)]
@@ -432,4 +1285,501 @@ struct Table {
     name: String,
     //data: HashMap<i64, (TableColumn, Vec<Serialize>)>,
     surrogate_key_column_oid: Option<i64>
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all="camelCase")]
+/// The page, sort, filter, and scroll position that a table was last viewed with.
+pub struct ViewState {
+    pub page_num: i64,
+    pub page_size: i64,
+    pub sort_column_oid: Option<i64>,
+    pub sort_ascending: bool,
+    pub filter_text: Option<String>,
+    pub scroll_top: i64
+}
+
+/// Saves the state that a table is being viewed with, so that it can be restored when the table is reopened.
+pub fn save_view_state(table_oid: i64, view_state: &ViewState) -> Result<(), error::Error> {
+    let conn = db::open()?;
+    conn.execute(
+        "INSERT INTO METADATA_TABLE_VIEW_STATE (TABLE_OID, PAGE_NUM, PAGE_SIZE, SORT_COLUMN_OID, SORT_ASCENDING, FILTER_TEXT, SCROLL_TOP) 
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        ON CONFLICT (TABLE_OID) DO UPDATE SET 
+            PAGE_NUM = excluded.PAGE_NUM,
+            PAGE_SIZE = excluded.PAGE_SIZE,
+            SORT_COLUMN_OID = excluded.SORT_COLUMN_OID,
+            SORT_ASCENDING = excluded.SORT_ASCENDING,
+            FILTER_TEXT = excluded.FILTER_TEXT,
+            SCROLL_TOP = excluded.SCROLL_TOP;", 
+        params![table_oid, view_state.page_num, view_state.page_size, view_state.sort_column_oid, view_state.sort_ascending, view_state.filter_text, view_state.scroll_top]
+    )?;
+    return Ok(());
+}
+
+/// Gets the state that a table was last viewed with, if any was saved.
+pub fn get_view_state(table_oid: i64) -> Result<Option<ViewState>, error::Error> {
+    let conn = db::open()?;
+    return Ok(conn.query_one(
+        "SELECT PAGE_NUM, PAGE_SIZE, SORT_COLUMN_OID, SORT_ASCENDING, FILTER_TEXT, SCROLL_TOP FROM METADATA_TABLE_VIEW_STATE WHERE TABLE_OID = ?1;", 
+        params![table_oid], 
+        |row| {
+            Ok(ViewState {
+                page_num: row.get("PAGE_NUM")?,
+                page_size: row.get("PAGE_SIZE")?,
+                sort_column_oid: row.get("SORT_COLUMN_OID")?,
+                sort_ascending: row.get("SORT_ASCENDING")?,
+                filter_text: row.get("FILTER_TEXT")?,
+                scroll_top: row.get("SCROLL_TOP")?
+            })
+        }
+    ).optional()?);
+}
+
+
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all="camelCase")]
+/// How a child table was attached to its parent table, so that a promoted child table can be demoted back into it.
+pub struct ChildTableLink {
+    pub table_name: String,
+    pub column_name: String,
+    pub column_ordering: i64,
+    pub column_style: String
+}
+
+/// Gets how the child table of a column is attached to the column's table.
+pub fn get_child_table_link(parent_table_oid: i64, column_oid: i64) -> Result<ChildTableLink, error::Error> {
+    let conn = db::open()?;
+    return Ok(conn.query_one(
+        "SELECT 
+            tbl.NAME AS TABLE_NAME,
+            col.NAME AS COLUMN_NAME,
+            col.COLUMN_ORDERING,
+            col.COLUMN_CSS_STYLE
+        FROM METADATA_TABLE_COLUMN col
+        INNER JOIN METADATA_TYPE typ ON typ.OID = col.TYPE_OID
+        INNER JOIN METADATA_TABLE tbl ON tbl.TYPE_OID = col.TYPE_OID
+        WHERE col.OID = ?1 AND col.TABLE_OID = ?2 AND typ.MODE = 5;", 
+        params![column_oid, parent_table_oid], 
+        |row| {
+            Ok(ChildTableLink {
+                table_name: row.get("TABLE_NAME")?,
+                column_name: row.get("COLUMN_NAME")?,
+                column_ordering: row.get("COLUMN_ORDERING")?,
+                column_style: row.get("COLUMN_CSS_STYLE")?
+            })
+        }
+    ).optional()?
+    .ok_or(error::Error::AdhocError("The column is not a child table of the given table."))?);
+}
+
+/// Converts the child table of a column into a standalone table that other tables can reference.
+/// The rows of the child table are kept, and the column is moved into the promoted table as a reference to the row of the parent table that each row belonged to.
+/// Returns the OID of the promoted table.
+pub fn promote_child_table(parent_table_oid: i64, column_oid: i64) -> Result<i64, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(promote_child_table_in(&action, parent_table_oid, column_oid));
+    log::debug!("Promoted child table of COLUMN{column_oid} in TABLE{parent_table_oid} in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Converts the child table of a column into a standalone table, as part of an ongoing action.
+fn promote_child_table_in(trans: &Connection, parent_table_oid: i64, column_oid: i64) -> Result<i64, error::Error> {
+    // Find the child table and the name that the column gave it
+    let (child_table_oid, column_name): (i64, String) = trans.query_one(
+        "SELECT col.TYPE_OID, col.NAME 
+        FROM METADATA_TABLE_COLUMN col
+        INNER JOIN METADATA_TYPE typ ON typ.OID = col.TYPE_OID
+        WHERE col.OID = ?1 AND col.TABLE_OID = ?2 AND col.TRASH = 0 AND typ.MODE = 5;", 
+        params![column_oid, parent_table_oid], 
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).optional()?
+    .ok_or(error::Error::AdhocError("The column is not a child table of the given table."))?;
+    let parent_table_name: String = trans.query_one(
+        "SELECT NAME FROM METADATA_TABLE WHERE TYPE_OID = ?1;", 
+        params![parent_table_oid], 
+        |row| row.get(0)
+    )?;
+    validate_name_in(trans, &column_name, Some(child_table_oid))?;
+
+    // Drop the surrogate view of the child table, which is rebuilt once the table is standalone
+    let drop_view_cmd = format!("DROP VIEW IF EXISTS TABLE{child_table_oid}_SURROGATE;");
+    trans.execute(&drop_view_cmd, [])?;
+
+    // Turn the child table into a standalone table named after the column
+    trans.execute(
+        "UPDATE METADATA_TYPE SET MODE = 3 WHERE OID = ?1;", 
+        params![child_table_oid]
+    )?;
+    trans.execute(
+        "UPDATE METADATA_TABLE SET NAME = ?1, PARENT_TABLE_OID = NULL WHERE TYPE_OID = ?2;", 
+        params![column_name, child_table_oid]
+    )?;
+
+    // Move the column into the promoted table as a reference to the parent table, placed after the promoted table's own columns
+    let column_ordering: i64 = trans.query_one(
+        "SELECT COALESCE(MAX(COLUMN_ORDERING), 0) + 1 FROM METADATA_TABLE_COLUMN WHERE TABLE_OID = ?1;", 
+        params![child_table_oid], 
+        |row| row.get(0)
+    )?;
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN 
+        SET 
+            TABLE_OID = ?1,
+            TYPE_OID = ?2,
+            NAME = ?3,
+            COLUMN_ORDERING = ?4,
+            IS_NULLABLE = 0,
+            IS_UNIQUE = 0,
+            IS_PRIMARY_KEY = 0
+        WHERE OID = ?5;", 
+        params![child_table_oid, parent_table_oid, parent_table_name, column_ordering, column_oid]
+    )?;
+
+    // Keep the link from each row to its parent row as the data of the reference column
+    let rename_cmd = format!("ALTER TABLE TABLE{child_table_oid} RENAME COLUMN PARENT_OID TO COLUMN{column_oid};");
+    trans.execute(&rename_cmd, [])?;
+
+    // Rebuild the views of both tables
+    update_surrogate_view(trans, child_table_oid)?;
+    update_surrogate_view(trans, parent_table_oid)?;
+    obj_type::update_flattened_views(trans, parent_table_oid)?;
+    db::update_friendly_view(trans, child_table_oid)?;
+    db::update_friendly_view(trans, parent_table_oid)?;
+    return Ok(child_table_oid);
+}
+
+/// Converts a table that was promoted from a child table back into the child table of a column.
+/// This fails if any other column has come to reference the table since it was promoted.
+pub fn demote_to_child_table(parent_table_oid: i64, table_oid: i64, column_oid: i64, link: &ChildTableLink) -> Result<(), error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(demote_to_child_table_in(&action, parent_table_oid, table_oid, column_oid, link));
+    log::debug!("Demoted TABLE{table_oid} to a child table of TABLE{parent_table_oid} in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Converts a promoted table back into the child table of a column, as part of an ongoing action.
+fn demote_to_child_table_in(trans: &Connection, parent_table_oid: i64, table_oid: i64, column_oid: i64, link: &ChildTableLink) -> Result<(), error::Error> {
+    // Make sure nothing else has started referencing the table
+    let other_reference_count: i64 = trans.query_one(
+        "SELECT COUNT(*) FROM METADATA_TABLE_COLUMN WHERE TYPE_OID = ?1 AND OID <> ?2;", 
+        params![table_oid, column_oid], 
+        |row| row.get(0)
+    )?;
+    if other_reference_count > 0 {
+        return Err(error::Error::AdhocError("The table cannot be turned back into a child table while other columns reference it."));
+    }
+
+    // Drop the views of the standalone table
+    db::drop_friendly_view(trans, table_oid)?;
+    let drop_view_cmd = format!("DROP VIEW IF EXISTS TABLE{table_oid}_SURROGATE;");
+    trans.execute(&drop_view_cmd, [])?;
+
+    // Restore the link from each row to its parent row
+    let rename_cmd = format!("ALTER TABLE TABLE{table_oid} RENAME COLUMN COLUMN{column_oid} TO PARENT_OID;");
+    trans.execute(&rename_cmd, [])?;
+
+    // Move the column back into the parent table
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN 
+        SET 
+            TABLE_OID = ?1,
+            TYPE_OID = ?2,
+            NAME = ?3,
+            COLUMN_ORDERING = ?4,
+            COLUMN_CSS_STYLE = ?5,
+            IS_NULLABLE = 1
+        WHERE OID = ?6;", 
+        params![parent_table_oid, table_oid, link.column_name, link.column_ordering, link.column_style, column_oid]
+    )?;
+
+    // Turn the table back into a child table
+    trans.execute(
+        "UPDATE METADATA_TABLE SET NAME = ?1, PARENT_TABLE_OID = ?2 WHERE TYPE_OID = ?3;", 
+        params![link.table_name, parent_table_oid, table_oid]
+    )?;
+    trans.execute(
+        "UPDATE METADATA_TYPE SET MODE = 5 WHERE OID = ?1;", 
+        params![table_oid]
+    )?;
+
+    // Rebuild the views of both tables
+    update_surrogate_view(trans, table_oid)?;
+    update_surrogate_view(trans, parent_table_oid)?;
+    obj_type::update_flattened_views(trans, parent_table_oid)?;
+    db::update_friendly_view(trans, parent_table_oid)?;
+    return Ok(());
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// How a formatting rule compares the value of its condition column.
+pub enum FormatRuleOperator {
+    Equals,
+    NotEquals,
+    LessThan,
+    GreaterThan,
+    Contains,
+    IsEmpty,
+    IsNotEmpty
+}
+
+impl FormatRuleOperator {
+    /// Gets the name under which the operator is stored in the database.
+    fn as_str(&self) -> &'static str {
+        return match self {
+            Self::Equals => "equals",
+            Self::NotEquals => "notEquals",
+            Self::LessThan => "lessThan",
+            Self::GreaterThan => "greaterThan",
+            Self::Contains => "contains",
+            Self::IsEmpty => "isEmpty",
+            Self::IsNotEmpty => "isNotEmpty"
+        };
+    }
+
+    /// Gets the operator stored in the database under a name.
+    fn from_str(name: &str) -> Option<Self> {
+        return match name {
+            "equals" => Some(Self::Equals),
+            "notEquals" => Some(Self::NotEquals),
+            "lessThan" => Some(Self::LessThan),
+            "greaterThan" => Some(Self::GreaterThan),
+            "contains" => Some(Self::Contains),
+            "isEmpty" => Some(Self::IsEmpty),
+            "isNotEmpty" => Some(Self::IsNotEmpty),
+            _ => None
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// A condition on the value of a column under which the rows of a table are displayed with a style.
+/// A value of @today is replaced by the current date when the rule is evaluated, so that rows can be compared to the date on which they are viewed.
+pub struct FormatRule {
+    pub rule_oid: i64,
+    pub condition_column_oid: i64,
+    pub operator: FormatRuleOperator,
+    pub value: Option<String>,
+    pub css_style: String
+}
+
+impl FormatRule {
+    /// Checks whether a cell value satisfies the rule.
+    /// Values are compared as numbers when both can be parsed as one, and otherwise as text, which orders dates and timestamps correctly.
+    pub fn matches(&self, cell_value: Option<&str>, today: &str) -> bool {
+        let rule_value: Option<&str> = match self.value.as_deref() {
+            Some(table_column::DEFAULT_TODAY) => Some(today),
+            v => v
+        };
+        return match (self.operator, cell_value, rule_value) {
+            (FormatRuleOperator::IsEmpty, cell_value, _) => cell_value.map_or(true, |v| v.is_empty()),
+            (FormatRuleOperator::IsNotEmpty, cell_value, _) => cell_value.map_or(false, |v| !v.is_empty()),
+            (_, None, _) | (_, _, None) => false,
+            (FormatRuleOperator::Contains, Some(cell_value), Some(rule_value)) => cell_value.to_lowercase().contains(&rule_value.to_lowercase()),
+            (operator, Some(cell_value), Some(rule_value)) => {
+                let ordering = match (cell_value.parse::<f64>(), rule_value.parse::<f64>()) {
+                    (Ok(a), Ok(b)) => a.partial_cmp(&b),
+                    _ => Some(cell_value.cmp(rule_value))
+                };
+                match (operator, ordering) {
+                    (FormatRuleOperator::Equals, Some(std::cmp::Ordering::Equal)) => true,
+                    (FormatRuleOperator::NotEquals, Some(o)) => o != std::cmp::Ordering::Equal,
+                    (FormatRuleOperator::LessThan, Some(std::cmp::Ordering::Less)) => true,
+                    (FormatRuleOperator::GreaterThan, Some(std::cmp::Ordering::Greater)) => true,
+                    _ => false
+                }
+            }
+        };
+    }
+}
+
+/// Adds a formatting rule to a table.
+/// Returns the OID of the rule.
+pub fn add_format_rule(table_oid: i64, condition_column_oid: i64, operator: FormatRuleOperator, value: Option<String>, css_style: String) -> Result<i64, error::Error> {
+    let action = db::begin_db_action()?;
+
+    // Make sure the condition column belongs to the table
+    let is_column_of_table: bool = action.query_one(
+        "SELECT EXISTS (SELECT 1 FROM METADATA_TABLE_COLUMN WHERE OID = ?1 AND TABLE_OID = ?2 AND TRASH = 0);", 
+        params![condition_column_oid, table_oid], 
+        |row| row.get(0)
+    )?;
+    if !is_column_of_table {
+        return Err(error::Error::AdhocError("The condition of a formatting rule must be a column of the table."));
+    }
+
+    action.execute(
+        "INSERT INTO METADATA_TABLE_FORMAT_RULE (TABLE_OID, CONDITION_COLUMN_OID, OPERATOR, VALUE, CSS_STYLE) VALUES (?1, ?2, ?3, ?4, ?5);", 
+        params![table_oid, condition_column_oid, operator.as_str(), value, css_style]
+    )?;
+    return Ok(action.last_insert_rowid());
+}
+
+/// Removes a formatting rule from a table.
+/// Returns whether the rule was removed, which is false if it did not exist.
+pub fn remove_format_rule(rule_oid: i64) -> Result<bool, error::Error> {
+    let action = db::begin_db_action()?;
+    let deleted_count = action.execute(
+        "DELETE FROM METADATA_TABLE_FORMAT_RULE WHERE OID = ?1;", 
+        params![rule_oid]
+    )?;
+    return Ok(deleted_count > 0);
+}
+
+/// Gets the formatting rules of a table, in the order that they were added.
+pub fn get_format_rules(table_oid: i64) -> Result<Vec<FormatRule>, error::Error> {
+    let conn = db::open()?;
+    return get_format_rules_in(&conn, table_oid);
+}
+
+/// Gets the formatting rules of a table, as part of an ongoing action.
+pub fn get_format_rules_in(trans: &Connection, table_oid: i64) -> Result<Vec<FormatRule>, error::Error> {
+    let mut rules: Vec<FormatRule> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT r.OID, r.CONDITION_COLUMN_OID, r.OPERATOR, r.VALUE, r.CSS_STYLE 
+        FROM METADATA_TABLE_FORMAT_RULE r
+        INNER JOIN METADATA_TABLE_COLUMN c ON c.OID = r.CONDITION_COLUMN_OID
+        WHERE r.TABLE_OID = ?1 AND c.TRASH = 0
+        ORDER BY r.OID;", 
+        params![table_oid], 
+        &mut |row| {
+            let operator_name: String = row.get(2)?;
+            match FormatRuleOperator::from_str(&operator_name) {
+                Some(operator) => {
+                    rules.push(FormatRule { 
+                        rule_oid: row.get(0)?, 
+                        condition_column_oid: row.get(1)?, 
+                        operator, 
+                        value: row.get(3)?, 
+                        css_style: row.get(4)? 
+                    });
+                },
+                None => {
+                    log::warn!("Skipping formatting rule with unknown operator {operator_name}.");
+                }
+            }
+            return Ok(());
+        }
+    )?;
+    return Ok(rules);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{data_type, obj_type, table_column, table_data, test_util};
+
+    /// Looks up the display values of a reference column into a 50,000-row table, as a grid scrolling through it would.
+    /// Run with `cargo test surrogate_cache_benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn surrogate_cache_benchmark() {
+        const ROW_COUNT: i64 = 50000;
+        let _db = test_util::open_test_db();
+
+        // Create a table with a text primary key, and a table referencing it
+        let target_oid = create(String::from("Targets"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(target_oid, "Name", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, true, None).unwrap();
+        let source_oid = create(String::from("Sources"), &vec![]).unwrap();
+        let reference_column_oid = table_column::create(source_oid, "Target", data_type::MetadataColumnType::Reference(target_oid), None, "", true, false, false, None).unwrap();
+        {
+            let mut conn = db::open().unwrap();
+            let trans = conn.transaction().unwrap();
+            for oid in 1..=ROW_COUNT {
+                trans.execute(&format!("INSERT INTO TABLE{target_oid} (OID, COLUMN{name_column_oid}) VALUES (?1, ?2);"), params![oid, format!("Target {oid}")]).unwrap();
+                trans.execute(&format!("INSERT INTO TABLE{source_oid} (OID, COLUMN{reference_column_oid}) VALUES (?1, ?1);"), params![oid]).unwrap();
+            }
+            trans.commit().unwrap();
+        }
+
+        // Look up every referenced row one page at a time, first with nothing cached, then again with whatever the cache kept,
+        // both with the default cache size and with a cache large enough to hold every row
+        let row_oids: Vec<i64> = (1..=ROW_COUNT).collect();
+        for cache_size in [DEFAULT_SURROGATE_CACHE_SIZE, ROW_COUNT as usize] {
+            set_surrogate_cache_size(cache_size);
+            clear_surrogate_cache();
+            let mut timings: Vec<std::time::Duration> = Vec::new();
+            for _ in 0..2 {
+                let start = std::time::Instant::now();
+                let mut display_count: usize = 0;
+                for page in row_oids.chunks(MAX_SURROGATE_LOOKUP) {
+                    display_count += get_surrogate_displays(target_oid, page.to_vec()).unwrap().len();
+                }
+                assert_eq!(display_count, ROW_COUNT as usize);
+                timings.push(start.elapsed());
+            }
+            println!("{ROW_COUNT} display values with a cache of {cache_size}: {:?} first pass, {:?} second pass", timings[0], timings[1]);
+        }
+        set_surrogate_cache_size(DEFAULT_SURROGATE_CACHE_SIZE);
+        assert_eq!(get_surrogate_display(target_oid, ROW_COUNT).unwrap(), Some(format!("Target {ROW_COUNT}")));
+    }
+
+    #[test]
+    fn rebuilding_surrogate_views_restores_reference_display() {
+        let _db = test_util::open_test_db();
+
+        // Create a table with a text primary key, and a table referencing one of its rows
+        let target_oid = create(String::from("Targets"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(target_oid, "Name", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, true, None).unwrap();
+        let source_oid = create(String::from("Sources"), &vec![]).unwrap();
+        let reference_column_oid = table_column::create(source_oid, "Target", data_type::MetadataColumnType::Reference(target_oid), None, "", true, false, false, None).unwrap();
+        db::open().unwrap().execute_batch(&format!("
+        INSERT INTO TABLE{target_oid} (OID, COLUMN{name_column_oid}) VALUES (1, 'Alpha');
+        INSERT INTO TABLE{source_oid} (OID, COLUMN{reference_column_oid}) VALUES (1, 1);")).unwrap();
+        let get_reference_display = || table_data::get_cell_values(source_oid, 1, vec![reference_column_oid]).map(|values| values[&reference_column_oid].clone());
+        assert_eq!(get_reference_display().unwrap(), Some(String::from("Alpha")));
+
+        // Simulate an edit that was interrupted after dropping the view
+        db::open().unwrap().execute(&format!("DROP VIEW TABLE{target_oid}_SURROGATE;"), []).unwrap();
+        assert!(get_reference_display().is_err());
+
+        // Rebuilding every view brings the display value back
+        assert!(rebuild_all_surrogate_views().unwrap() >= 2);
+        assert_eq!(get_reference_display().unwrap(), Some(String::from("Alpha")));
+    }
+
+    #[test]
+    fn table_names_are_validated() {
+        let _db = test_util::open_test_db();
+
+        // Blank names and the internal names of tables are rejected
+        for name in ["", "   ", "TABLE5", "table12"] {
+            assert!(create(String::from(name), &vec![]).is_err(), "{name:?} was accepted");
+        }
+        create(String::from("Table"), &vec![]).unwrap();
+
+        // Names already used by a table or object type are rejected, regardless of case and surrounding spaces
+        let customers_oid = create(String::from("Customers"), &vec![]).unwrap();
+        assert!(create(String::from(" customers "), &vec![]).is_err());
+        assert!(obj_type::create(String::from("CUSTOMERS"), &vec![]).is_err());
+
+        // Once the table is in the trash, its name can be used again
+        move_trash(customers_oid).unwrap();
+        create(String::from("Customers"), &vec![]).unwrap();
+    }
+
+    #[test]
+    fn tables_can_be_renamed_and_renamed_back() {
+        let _db = test_util::open_test_db();
+        let table_oid = create(String::from("Clients"), &vec![]).unwrap();
+        create(String::from("Orders"), &vec![]).unwrap();
+        let get_name = || db::open().unwrap().query_one("SELECT NAME FROM METADATA_TABLE WHERE TYPE_OID = ?1;", params![table_oid], |row| row.get::<_, String>(0)).unwrap();
+
+        // A table cannot take the name of another table, but can change the case of its own name
+        assert!(rename(table_oid, String::from("Orders")).is_err());
+        assert!(rename(table_oid, String::from("TABLE1")).is_err());
+        assert_eq!(get_name(), "Clients");
+        assert_eq!(rename(table_oid, String::from("CLIENTS")).unwrap(), "Clients");
+
+        // Undoing a rename gives back the prior name
+        let prior_name = rename(table_oid, String::from("Customers")).unwrap();
+        assert_eq!(get_name(), "Customers");
+        assert_eq!(rename(table_oid, prior_name).unwrap(), "Customers");
+        assert_eq!(get_name(), "CLIENTS");
+    }
+}
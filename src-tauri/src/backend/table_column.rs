@@ -1,13 +1,19 @@
 use std::cell::Ref;
 use std::collections::HashMap;
 use std::sync::mpsc::channel;
+use std::time::Instant;
 use rusqlite::fallible_streaming_iterator::FallibleStreamingIterator;
-use rusqlite::{params, Row, Error as RusqliteError, OptionalExtension};
+use rusqlite::{params, Error as RusqliteError, ErrorCode, OptionalExtension, Connection};
 use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
-use crate::backend::{data_type, db, table};
+use crate::backend::{data_type, db, obj_type, table};
 use crate::util::error;
 
+/// Default value of a timestamp column that is replaced by the current time when a row is inserted.
+pub const DEFAULT_NOW: &str = "@now";
+/// Default value of a date column that is replaced by the current date when a row is inserted.
+pub const DEFAULT_TODAY: &str = "@today";
+
 
 #[derive(Serialize)]
 #[serde(rename_all="camelCase")]
@@ -21,16 +27,300 @@ pub struct Metadata {
     is_nullable: bool,
     is_unique: bool,
     is_primary_key: bool,
+    is_pinned: bool,
+    default_value: Option<String>,
+}
+
+/// Creates the trigger that deletes a child object when the row that owns it is deleted.
+/// The column referencing the object only clears itself when the object is deleted, as a foreign key cascades from the referenced row to the referencing row, which is the opposite of ownership.
+pub fn create_child_object_trigger(trans: &Connection, table_oid: i64, column_oid: i64, object_table_oid: i64) -> Result<(), error::Error> {
+    let create_cmd = format!("
+    CREATE TRIGGER IF NOT EXISTS TABLE{table_oid}_COLUMN{column_oid}_DELETE_OBJECT 
+    AFTER DELETE ON TABLE{table_oid} 
+    FOR EACH ROW WHEN OLD.COLUMN{column_oid} IS NOT NULL 
+    BEGIN 
+        DELETE FROM TABLE{object_table_oid} WHERE OID = OLD.COLUMN{column_oid}; 
+    END;");
+    trans.execute(&create_cmd, [])?;
+    return Ok(());
+}
+
+/// Drops the trigger that deletes a child object along with the row that owns it, if the column has one.
+fn drop_child_object_trigger(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
+    let drop_cmd = format!("DROP TRIGGER IF EXISTS TABLE{table_oid}_COLUMN{column_oid}_DELETE_OBJECT;");
+    trans.execute(&drop_cmd, [])?;
+    return Ok(());
+}
+
+/// Checks that a default value can be used for a column of the given type.
+/// Default values starting with @ are tokens evaluated at insert time, and must be one that is understood for the type of the column.
+pub fn validate_default_value(column_type: &data_type::MetadataColumnType, default_value: Option<&str>) -> Result<(), error::Error> {
+    return match default_value {
+        Some(DEFAULT_NOW) => match column_type {
+            data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => Ok(()),
+            _ => Err(error::Error::AdhocError("The @now default value can only be used for timestamp columns."))
+        },
+        Some(DEFAULT_TODAY) => match column_type {
+            data_type::MetadataColumnType::Primitive(data_type::Primitive::Date) => Ok(()),
+            _ => Err(error::Error::AdhocError("The @today default value can only be used for date columns."))
+        },
+        Some(v) if v.starts_with('@') => Err(error::Error::AdhocError("Unknown default value token. Only @now and @today are supported.")),
+        _ => Ok(())
+    };
+}
+
+/// Reads the default value of a column in the form that its cells are entered in, rather than the julian day that the defaults of date and timestamp columns are stored as.
+const DEFAULT_VALUE_TEXT_SQL: &str = "CASE 
+    WHEN typeof(c.DEFAULT_VALUE) IN ('integer', 'real') AND c.TYPE_OID = 4 THEN DATE(c.DEFAULT_VALUE, 'julianday')
+    WHEN typeof(c.DEFAULT_VALUE) IN ('integer', 'real') AND c.TYPE_OID = 5 THEN STRFTIME('%FT%TZ', c.DEFAULT_VALUE, 'julianday')
+    ELSE CAST(c.DEFAULT_VALUE AS TEXT)
+END";
+
+/// Converts a default value into the form that the cells of a column are stored in, as part of an ongoing action.
+/// Dates and timestamps are stored as julian days, while tokens are stored as-is to be evaluated at insert time.
+fn parse_default_value_in(trans: &Connection, column_type: &data_type::MetadataColumnType, default_value: Option<String>) -> Result<Option<rusqlite::types::Value>, error::Error> {
+    let default_value: String = match default_value {
+        Some(v) if !v.starts_with('@') => v,
+        Some(v) => {
+            return Ok(Some(rusqlite::types::Value::Text(v)));
+        },
+        None => {
+            return Ok(None);
+        }
+    };
+    return match column_type {
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer) => match default_value.parse::<f64>() {
+            Ok(n) => Ok(Some(rusqlite::types::Value::Integer(n as i64))),
+            Err(_) => Err(error::Error::AdhocError("The default value cannot be converted into an integer."))
+        },
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Number) => match default_value.parse::<f64>() {
+            Ok(n) => Ok(Some(rusqlite::types::Value::Real(n))),
+            Err(_) => Err(error::Error::AdhocError("The default value cannot be converted into a number."))
+        },
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Date) => {
+            // Dates are stored as the julian day number of the date
+            let julian_day: Option<i64> = trans.query_one("SELECT CAST(julianday(?1) + 0.5 AS INTEGER);", params![default_value], |row| row.get(0))?;
+            julian_day
+                .map(|d| Some(rusqlite::types::Value::Integer(d)))
+                .ok_or(error::Error::AdhocError("The default value cannot be converted into a date."))
+        },
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => {
+            // Timestamps are stored as fractional julian days in UTC
+            let julian_day: Option<f64> = trans.query_one("SELECT julianday(?1);", params![default_value], |row| row.get(0))?;
+            julian_day
+                .map(|d| Some(rusqlite::types::Value::Real(d)))
+                .ok_or(error::Error::AdhocError("The default value cannot be converted into a timestamp."))
+        },
+        _ => Ok(Some(rusqlite::types::Value::Text(default_value)))
+    };
+}
+
+/// Sets the default value of a column, after checking that it can be used for the type of the column.
+pub fn set_default_value(column_oid: i64, default_value: Option<String>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_default_value_in(&action, column_oid, default_value));
+}
+
+/// Sets the default value of a column, as part of an ongoing action.
+fn set_default_value_in(trans: &Connection, column_oid: i64, default_value: Option<String>) -> Result<(), error::Error> {
+    // Check the default value against the type of the column
+    let column_type: data_type::MetadataColumnType = trans.query_one(
+        "SELECT c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.OID = ?1;", 
+        params![column_oid], 
+        |row| Ok(data_type::MetadataColumnType::from_database(row.get(0)?, row.get(1)?))
+    )?;
+    validate_default_value(&column_type, default_value.as_deref())?;
+    let stored_value = parse_default_value_in(trans, &column_type, default_value)?;
+
+    // Update the metadata
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET DEFAULT_VALUE = ?1 WHERE OID = ?2;", 
+        params![stored_value, column_oid]
+    )?;
+    return Ok(());
+}
+
+/// Sets whether a timestamp column is set to the current time whenever another cell in the same row is edited.
+pub fn set_auto_update(column_oid: i64, auto_update: bool) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_auto_update_in(&action, column_oid, auto_update));
+}
+
+/// Sets whether a timestamp column is automatically updated, as part of an ongoing action.
+fn set_auto_update_in(trans: &Connection, column_oid: i64, auto_update: bool) -> Result<(), error::Error> {
+    // Only timestamp columns can be automatically updated
+    let column_type: data_type::MetadataColumnType = trans.query_one(
+        "SELECT c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.OID = ?1;", 
+        params![column_oid], 
+        |row| Ok(data_type::MetadataColumnType::from_database(row.get(0)?, row.get(1)?))
+    )?;
+    if auto_update && column_type != data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) {
+        return Err(error::Error::AdhocError("Only timestamp columns can be automatically updated."));
+    }
+
+    // Update the metadata
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET AUTO_UPDATE = ?1 WHERE OID = ?2;", 
+        params![auto_update, column_oid]
+    )?;
+    return Ok(());
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+/// The range of plausible values for a date or timestamp column, outside of which a value is flagged.
+pub enum DateBound {
+    /// The value cannot be after the current date or time, such as a birthdate.
+    NotFuture,
+    /// The value cannot be before the current date or time, such as a deadline.
+    NotPast,
+    /// The value must fall between an inclusive minimum and maximum, written in the same format as the values of the column.
+    Range {
+        min: Option<String>,
+        max: Option<String>
+    }
+}
+
+impl DateBound {
+    /// Checks whether a value falls outside of the bound.
+    /// The current time is given as a timestamp, which is cut down to its date for the shorter values of date columns; values are compared as text, which orders dates and timestamps correctly.
+    pub fn is_violated_by(&self, value: &str, now: &str) -> bool {
+        let now: &str = &now[..now.len().min(value.len())];
+        return match self {
+            Self::NotFuture => value > now,
+            Self::NotPast => value < now,
+            Self::Range { min, max } => {
+                min.as_deref().map_or(false, |min| value < min) 
+                || max.as_deref().map_or(false, |max| value > max)
+            }
+        };
+    }
+
+    /// Gets how serious a value falling outside of the bound is.
+    /// A value that has fallen into the past is usually a deadline that has since passed rather than a mistake, so it is only informational.
+    pub fn get_severity(&self) -> error::Severity {
+        return match self {
+            Self::NotPast => error::Severity::Info,
+            Self::NotFuture
+            | Self::Range { .. } => error::Severity::Warning
+        };
+    }
+}
+
+/// Sets the range of plausible values for a date or timestamp column, or removes it if none is given.
+pub fn set_date_bound(column_oid: i64, date_bound: Option<DateBound>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_date_bound_in(&action, column_oid, date_bound));
+}
+
+/// Sets the range of plausible values for a date or timestamp column, as part of an ongoing action.
+fn set_date_bound_in(trans: &Connection, column_oid: i64, date_bound: Option<DateBound>) -> Result<(), error::Error> {
+    // Only date and timestamp columns can be bounded
+    let column_type: data_type::MetadataColumnType = trans.query_one(
+        "SELECT c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.OID = ?1;", 
+        params![column_oid], 
+        |row| Ok(data_type::MetadataColumnType::from_database(row.get(0)?, row.get(1)?))
+    )?;
+    match (&date_bound, &column_type) {
+        (None, _)
+        | (Some(_), data_type::MetadataColumnType::Primitive(data_type::Primitive::Date))
+        | (Some(_), data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp)) => {},
+        _ => {
+            return Err(error::Error::AdhocError("Only date and timestamp columns can be bounded."));
+        }
+    }
+
+    // Update the metadata
+    let date_bound_json: Option<String> = match &date_bound {
+        Some(b) => Some(serde_json::to_string(b).map_err(|_| error::Error::AdhocError("The date bound could not be saved."))?),
+        None => None
+    };
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET DATE_BOUND = ?1 WHERE OID = ?2;", 
+        params![date_bound_json, column_oid]
+    )?;
+    return Ok(());
 }
 
-/// Creates a new column in a table.
-pub fn create(table_oid: i64, column_name: &str, column_type: data_type::MetadataColumnType, column_ordering: Option<i64>, column_style: &str, is_nullable: bool, is_unique: bool, is_primary_key: bool) -> Result<i64, error::Error> {
+/// Sets the number of decimal places a number column is displayed with, or removes it if none is given.
+pub fn set_decimal_places(column_oid: i64, decimal_places: Option<i64>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_decimal_places_in(&action, column_oid, decimal_places));
+}
+
+/// Sets the number of decimal places a number column is displayed with, as part of an ongoing action.
+fn set_decimal_places_in(trans: &Connection, column_oid: i64, decimal_places: Option<i64>) -> Result<(), error::Error> {
+    // Only number columns can be formatted
+    let column_type: data_type::MetadataColumnType = trans.query_one(
+        "SELECT c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.OID = ?1;", 
+        params![column_oid], 
+        |row| Ok(data_type::MetadataColumnType::from_database(row.get(0)?, row.get(1)?))
+    )?;
+    match (&decimal_places, &column_type) {
+        (None, _)
+        | (Some(_), data_type::MetadataColumnType::Primitive(data_type::Primitive::Number)) => {},
+        _ => {
+            return Err(error::Error::AdhocError("Only number columns can have a number of decimal places."));
+        }
+    }
+    if let Some(n) = decimal_places {
+        if n < 0 || n > 15 {
+            return Err(error::Error::AdhocError("The number of decimal places must be between 0 and 15."));
+        }
+    }
+
+    // Update the metadata
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET DECIMAL_PLACES = ?1 WHERE OID = ?2;", 
+        params![decimal_places, column_oid]
+    )?;
+    return Ok(());
+}
+
+/// Reads the range of plausible values of a column as it is stored in the metadata.
+pub fn parse_date_bound(date_bound_json: Option<String>) -> Option<DateBound> {
+    return date_bound_json.and_then(|s| serde_json::from_str(&s).ok());
+}
+
+/// Adds the columns that store the name and MIME type of the file in each cell of a file column.
+fn add_file_metadata_columns(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
+    let alter_name_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid}_NAME TEXT;");
+    trans.execute(&alter_name_cmd, [])?;
+    let alter_mime_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid}_MIME TEXT;");
+    trans.execute(&alter_mime_cmd, [])?;
+    return Ok(());
+}
+
+/// Drops the columns that store the name and MIME type of the file in each cell of a file column.
+fn drop_file_metadata_columns(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
+    let alter_name_cmd = format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid}_NAME;");
+    trans.execute(&alter_name_cmd, [])?;
+    let alter_mime_cmd = format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid}_MIME;");
+    trans.execute(&alter_mime_cmd, [])?;
+    return Ok(());
+}
+
+/// Creates a new column in a table, with a value that new rows start with if one is given.
+pub fn create(table_oid: i64, column_name: &str, column_type: data_type::MetadataColumnType, column_ordering: Option<i64>, column_style: &str, is_nullable: bool, is_unique: bool, is_primary_key: bool, default_value: Option<String>) -> Result<i64, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(
+        create_in(&action, table_oid, column_name, column_type, column_ordering, column_style, is_nullable, is_unique, is_primary_key)
+        .and_then(|column_oid| {
+            set_default_value_in(&action, column_oid, default_value)?;
+            return Ok(column_oid);
+        })
+    );
+    log::debug!("Created column (OID {:?}) in TABLE{table_oid} in {:?}.", result.as_ref().ok(), start.elapsed());
+    return result;
+}
+
+/// Creates a new column in a table, as part of an ongoing action.
+pub fn create_in(trans: &Connection, table_oid: i64, column_name: &str, column_type: data_type::MetadataColumnType, column_ordering: Option<i64>, column_style: &str, is_nullable: bool, is_unique: bool, is_primary_key: bool) -> Result<i64, error::Error> {
     let is_nullable_bit = if is_nullable { 1 } else { 0 };
     let is_unique_bit = if is_unique { 1 } else { 0 };
     let is_primary_key_bit = if is_primary_key { 1 } else { 0 };
 
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
 
     let column_ordering: i64 = match column_ordering {
         Some(o) => {
@@ -44,7 +334,7 @@ pub fn create(table_oid: i64, column_name: &str, column_type: data_type::Metadat
         None => {
             // If no explicit ordering was given, insert at the back
             trans.query_one(
-                "SELECT COALESCE(MAX(COLUMN_ORDERING), 0) AS NEW_COLUMN_ORDERING FROM METADATA_TABLE_COLUMN", 
+                "SELECT COALESCE(MAX(COLUMN_ORDERING), 0) + 1 AS NEW_COLUMN_ORDERING FROM METADATA_TABLE_COLUMN WHERE TABLE_OID = ?1", 
                 params![table_oid], 
                 |row| row.get::<_, i64>(0)
             )?
@@ -65,12 +355,19 @@ pub fn create(table_oid: i64, column_name: &str, column_type: data_type::Metadat
             let sqlite_type = prim.get_sqlite_type();
             let alter_table_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid} {sqlite_type};");
             trans.execute(&alter_table_cmd, [])?;
+            if prim.is_file() {
+                add_file_metadata_columns(&trans, table_oid, column_oid)?;
+            }
 
-            // Update table's surrogate view
+            // Update table's surrogate view and primary key index
             table::update_surrogate_view(&trans, table_oid)?;
+            table::update_primary_key_index(&trans, table_oid)?;
+
+            // Update the flattened views of the table, if it is an object type
+            obj_type::update_flattened_views(&trans, table_oid)?;
+            db::update_friendly_view(&trans, table_oid)?;
 
             // Return the column OID
-            trans.commit()?;
             return Ok(column_oid);
         },
         data_type::MetadataColumnType::SingleSelectDropdown(referenced_table_oid)
@@ -84,14 +381,21 @@ pub fn create(table_oid: i64, column_name: &str, column_type: data_type::Metadat
             let column_oid = trans.last_insert_rowid();
 
             // Add the column to the table as a reference to another table
-            let alter_table_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid} INTEGER REFERENCES TABLE{referenced_table_oid} (OID) ON UPDATE CASCADE ON DELETE SET DEFAULT;");
+            let alter_table_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid} INTEGER REFERENCES TABLE{referenced_table_oid} (OID) ON UPDATE CASCADE ON DELETE SET NULL;");
             trans.execute(&alter_table_cmd, [])?;
+            if let data_type::MetadataColumnType::ChildObject(_) = column_type {
+                create_child_object_trigger(&trans, table_oid, column_oid, *referenced_table_oid)?;
+            }
 
-            // Update table's surrogate view
+            // Update table's surrogate view and primary key index
             table::update_surrogate_view(&trans, table_oid)?;
+            table::update_primary_key_index(&trans, table_oid)?;
+
+            // Update the flattened views of the table, if it is an object type
+            obj_type::update_flattened_views(&trans, table_oid)?;
+            db::update_friendly_view(&trans, table_oid)?;
 
             // Return the column's OID
-            trans.commit()?;
             return Ok(column_oid);
         },
         data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid)
@@ -103,21 +407,31 @@ pub fn create(table_oid: i64, column_name: &str, column_type: data_type::Metadat
             )?;
             let column_oid = trans.last_insert_rowid();
 
-            // Update table's surrogate view
+            // Update table's surrogate view and primary key index
             table::update_surrogate_view(&trans, table_oid)?;
+            table::update_primary_key_index(&trans, table_oid)?;
+
+            // Update the flattened views of the table, if it is an object type
+            obj_type::update_flattened_views(&trans, table_oid)?;
+            db::update_friendly_view(&trans, table_oid)?;
 
             // Return the column OID
-            trans.commit()?;
             return Ok(column_oid);
         }
     }
 }
 
 /// Edits a column's metadata and/or type.
-pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: data_type::MetadataColumnType, column_style: &str, is_nullable: bool, is_unique: bool, is_primary_key: bool) -> Result<Option<i64>, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: data_type::MetadataColumnType, column_style: &str, is_nullable: bool, is_unique: bool, is_primary_key: bool, is_pinned: bool, default_value: Option<String>) -> Result<Option<i64>, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(edit_in(&action, table_oid, column_oid, column_name, column_type, column_style, is_nullable, is_unique, is_primary_key, is_pinned, default_value));
+    log::debug!("Edited COLUMN{column_oid} in TABLE{table_oid} in {:?}.", start.elapsed());
+    return result;
+}
 
+/// Edits a column's metadata, as part of an ongoing action.
+fn edit_in(trans: &Connection, table_oid: i64, column_oid: i64, column_name: &str, column_type: data_type::MetadataColumnType, column_style: &str, is_nullable: bool, is_unique: bool, is_primary_key: bool, is_pinned: bool, default_value: Option<String>) -> Result<Option<i64>, error::Error> {
     // Record the old values of the column metadata
     trans.execute(
         "INSERT INTO METADATA_TABLE_COLUMN (
@@ -130,6 +444,8 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
             IS_NULLABLE, 
             IS_UNIQUE, 
             IS_PRIMARY_KEY, 
+            IS_PINNED, 
+            AUTO_UPDATE, 
             DEFAULT_VALUE
         )
         SELECT
@@ -142,6 +458,8 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
             IS_NULLABLE,
             IS_UNIQUE,
             IS_PRIMARY_KEY,
+            IS_PINNED,
+            AUTO_UPDATE,
             DEFAULT_VALUE
         FROM METADATA_TABLE_COLUMN
         WHERE OID = ?1", 
@@ -173,15 +491,30 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
                     COLUMN_CSS_STYLE = ?3,
                     IS_NULLABLE = ?4,
                     IS_UNIQUE = ?5,
-                    IS_PRIMARY_KEY = ?6
-                WHERE OID = ?7;", 
-                params![column_name, column_type.get_type_oid(), column_style, is_nullable, is_unique, is_primary_key, column_oid]
+                    IS_PRIMARY_KEY = ?6,
+                    IS_PINNED = ?7
+                WHERE OID = ?8;", 
+                params![column_name, column_type.get_type_oid(), column_style, is_nullable, is_unique, is_primary_key, is_pinned, column_oid]
             )?;
 
             if prior_column_type != column_type {
+                // Drop any views that select from the column before it is dropped
+                obj_type::drop_flattened_views(&trans, table_oid)?;
+                db::drop_friendly_view(&trans, table_oid)?;
+
                 // Attempt to transfer over data
                 let trans_table_created: bool;
 
+                // Drop the index enforcing the primary key, which would prevent the column from being dropped
+                table::drop_primary_key_index(&trans, table_oid)?;
+
+                // Switching between file and image keeps the name and MIME type of each file
+                let is_file_type = |t: &data_type::MetadataColumnType| match t {
+                    data_type::MetadataColumnType::Primitive(prim) => prim.is_file(),
+                    _ => false
+                };
+                let keep_file_metadata: bool = is_file_type(&prior_column_type) && is_file_type(&column_type);
+
                 // Start by deconstructing any tables and dropping any columns for the previous type
                 match prior_column_type {
                     data_type::MetadataColumnType::Primitive(_)
@@ -193,8 +526,15 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
                         trans_table_created = true;
 
                         // Delete the previous column from the data
+                        drop_child_object_trigger(&trans, table_oid, column_oid)?;
                         let alter_cmd = format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid};");
                         trans.execute(&alter_cmd, [])?;
+                        match &prior_column_type {
+                            data_type::MetadataColumnType::Primitive(prim) if prim.is_file() && !keep_file_metadata => {
+                                drop_file_metadata_columns(&trans, table_oid, column_oid)?;
+                            },
+                            _ => {}
+                        }
                     },
                     data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
                         // Create temporary table to hold prior data
@@ -269,6 +609,9 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
                         let sqlite_type = prim.get_sqlite_type();
                         let alter_table_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid} {sqlite_type};");
                         trans.execute(&alter_table_cmd, [])?;
+                        if prim.is_file() && !keep_file_metadata {
+                            add_file_metadata_columns(&trans, table_oid, column_oid)?;
+                        }
 
                         // Copy over previous data
                         if trans_table_created {
@@ -296,6 +639,9 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
                         // Add the column to the table
                         let alter_table_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid} INTEGER REFERENCES TABLE{referenced_table_oid} (OID) ON UPDATE CASCADE ON DELETE SET NULL;");
                         trans.execute(&alter_table_cmd, [])?;
+                        if let data_type::MetadataColumnType::ChildObject(_) = column_type {
+                            create_child_object_trigger(&trans, table_oid, column_oid, referenced_table_oid)?;
+                        }
 
                         // Copy over previous data
                         if trans_table_created {
@@ -322,11 +668,17 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
                 }
             }
 
-            // Update table's surrogate view
+            // Set the default value, now that the column has its new type
+            set_default_value_in(trans, column_oid, default_value)?;
+
+            // Update table's surrogate view and primary key index
             table::update_surrogate_view(&trans, table_oid)?;
+            table::update_primary_key_index(&trans, table_oid)?;
+
+            // Update the flattened views of the table, if it is an object type
+            obj_type::update_flattened_views(&trans, table_oid)?;
+            db::update_friendly_view(&trans, table_oid)?;
 
-            // Commit the changes
-            trans.commit()?;
             return Ok(Some(trash_column_oid));
         },
         None => {
@@ -335,43 +687,121 @@ pub fn edit(table_oid: i64, column_oid: i64, column_name: &str, column_type: dat
     };
 }
 
+/// Moves a column to a new position among the columns of its table, shifting the columns in between over by one.
+/// Returns the prior position of the column.
+pub fn reorder(table_oid: i64, column_oid: i64, new_ordering: i64) -> Result<i64, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(reorder_in(&action, table_oid, column_oid, new_ordering));
+}
+
+/// Moves a column to a new position among the columns of its table, as part of an ongoing action.
+fn reorder_in(trans: &Connection, table_oid: i64, column_oid: i64, new_ordering: i64) -> Result<i64, error::Error> {
+    let (prior_ordering, is_primary_key): (i64, bool) = trans.query_one(
+        "SELECT COLUMN_ORDERING, IS_PRIMARY_KEY FROM METADATA_TABLE_COLUMN WHERE OID = ?1 AND TABLE_OID = ?2;", 
+        params![column_oid, table_oid], 
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).optional()?
+    .ok_or(error::Error::AdhocError("The column does not belong to the given table."))?;
+    if new_ordering == prior_ordering {
+        return Ok(prior_ordering);
+    }
+
+    // Shift the columns between the prior and new positions over by one to make space
+    if new_ordering < prior_ordering {
+        trans.execute(
+            "UPDATE METADATA_TABLE_COLUMN SET COLUMN_ORDERING = COLUMN_ORDERING + 1 WHERE TABLE_OID = ?1 AND COLUMN_ORDERING >= ?2 AND COLUMN_ORDERING < ?3;",
+            params![table_oid, new_ordering, prior_ordering]
+        )?;
+    } else {
+        trans.execute(
+            "UPDATE METADATA_TABLE_COLUMN SET COLUMN_ORDERING = COLUMN_ORDERING - 1 WHERE TABLE_OID = ?1 AND COLUMN_ORDERING > ?2 AND COLUMN_ORDERING <= ?3;",
+            params![table_oid, prior_ordering, new_ordering]
+        )?;
+    }
+
+    // Move the column into place
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET COLUMN_ORDERING = ?1 WHERE OID = ?2;",
+        params![new_ordering, column_oid]
+    )?;
+
+    // The display value of each row joins its primary key columns in order, so it changes when one of them moves
+    if is_primary_key {
+        table::update_surrogate_view(trans, table_oid)?;
+    }
+    return Ok(prior_ordering);
+}
+
 /// Flags a column as being trash.
 pub fn move_trash(table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(move_trash_in(&action, table_oid, column_oid));
+}
 
+/// Flags a column as being trash, as part of an ongoing action.
+pub fn move_trash_in(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
     // Flag the table as trash
     trans.execute("UPDATE METADATA_TABLE_COLUMN SET TRASH = 1 WHERE OID = ?1;", params![column_oid])?;
 
-    // Update table's surrogate view
+    // Update table's surrogate view and primary key index
     table::update_surrogate_view(&trans, table_oid)?;
+    table::update_primary_key_index(&trans, table_oid)?;
+
+    // Update the flattened views of the table, if it is an object type
+    obj_type::update_flattened_views(&trans, table_oid)?;
+    db::update_friendly_view(&trans, table_oid)?;
 
-    // Commit and return
-    trans.commit()?;
     return Ok(());
 }
 
+/// Counts the rows of a table that are not in the trash and have a value in the given column.
+pub fn count_values_in(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<i64, error::Error> {
+    let column_type: data_type::MetadataColumnType = trans.query_one(
+        "SELECT c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.OID = ?1;", 
+        params![column_oid], 
+        |row| Ok(data_type::MetadataColumnType::from_database(row.get(0)?, row.get(1)?))
+    )?;
+    let count_cmd: String = match column_type {
+        data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => format!("SELECT COUNT(DISTINCT m.ROW_OID) FROM TABLE{column_type_oid}_MULTISELECT m INNER JOIN TABLE{table_oid} t ON t.OID = m.ROW_OID WHERE t.TRASH = 0;"),
+        data_type::MetadataColumnType::ChildTable(column_type_oid) => format!("SELECT COUNT(DISTINCT c.PARENT_OID) FROM TABLE{column_type_oid} c INNER JOIN TABLE{table_oid} t ON t.OID = c.PARENT_OID WHERE t.TRASH = 0 AND c.TRASH = 0;"),
+        _ => format!("SELECT COUNT(*) FROM TABLE{table_oid} WHERE TRASH = 0 AND COLUMN{column_oid} IS NOT NULL;")
+    };
+    return Ok(trans.query_one(&count_cmd, [], |row| row.get(0))?);
+}
+
 /// Unflags a column as being trash.
 pub fn unmove_trash(table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(unmove_trash_in(&action, table_oid, column_oid));
+}
 
+/// Unflags a column as being trash, as part of an ongoing action.
+fn unmove_trash_in(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<(), error::Error> {
     // Unflag the table as trash
     trans.execute("UPDATE METADATA_TABLE_COLUMN SET TRASH = 0 WHERE OID = ?1;", params![column_oid])?;
 
-    // Update table's surrogate view
+    // Update table's surrogate view and primary key index
     table::update_surrogate_view(&trans, table_oid)?;
+    table::update_primary_key_index(&trans, table_oid)?;
+
+    // Update the flattened views of the table, if it is an object type
+    obj_type::update_flattened_views(&trans, table_oid)?;
+    db::update_friendly_view(&trans, table_oid)?;
 
-    // Commit and return
-    trans.commit()?;
     return Ok(());
 }
 
 /// Delete the column with the given OID.
 pub fn delete(column_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(delete_in(&action, column_oid));
+    log::debug!("Deleted COLUMN{column_oid} in {:?}.", start.elapsed());
+    return result;
+}
 
+/// Delete the column with the given OID, as part of an ongoing action.
+fn delete_in(trans: &Connection, column_oid: i64) -> Result<(), error::Error> {
     match trans.query_one(
         "SELECT
             c.TYPE_OID,
@@ -389,20 +819,37 @@ pub fn delete(column_oid: i64) -> Result<(), error::Error> {
         }
     ).optional()? {
         Some((table_oid, column_type)) => {
+            // Drop any views that select from the column before it is dropped, then rebuild them once the column is gone
+            obj_type::drop_flattened_views(&trans, table_oid)?;
+            db::drop_friendly_view(&trans, table_oid)?;
+            trans.execute(
+                "UPDATE METADATA_TABLE_COLUMN SET TRASH = 1 WHERE OID = ?1", 
+                params![column_oid]
+            )?;
+            obj_type::update_flattened_views(&trans, table_oid)?;
+            db::update_friendly_view(&trans, table_oid)?;
+            table::update_primary_key_index(&trans, table_oid)?;
+
             match column_type {
                 data_type::MetadataColumnType::Primitive(_)
                 | data_type::MetadataColumnType::Reference(_)
                 | data_type::MetadataColumnType::ChildObject(_)  => {
                     // Delete the column from the data
+                    drop_child_object_trigger(&trans, table_oid, column_oid)?;
                     let alter_cmd = format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid};");
                     trans.execute(&alter_cmd, [])?;
+                    match &column_type {
+                        data_type::MetadataColumnType::Primitive(prim) if prim.is_file() => {
+                            drop_file_metadata_columns(&trans, table_oid, column_oid)?;
+                        },
+                        _ => {}
+                    }
 
                     // Delete the column from the metadata
                     trans.execute(
                         "DELETE FROM METADATA_TABLE_COLUMN WHERE OID = ?1", 
                         params![column_oid]
                     )?;
-                    trans.commit()?;
                     return Ok(());
                 },
                 data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
@@ -425,7 +872,6 @@ pub fn delete(column_oid: i64) -> Result<(), error::Error> {
                         "DELETE FROM METADATA_TYPE WHERE OID = ?1", 
                         params![column_type_oid]
                     )?;
-                    trans.commit()?;
                     return Ok(());
                 },
                 data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
@@ -448,7 +894,6 @@ pub fn delete(column_oid: i64) -> Result<(), error::Error> {
                         "DELETE FROM METADATA_TYPE WHERE OID = ?1", 
                         params![column_type_oid]
                     )?;
-                    trans.commit()?;
                     return Ok(());
                 },
                 data_type::MetadataColumnType::ChildTable(column_type_oid) => {
@@ -477,7 +922,6 @@ pub fn delete(column_oid: i64) -> Result<(), error::Error> {
                         "DELETE FROM METADATA_TYPE WHERE OID = ?1", 
                         params![column_type_oid]
                     )?;
-                    trans.commit()?;
                     return Ok(());
                 }
             }
@@ -493,7 +937,7 @@ pub fn get_metadata(column_oid: i64) -> Result<Option<Metadata>, error::Error> {
     let trans = conn.transaction()?;
 
     return Ok(trans.query_one(
-        "SELECT 
+        &format!("SELECT 
                 c.OID, 
                 c.NAME,
                 c.COLUMN_ORDERING, 
@@ -502,11 +946,13 @@ pub fn get_metadata(column_oid: i64) -> Result<Option<Metadata>, error::Error> {
                 t.MODE,
                 c.IS_NULLABLE,
                 c.IS_UNIQUE,
-                c.IS_PRIMARY_KEY
+                c.IS_PRIMARY_KEY,
+                c.IS_PINNED,
+                {DEFAULT_VALUE_TEXT_SQL} AS DEFAULT_VALUE
             FROM METADATA_TABLE_COLUMN c
             INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
             WHERE c.OID = ?1 
-            ORDER BY c.COLUMN_ORDERING ASC;",
+            ORDER BY c.COLUMN_ORDERING ASC;"),
          params![column_oid], 
         |row| {
             return Ok(Metadata {
@@ -518,18 +964,83 @@ pub fn get_metadata(column_oid: i64) -> Result<Option<Metadata>, error::Error> {
                 is_nullable: row.get("IS_NULLABLE")?,
                 is_unique: row.get("IS_UNIQUE")?,
                 is_primary_key: row.get("IS_PRIMARY_KEY")?,
+                is_pinned: row.get("IS_PINNED")?,
+                default_value: row.get("DEFAULT_VALUE")?,
             });
         }
     ).optional()?);
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The rules that a value of a column must follow, so that a value can be checked before it is submitted.
+pub struct ValidationRules {
+    column_type: data_type::MetadataColumnType,
+    is_nullable: bool,
+    is_unique: bool,
+    is_primary_key: bool,
+    default_value: Option<String>,
+    date_bound: Option<DateBound>,
+    allowed_values: Option<Vec<String>>
+}
+
+/// Gets the rules that a value of a column must follow.
+pub fn get_validation_rules(column_oid: i64) -> Result<Option<ValidationRules>, error::Error> {
+    let conn = db::open()?;
+    let rules = conn.query_one(
+        &format!("SELECT 
+                c.TYPE_OID, 
+                t.MODE,
+                c.IS_NULLABLE,
+                c.IS_UNIQUE,
+                c.IS_PRIMARY_KEY,
+                {DEFAULT_VALUE_TEXT_SQL} AS DEFAULT_VALUE,
+                c.DATE_BOUND
+            FROM METADATA_TABLE_COLUMN c
+            INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+            WHERE c.OID = ?1;"),
+        params![column_oid], 
+        |row| {
+            return Ok(ValidationRules {
+                column_type: data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?),
+                is_nullable: row.get("IS_NULLABLE")?,
+                is_unique: row.get("IS_UNIQUE")?,
+                is_primary_key: row.get("IS_PRIMARY_KEY")?,
+                default_value: row.get("DEFAULT_VALUE")?,
+                date_bound: parse_date_bound(row.get("DATE_BOUND")?),
+                allowed_values: None
+            });
+        }
+    ).optional()?;
+    return match rules {
+        Some(mut rules) => {
+            // A dropdown column only allows the values of its dropdown
+            match rules.column_type {
+                data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) 
+                | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+                    let mut allowed_values: Vec<String> = Vec::new();
+                    let select_cmd = format!("SELECT VALUE FROM TABLE{column_type_oid} WHERE TRASH = 0 ORDER BY ORDERING, OID;");
+                    db::query_iterate(&conn, &select_cmd, [], &mut |row| {
+                        allowed_values.push(row.get(0)?);
+                        return Ok(());
+                    })?;
+                    rules.allowed_values = Some(allowed_values);
+                },
+                _ => {}
+            }
+            Ok(Some(rules))
+        },
+        None => Ok(None)
+    };
+}
+
 /// Send a metadata list of columns.
 pub fn send_metadata_list(table_oid: i64, column_channel: Channel<Metadata>) -> Result<(), error::Error> {
     let mut conn = db::open()?;
     let trans = conn.transaction()?;
 
     db::query_iterate(&trans,
-        "SELECT 
+        &format!("SELECT 
                 c.OID, 
                 c.NAME, 
                 c.COLUMN_ORDERING,
@@ -538,11 +1049,13 @@ pub fn send_metadata_list(table_oid: i64, column_channel: Channel<Metadata>) ->
                 t.MODE,
                 c.IS_NULLABLE,
                 c.IS_UNIQUE,
-                c.IS_PRIMARY_KEY
+                c.IS_PRIMARY_KEY,
+                c.IS_PINNED,
+                {DEFAULT_VALUE_TEXT_SQL} AS DEFAULT_VALUE
             FROM METADATA_TABLE_COLUMN c
             INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
             WHERE c.TABLE_OID = ?1 AND c.TRASH = 0
-            ORDER BY c.COLUMN_ORDERING ASC;",
+            ORDER BY c.COLUMN_ORDERING ASC;"),
          params![table_oid], 
         &mut |row| {
             column_channel.send(Metadata {
@@ -554,6 +1067,8 @@ pub fn send_metadata_list(table_oid: i64, column_channel: Channel<Metadata>) ->
                 is_nullable: row.get("IS_NULLABLE")?,
                 is_unique: row.get("IS_UNIQUE")?,
                 is_primary_key: row.get("IS_PRIMARY_KEY")?,
+                is_pinned: row.get("IS_PINNED")?,
+                default_value: row.get("DEFAULT_VALUE")?,
             })?;
             return Ok(());
         }
@@ -572,11 +1087,15 @@ pub struct DropdownValue {
 
 /// Sets the possible values for a dropdown column.
 pub fn set_table_column_dropdown_values(column_oid: i64, dropdown_values: Vec<DropdownValue>) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(set_table_column_dropdown_values_in(&action, column_oid, dropdown_values));
+}
 
-    match trans.query_one(
+/// Sets the possible values for a dropdown column, as part of an ongoing action.
+fn set_table_column_dropdown_values_in(trans: &Connection, column_oid: i64, dropdown_values: Vec<DropdownValue>) -> Result<(), error::Error> {
+    let (table_oid, column_type) = trans.query_one(
         "SELECT 
+                c.TABLE_OID,
                 c.TYPE_OID, 
                 t.MODE
             FROM METADATA_TABLE_COLUMN c
@@ -584,12 +1103,13 @@ pub fn set_table_column_dropdown_values(column_oid: i64, dropdown_values: Vec<Dr
             WHERE c.OID = ?1;",
          params![column_oid], 
         |row| {
-            return Ok(data_type::MetadataColumnType::from_database(
-                row.get(0)?, 
-                row.get(1)?
-            ));
+            return Ok((row.get::<_, i64>(0)?, data_type::MetadataColumnType::from_database(
+                row.get(1)?, 
+                row.get(2)?
+            )));
         }
-    )? {
+    )?;
+    match column_type {
         data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) 
         | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
             // Flag all values in the corresponding table as trash
@@ -597,7 +1117,13 @@ pub fn set_table_column_dropdown_values(column_oid: i64, dropdown_values: Vec<Dr
             trans.execute(&flag_cmd, [])?;
 
             // Insert the new values
+            let check_duplicate_cmd = format!("SELECT EXISTS (SELECT 1 FROM TABLE{column_type_oid} WHERE TRASH = 0 AND VALUE = ?1);");
             for dropdown_value in dropdown_values.iter() {
+                // Check that the value is not a duplicate of a value already inserted
+                if trans.query_one(&check_duplicate_cmd, params![dropdown_value.display_value], |row| row.get::<_, bool>(0))? {
+                    return Err(error::Error::AdhocError("A dropdown cannot have the same value more than once."));
+                }
+
                 match &dropdown_value.true_value {
                     Some(dropdown_oid_str) => {
                         let dropdown_oid: i64 = match str::parse(&dropdown_oid_str) {
@@ -608,22 +1134,513 @@ pub fn set_table_column_dropdown_values(column_oid: i64, dropdown_values: Vec<Dr
                         UPDATE TABLE{column_type_oid} 
                         SET 
                             OID = (SELECT MAX(OID) AS NEW_OID FROM TABLE{column_type_oid}) + 1, 
-                            VALUE = ?1
+                            TRASH = 0,
+                            VALUE = ?1,
+                            ORDERING = (SELECT COALESCE(MAX(ORDERING), 0) FROM TABLE{column_type_oid}) + 1
                         WHERE OID = ?2;");
                         trans.execute(&update_cmd, params![dropdown_value.display_value, dropdown_oid])?;
                     },
                     None => {
-                        let insert_cmd = format!("INSERT INTO TABLE{column_type_oid} (VALUE) VALUES (?1);");
+                        let insert_cmd = format!("INSERT INTO TABLE{column_type_oid} (VALUE, ORDERING) VALUES (?1, (SELECT COALESCE(MAX(ORDERING), 0) FROM TABLE{column_type_oid}) + 1);");
                         trans.execute(&insert_cmd, params![dropdown_value.display_value])?;
                     }
                 }
             }
+
+            // Recompute the display values built from the dropdown values
+            table::refresh_display_value_cache(trans, table_oid, None)?;
         },
         _ => {}
     };
     return Ok(());
 }
 
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A value that appears more than once in a dropdown.
+pub struct DuplicateDropdownValue {
+    value: String,
+    value_oids: Vec<i64>
+}
+
+/// Finds every value that appears more than once among the non-trashed values of a dropdown column.
+pub fn find_duplicate_dropdown_values(column_oid: i64) -> Result<Vec<DuplicateDropdownValue>, error::Error> {
+    let conn = db::open()?;
+    let column_type_oid = get_dropdown_type_oid(&conn, column_oid)?;
+
+    let mut duplicates: Vec<DuplicateDropdownValue> = Vec::new();
+    let select_cmd = format!("
+    SELECT 
+        VALUE, 
+        GROUP_CONCAT(CAST(OID AS TEXT)) AS VALUE_OIDS 
+    FROM TABLE{column_type_oid} 
+    WHERE TRASH = 0 
+    GROUP BY VALUE 
+    HAVING COUNT(OID) > 1 
+    ORDER BY MIN(ORDERING);");
+    db::query_iterate(&conn, &select_cmd, [], 
+        &mut |row| {
+            let value_oids_str: String = row.get("VALUE_OIDS")?;
+            duplicates.push(DuplicateDropdownValue {
+                value: row.get("VALUE")?,
+                value_oids: value_oids_str.split(',').filter_map(|s| s.parse::<i64>().ok()).collect()
+            });
+            return Ok(());
+        }
+    )?;
+    return Ok(duplicates);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// The outcome of importing a list of values into a dropdown.
+pub struct DropdownImportSummary {
+    pub added_value_oids: Vec<i64>,
+    pub skipped_count: usize
+}
+
+/// Gets the OID of the table that stores the values of a dropdown column.
+fn get_dropdown_type_oid(trans: &Connection, column_oid: i64) -> Result<i64, error::Error> {
+    match trans.query_one(
+        "SELECT 
+                c.TYPE_OID, 
+                t.MODE
+            FROM METADATA_TABLE_COLUMN c
+            INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+            WHERE c.OID = ?1;",
+         params![column_oid], 
+        |row| {
+            return Ok(data_type::MetadataColumnType::from_database(
+                row.get(0)?, 
+                row.get(1)?
+            ));
+        }
+    )? {
+        data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) 
+        | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+            return Ok(column_type_oid);
+        },
+        _ => {
+            return Err(error::Error::AdhocError("The column is not a dropdown."));
+        }
+    }
+}
+
+/// Appends a list of text values to the possible values for a dropdown column, skipping any that are already present.
+pub fn import_dropdown_values(column_oid: i64, values: Vec<String>) -> Result<DropdownImportSummary, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(import_dropdown_values_in(&action, column_oid, values));
+}
+
+/// Appends a list of text values to the possible values for a dropdown column, as part of an ongoing action.
+pub fn import_dropdown_values_in(trans: &Connection, column_oid: i64, values: Vec<String>) -> Result<DropdownImportSummary, error::Error> {
+    let column_type_oid = get_dropdown_type_oid(trans, column_oid)?;
+    let select_cmd = format!("SELECT OID, TRASH FROM TABLE{column_type_oid} WHERE VALUE = ?1 ORDER BY TRASH ASC LIMIT 1;");
+    let restore_cmd = format!("UPDATE TABLE{column_type_oid} SET TRASH = 0 WHERE OID = ?1;");
+    let insert_cmd = format!("INSERT INTO TABLE{column_type_oid} (VALUE, ORDERING) VALUES (?1, (SELECT COALESCE(MAX(ORDERING), 0) FROM TABLE{column_type_oid}) + 1);");
+
+    let mut summary = DropdownImportSummary {
+        added_value_oids: Vec::new(),
+        skipped_count: 0
+    };
+    for value in values.iter() {
+        let value = value.trim();
+        if value.is_empty() {
+            summary.skipped_count += 1;
+            continue;
+        }
+
+        // Check whether the value already exists
+        match trans.query_one(&select_cmd, params![value], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?))).optional()? {
+            Some((_, false)) => {
+                // Skip duplicates
+                summary.skipped_count += 1;
+            },
+            Some((value_oid, true)) => {
+                // Restore a value that was previously trashed
+                trans.execute(&restore_cmd, params![value_oid])?;
+                summary.added_value_oids.push(value_oid);
+            },
+            None => {
+                // Insert the new value
+                trans.execute(&insert_cmd, params![value])?;
+                summary.added_value_oids.push(trans.last_insert_rowid());
+            }
+        }
+    }
+    return Ok(summary);
+}
+
+/// Flags or unflags the given values of a dropdown column as trash.
+/// A trashed value can no longer be picked, but cells already set to the value continue to display it.
+pub fn set_dropdown_values_trash(column_oid: i64, value_oids: &Vec<i64>, is_trash: bool) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_dropdown_values_trash_in(&action, column_oid, value_oids, is_trash));
+}
+
+/// Flags or unflags the given values of a dropdown column as trash, as part of an ongoing action.
+fn set_dropdown_values_trash_in(trans: &Connection, column_oid: i64, value_oids: &Vec<i64>, is_trash: bool) -> Result<(), error::Error> {
+    let column_type_oid = get_dropdown_type_oid(trans, column_oid)?;
+    let update_cmd = format!("UPDATE TABLE{column_type_oid} SET TRASH = ?1 WHERE OID = ?2;");
+    for value_oid in value_oids.iter() {
+        match trans.execute(&update_cmd, params![is_trash, value_oid]) {
+            Ok(_) => {},
+            Err(RusqliteError::SqliteFailure(e, _)) if e.code == ErrorCode::ConstraintViolation => {
+                return Err(error::Error::AdhocError("A dropdown cannot have the same value more than once."));
+            },
+            Err(e) => {
+                return Err(error::Error::from(e));
+            }
+        }
+    }
+    return Ok(());
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all="camelCase")]
+/// The rows affected by merging one dropdown value into another.
+pub struct DropdownMerge {
+    /// The rows whose cells were changed from the merged value to the value it was merged into.
+    pub repointed_row_oids: Vec<i64>,
+    /// The rows of a multi-select dropdown that already had both values, and so only lost the merged value.
+    pub removed_row_oids: Vec<i64>
+}
+
+/// Merges one value of a dropdown column into another, then flags the merged value as trash.
+/// Returns the rows that were affected, so that the merge can be undone.
+pub fn merge_dropdown_values_tracked(column_oid: i64, from_value_oid: i64, into_value_oid: i64) -> Result<DropdownMerge, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(merge_dropdown_values_in(&action, column_oid, from_value_oid, into_value_oid));
+}
+
+/// Merges one value of a dropdown column into another, as part of an ongoing action.
+fn merge_dropdown_values_in(trans: &Connection, column_oid: i64, from_value_oid: i64, into_value_oid: i64) -> Result<DropdownMerge, error::Error> {
+    if from_value_oid == into_value_oid {
+        return Err(error::Error::AdhocError("Cannot merge a dropdown value into itself."));
+    }
+    let (table_oid, column_type) = trans.query_one(
+        "SELECT 
+                c.TABLE_OID,
+                c.TYPE_OID, 
+                t.MODE
+            FROM METADATA_TABLE_COLUMN c
+            INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+            WHERE c.OID = ?1;",
+        params![column_oid], 
+        |row| {
+            return Ok((row.get::<_, i64>(0)?, data_type::MetadataColumnType::from_database(row.get(1)?, row.get(2)?)));
+        }
+    )?;
+
+    let mut merge = DropdownMerge {
+        repointed_row_oids: Vec::new(),
+        removed_row_oids: Vec::new()
+    };
+    match column_type {
+        data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
+            // Find every row set to the merged value
+            let select_cmd = format!("SELECT OID FROM TABLE{table_oid} WHERE COLUMN{column_oid} = ?1;");
+            db::query_iterate(trans, &select_cmd, params![from_value_oid], 
+                &mut |row| {
+                    merge.repointed_row_oids.push(row.get(0)?);
+                    return Ok(());
+                }
+            )?;
+
+            // Repoint those rows to the value being merged into
+            let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE COLUMN{column_oid} = ?2;");
+            trans.execute(&update_cmd, params![into_value_oid, from_value_oid])?;
+
+            // Flag the merged value as trash
+            let trash_cmd = format!("UPDATE TABLE{column_type_oid} SET TRASH = 1 WHERE OID = ?1;");
+            trans.execute(&trash_cmd, params![from_value_oid])?;
+        },
+        data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+            // Find every row with the merged value, noting which rows already have the value being merged into
+            let select_cmd = format!("
+            SELECT 
+                a.ROW_OID,
+                EXISTS (SELECT 1 FROM TABLE{column_type_oid}_MULTISELECT b WHERE b.ROW_OID = a.ROW_OID AND b.VALUE_OID = ?2) AS HAS_INTO_VALUE
+            FROM TABLE{column_type_oid}_MULTISELECT a 
+            WHERE a.VALUE_OID = ?1;");
+            db::query_iterate(trans, &select_cmd, params![from_value_oid, into_value_oid], 
+                &mut |row| {
+                    if row.get::<_, bool>(1)? {
+                        merge.removed_row_oids.push(row.get(0)?);
+                    } else {
+                        merge.repointed_row_oids.push(row.get(0)?);
+                    }
+                    return Ok(());
+                }
+            )?;
+
+            // Repoint the links to the value being merged into, dropping any that would become duplicates
+            let update_cmd = format!("UPDATE OR IGNORE TABLE{column_type_oid}_MULTISELECT SET VALUE_OID = ?1 WHERE VALUE_OID = ?2;");
+            trans.execute(&update_cmd, params![into_value_oid, from_value_oid])?;
+            let delete_cmd = format!("DELETE FROM TABLE{column_type_oid}_MULTISELECT WHERE VALUE_OID = ?1;");
+            trans.execute(&delete_cmd, params![from_value_oid])?;
+
+            // Flag the merged value as trash
+            let trash_cmd = format!("UPDATE TABLE{column_type_oid} SET TRASH = 1 WHERE OID = ?1;");
+            trans.execute(&trash_cmd, params![from_value_oid])?;
+        },
+        _ => {
+            return Err(error::Error::AdhocError("The column is not a dropdown."));
+        }
+    }
+
+    // Recompute the display values of the affected rows
+    table::refresh_display_value_cache(trans, table_oid, None)?;
+    return Ok(merge);
+}
+
+/// Undoes a merge of one dropdown value into another, restoring the merged value and repointing the affected rows back to it.
+pub fn split_dropdown_values(column_oid: i64, from_value_oid: i64, into_value_oid: i64, merge: &DropdownMerge) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(split_dropdown_values_in(&action, column_oid, from_value_oid, into_value_oid, merge));
+}
+
+/// Undoes a merge of one dropdown value into another, as part of an ongoing action.
+fn split_dropdown_values_in(trans: &Connection, column_oid: i64, from_value_oid: i64, into_value_oid: i64, merge: &DropdownMerge) -> Result<(), error::Error> {
+    let (table_oid, column_type) = trans.query_one(
+        "SELECT 
+                c.TABLE_OID,
+                c.TYPE_OID, 
+                t.MODE
+            FROM METADATA_TABLE_COLUMN c
+            INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+            WHERE c.OID = ?1;",
+        params![column_oid], 
+        |row| {
+            return Ok((row.get::<_, i64>(0)?, data_type::MetadataColumnType::from_database(row.get(1)?, row.get(2)?)));
+        }
+    )?;
+
+    match column_type {
+        data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
+            // Restore the merged value
+            let restore_cmd = format!("UPDATE TABLE{column_type_oid} SET TRASH = 0 WHERE OID = ?1;");
+            trans.execute(&restore_cmd, params![from_value_oid])?;
+
+            // Repoint the affected rows back to the merged value
+            let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE OID = ?2 AND COLUMN{column_oid} = ?3;");
+            for row_oid in merge.repointed_row_oids.iter() {
+                trans.execute(&update_cmd, params![from_value_oid, row_oid, into_value_oid])?;
+            }
+        },
+        data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+            // Restore the merged value
+            let restore_cmd = format!("UPDATE TABLE{column_type_oid} SET TRASH = 0 WHERE OID = ?1;");
+            trans.execute(&restore_cmd, params![from_value_oid])?;
+
+            // Repoint the affected links back to the merged value
+            let update_cmd = format!("UPDATE TABLE{column_type_oid}_MULTISELECT SET VALUE_OID = ?1 WHERE ROW_OID = ?2 AND VALUE_OID = ?3;");
+            for row_oid in merge.repointed_row_oids.iter() {
+                trans.execute(&update_cmd, params![from_value_oid, row_oid, into_value_oid])?;
+            }
+
+            // Re-add the links that were dropped as duplicates
+            let insert_cmd = format!("INSERT OR IGNORE INTO TABLE{column_type_oid}_MULTISELECT (ROW_OID, VALUE_OID) VALUES (?1, ?2);");
+            for row_oid in merge.removed_row_oids.iter() {
+                trans.execute(&insert_cmd, params![row_oid, from_value_oid])?;
+            }
+        },
+        _ => {
+            return Err(error::Error::AdhocError("The column is not a dropdown."));
+        }
+    }
+
+    // Recompute the display values of the affected rows
+    table::refresh_display_value_cache(trans, table_oid, None)?;
+    return Ok(());
+}
+
+/// Reorders the values of a dropdown column.
+/// The given values are placed first, in the given order, followed by any other values in their existing order.
+pub fn reorder_dropdown_values(column_oid: i64, ordered_value_oids: Vec<i64>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(reorder_dropdown_values_in(&action, column_oid, ordered_value_oids));
+}
+
+/// Reorders the values of a dropdown column, as part of an ongoing action.
+fn reorder_dropdown_values_in(trans: &Connection, column_oid: i64, ordered_value_oids: Vec<i64>) -> Result<(), error::Error> {
+    let column_type_oid = get_dropdown_type_oid(trans, column_oid)?;
+
+    // Append every value that was not explicitly ordered, in its existing order
+    let mut value_oids: Vec<i64> = ordered_value_oids.clone();
+    let select_cmd = format!("SELECT OID FROM TABLE{column_type_oid} ORDER BY ORDERING, OID;");
+    db::query_iterate(trans, &select_cmd, [], 
+        &mut |row| {
+            let value_oid: i64 = row.get(0)?;
+            if !ordered_value_oids.contains(&value_oid) {
+                value_oids.push(value_oid);
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Update the ordering of each value
+    let update_cmd = format!("UPDATE TABLE{column_type_oid} SET ORDERING = ?1 WHERE OID = ?2;");
+    for (ordering, value_oid) in value_oids.iter().enumerate() {
+        trans.execute(&update_cmd, params![ordering as i64 + 1, value_oid])?;
+    }
+    return Ok(());
+}
+
+/// Recreates the stored column of a dropdown or reference column so that it points to a different table, keeping the OID stored in each cell.
+fn repoint_column_in(trans: &Connection, table_oid: i64, column_oid: i64, column_type: data_type::MetadataColumnType) -> Result<(), error::Error> {
+    // Drop any views and indexes that select from the column before it is dropped
+    obj_type::drop_flattened_views(trans, table_oid)?;
+    db::drop_friendly_view(trans, table_oid)?;
+    table::drop_primary_key_index(trans, table_oid)?;
+
+    // Hold onto the values of the column while it is recreated
+    let create_temp_cmd = format!("CREATE TABLE TRANS_COLUMN{column_oid} AS SELECT OID, COLUMN{column_oid} AS VALUE FROM TABLE{table_oid};");
+    trans.execute(&create_temp_cmd, [])?;
+    drop_child_object_trigger(trans, table_oid, column_oid)?;
+    let alter_cmd = format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid};");
+    trans.execute(&alter_cmd, [])?;
+
+    // Point the column at the new table
+    trans.execute(
+        "UPDATE METADATA_TABLE_COLUMN SET TYPE_OID = ?1 WHERE OID = ?2;", 
+        params![column_type.get_type_oid(), column_oid]
+    )?;
+    let referenced_table_oid = column_type.get_type_oid();
+    let alter_table_cmd = format!("ALTER TABLE TABLE{table_oid} ADD COLUMN COLUMN{column_oid} INTEGER REFERENCES TABLE{referenced_table_oid} (OID) ON UPDATE CASCADE ON DELETE SET NULL;");
+    trans.execute(&alter_table_cmd, [])?;
+    if let data_type::MetadataColumnType::ChildObject(_) = column_type {
+        create_child_object_trigger(trans, table_oid, column_oid, referenced_table_oid)?;
+    }
+
+    // Copy the values back
+    let copy_cmd = format!("
+    UPDATE TABLE{table_oid} AS t
+    SET COLUMN{column_oid} = trans.VALUE
+    FROM TRANS_COLUMN{column_oid} AS trans
+    WHERE t.OID = trans.OID;
+    DROP TABLE TRANS_COLUMN{column_oid};");
+    trans.execute_batch(&copy_cmd)?;
+
+    // Rebuild the views and indexes of the table
+    table::update_surrogate_view(trans, table_oid)?;
+    table::update_primary_key_index(trans, table_oid)?;
+    obj_type::update_flattened_views(trans, table_oid)?;
+    db::update_friendly_view(trans, table_oid)?;
+    return Ok(());
+}
+
+/// Converts the values of a single-select dropdown column into a standalone table, which the column then references.
+/// Other columns can reference the same table, so the values are managed centrally; renaming or trashing a value affects every column that uses it, and new values are added as rows of the table rather than through the column.
+/// Returns the OID of the created table.
+pub fn promote_dropdown_to_shared(column_oid: i64) -> Result<i64, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(promote_dropdown_to_shared_in(&action, column_oid));
+    log::debug!("Promoted dropdown of COLUMN{column_oid} to a shared table in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Converts the values of a single-select dropdown column into a standalone table, as part of an ongoing action.
+fn promote_dropdown_to_shared_in(trans: &Connection, column_oid: i64) -> Result<i64, error::Error> {
+    // Find the dropdown and the name of the column
+    let (table_oid, column_name, column_type): (i64, String, data_type::MetadataColumnType) = trans.query_one(
+        "SELECT 
+            c.TABLE_OID, 
+            c.NAME, 
+            c.TYPE_OID, 
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.OID = ?1 AND c.TRASH = 0;", 
+        params![column_oid], 
+        |row| Ok((row.get(0)?, row.get(1)?, data_type::MetadataColumnType::from_database(row.get(2)?, row.get(3)?)))
+    ).optional()?
+    .ok_or(error::Error::AdhocError("The column does not exist."))?;
+    let dropdown_type_oid = match column_type {
+        data_type::MetadataColumnType::SingleSelectDropdown(dropdown_type_oid) => dropdown_type_oid,
+        data_type::MetadataColumnType::MultiSelectDropdown(_) => {
+            return Err(error::Error::AdhocError("A multi-select dropdown cannot be converted to a shared table, as a reference holds a single row."));
+        },
+        _ => {
+            return Err(error::Error::AdhocError("The column is not a dropdown."));
+        }
+    };
+
+    // Create the shared table, named after the column, with the value as its primary key
+    let shared_table_oid = table::create_in(trans, column_name, &Vec::new())?;
+    let value_column_oid = create_in(trans, shared_table_oid, "Value", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", false, true, true)?;
+
+    // Copy each dropdown value into a row with the same OID, so that the cells of the column keep pointing at the same value
+    let copy_cmd = format!("
+    INSERT INTO TABLE{shared_table_oid} (OID, TRASH, ORDER_KEY, COLUMN{value_column_oid})
+    SELECT OID, TRASH, ORDERING, VALUE FROM TABLE{dropdown_type_oid};");
+    trans.execute(&copy_cmd, [])?;
+
+    // Point the column at the shared table, then remove the dropdown
+    repoint_column_in(trans, table_oid, column_oid, data_type::MetadataColumnType::Reference(shared_table_oid))?;
+    let drop_cmd = format!("DROP TABLE TABLE{dropdown_type_oid};");
+    trans.execute(&drop_cmd, [])?;
+    trans.execute(
+        "DELETE FROM METADATA_TYPE WHERE OID = ?1;", 
+        params![dropdown_type_oid]
+    )?;
+    return Ok(shared_table_oid);
+}
+
+/// Converts a column referencing a table that was promoted from its dropdown back into a single-select dropdown column.
+/// The display value of each row of the table becomes a dropdown value, and the table is moved to the trash.
+/// This fails if any other column has come to reference the table since it was promoted.
+pub fn demote_shared_to_dropdown(column_oid: i64, shared_table_oid: i64) -> Result<(), error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(demote_shared_to_dropdown_in(&action, column_oid, shared_table_oid));
+    log::debug!("Demoted TABLE{shared_table_oid} to the dropdown of COLUMN{column_oid} in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Converts a column referencing a shared table back into a single-select dropdown column, as part of an ongoing action.
+fn demote_shared_to_dropdown_in(trans: &Connection, column_oid: i64, shared_table_oid: i64) -> Result<(), error::Error> {
+    // Make sure the column references the table, and that nothing else does
+    let table_oid: i64 = trans.query_one(
+        "SELECT TABLE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1 AND TYPE_OID = ?2;", 
+        params![column_oid, shared_table_oid], 
+        |row| row.get(0)
+    ).optional()?
+    .ok_or(error::Error::AdhocError("The column does not reference the given table."))?;
+    let other_reference_count: i64 = trans.query_one(
+        "SELECT COUNT(*) FROM METADATA_TABLE_COLUMN WHERE TYPE_OID = ?1 AND OID <> ?2 AND TRASH = 0;", 
+        params![shared_table_oid, column_oid], 
+        |row| row.get(0)
+    )?;
+    if other_reference_count > 0 {
+        return Err(error::Error::AdhocError("The table cannot be turned back into a dropdown while other columns reference it."));
+    }
+
+    // Create the dropdown and copy the display value of each row into it, keeping the OID of the row
+    let column_type = data_type::MetadataColumnType::SingleSelectDropdown(0).create_for_table(trans, &table_oid)?;
+    let dropdown_type_oid = column_type.get_type_oid();
+    let copy_cmd = format!("
+    INSERT INTO TABLE{dropdown_type_oid} (OID, TRASH, VALUE, ORDERING)
+    SELECT t.OID, t.TRASH, COALESCE(s.DISPLAY_VALUE, ''), ROW_NUMBER() OVER (ORDER BY t.ORDER_KEY, t.OID)
+    FROM TABLE{shared_table_oid} t
+    LEFT JOIN TABLE{shared_table_oid}_SURROGATE s ON s.OID = t.OID;");
+    match trans.execute(&copy_cmd, []) {
+        Ok(_) => {},
+        Err(RusqliteError::SqliteFailure(e, _)) if e.code == ErrorCode::ConstraintViolation => {
+            return Err(error::Error::Conflict("Rows of the table share a display value, so they cannot be turned into dropdown values."));
+        },
+        Err(e) => {
+            return Err(e.into());
+        }
+    }
+
+    // Point the column at the dropdown, then move the shared table to the trash
+    repoint_column_in(trans, table_oid, column_oid, column_type)?;
+    table::move_trash_in(trans, shared_table_oid)?;
+    return Ok(());
+}
+
 /// Retrieves the list of allowed dropdown values for a column.
 pub fn get_table_column_dropdown_values(column_oid: i64) -> Result<Vec<DropdownValue>, error::Error> {
     let mut conn = db::open()?;
@@ -648,7 +1665,7 @@ pub fn get_table_column_dropdown_values(column_oid: i64) -> Result<Vec<DropdownV
         data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) 
         | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
             // Select the values from the corresponding table
-            let select_cmd = format!("SELECT VALUE FROM TABLE{column_type_oid};");
+            let select_cmd = format!("SELECT VALUE FROM TABLE{column_type_oid} WHERE TRASH = 0 ORDER BY ORDERING, OID;");
             db::query_iterate(&trans, 
                 &select_cmd, 
                 [], 
@@ -688,7 +1705,7 @@ pub fn send_table_column_dropdown_values(column_oid: i64, dropdown_value_channel
         data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) 
         | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
             // Select the values from the corresponding table
-            let select_cmd = format!("SELECT VALUE FROM TABLE{column_type_oid};");
+            let select_cmd = format!("SELECT VALUE FROM TABLE{column_type_oid} WHERE TRASH = 0 ORDER BY ORDERING, OID;");
             db::query_iterate(&trans, 
                 &select_cmd, 
                 [], 
@@ -719,6 +1736,38 @@ pub fn send_table_column_dropdown_values(column_oid: i64, dropdown_value_channel
     return Ok(());
 }
 
+/// Retrieves the list of options that a cell of the given column type can be set to, as part of an ongoing action.
+/// Returns None if the column type is not a dropdown or reference.
+pub fn get_options_in(trans: &Connection, column_type: &data_type::MetadataColumnType) -> Result<Option<Vec<DropdownValue>>, error::Error> {
+    let select_cmd = match column_type {
+        data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) 
+        | data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => {
+            format!("SELECT CAST(OID AS TEXT) AS OID, VALUE AS DISPLAY_VALUE FROM TABLE{column_type_oid} WHERE TRASH = 0 ORDER BY ORDERING, OID;")
+        },
+        data_type::MetadataColumnType::Reference(referenced_table_oid) => {
+            format!("SELECT CAST(OID AS TEXT) AS OID, DISPLAY_VALUE FROM TABLE{referenced_table_oid}_SURROGATE;")
+        },
+        _ => {
+            return Ok(None);
+        }
+    };
+
+    // Select the values from the corresponding table or view
+    let mut options: Vec<DropdownValue> = Vec::new();
+    db::query_iterate(trans, 
+        &select_cmd, 
+        [], 
+        &mut |row| {
+            options.push(DropdownValue { 
+                true_value: row.get::<_, Option<String>>("OID")?, 
+                display_value: row.get::<_, Option<String>>("DISPLAY_VALUE")? 
+            });
+            return Ok(());
+        }
+    )?;
+    return Ok(Some(options));
+}
+
 
 #[derive(Serialize)]
 pub struct BasicTypeMetadata {
@@ -750,4 +1799,299 @@ pub fn send_type_metadata_list(column_type: data_type::MetadataColumnType, type_
         }
     )?;
     return Ok(());
-}
\ No newline at end of file
+}
+
+/// Send metadata for each object type that could be used as the type of a child object column in the given table.
+/// Object types that already contain the table, directly or through their own child objects, child tables, or inherited columns, are excluded, as using them would make the table contain itself.
+pub fn send_child_object_type_list(table_oid: i64, type_channel: Channel<BasicTypeMetadata>) -> Result<(), error::Error> {
+    let action = db::begin_readonly_db_action()?;
+
+    action.query_iterate(
+        "WITH RECURSIVE CONTAINER (TABLE_OID) AS (
+            SELECT ?1
+            UNION
+            -- Any table with a child object or child table column whose type contains the table
+            SELECT col.TABLE_OID
+            FROM METADATA_TABLE_COLUMN col
+            INNER JOIN METADATA_TYPE col_typ ON col_typ.OID = col.TYPE_OID
+            INNER JOIN CONTAINER c ON c.TABLE_OID = col.TYPE_OID
+            WHERE col.TRASH = 0 AND col_typ.MODE IN (4, 5)
+            UNION
+            -- Any table that inherits the columns of a table containing the table
+            SELECT inh.INHERITOR_TABLE_OID
+            FROM METADATA_TABLE_INHERITANCE inh
+            INNER JOIN CONTAINER c ON c.TABLE_OID = inh.MASTER_TABLE_OID
+            WHERE inh.TRASH = 0
+        )
+        SELECT 
+            tbl.TYPE_OID AS OID,
+            tbl.NAME
+        FROM METADATA_TABLE tbl
+        INNER JOIN METADATA_TYPE typ ON typ.OID = tbl.TYPE_OID
+        WHERE typ.MODE = 4 AND tbl.TRASH = 0
+            AND tbl.TYPE_OID NOT IN (SELECT TABLE_OID FROM CONTAINER)
+        ORDER BY tbl.NAME;", 
+        [table_oid], 
+        &mut |row| {
+            type_channel.send(BasicTypeMetadata {
+                oid: row.get("OID")?,
+                name: row.get("NAME")?
+            })?;
+            return Ok(());
+        }
+    )?;
+    return Ok(());
+}
+
+/// Send metadata for each table that could be referenced by a reference column, optionally excluding one table.
+pub fn send_reference_type_list(excluded_table_oid: Option<i64>, type_channel: Channel<BasicTypeMetadata>) -> Result<(), error::Error> {
+    let action = db::begin_readonly_db_action()?;
+
+    action.query_iterate(
+        "SELECT 
+            tbl.TYPE_OID AS OID,
+            tbl.NAME
+        FROM METADATA_TABLE tbl
+        INNER JOIN METADATA_TYPE typ ON typ.OID = tbl.TYPE_OID
+        WHERE typ.MODE = 3 AND (?1 IS NULL OR tbl.TYPE_OID <> ?1)
+        ORDER BY tbl.NAME;", 
+        [excluded_table_oid], 
+        &mut |row| {
+            type_channel.send(BasicTypeMetadata {
+                oid: row.get("OID")?,
+                name: row.get("NAME")?
+            })?;
+            return Ok(());
+        }
+    )?;
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{table_data, test_util};
+
+    #[test]
+    fn file_columns_keep_name_and_mime_type() {
+        let db = test_util::open_test_db();
+        let file_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::File);
+        let image_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Image);
+
+        // Simulate a file column created before files kept their name and MIME type
+        let table_oid = table::create(String::from("Documents"), &vec![]).unwrap();
+        let column_oid = create(table_oid, "Scan", file_type, None, "", true, false, false, None).unwrap();
+        {
+            let conn = db::open().unwrap();
+            conn.execute(&format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid}_NAME;"), []).unwrap();
+            conn.execute(&format!("ALTER TABLE TABLE{table_oid} DROP COLUMN COLUMN{column_oid}_MIME;"), []).unwrap();
+        }
+
+        // Reopening the database adds the missing columns back
+        db::init(db.path.to_string_lossy().into_owned()).unwrap();
+        let row_oid = table_data::push(table_oid).unwrap();
+        table_data::set_file(table_oid, row_oid, column_oid, Some(table_data::File {
+            name: Some(String::from("scan.png")),
+            mime_type: Some(String::from("image/png")),
+            data: vec![1, 2, 3]
+        })).unwrap();
+
+        // Switching the column to an image keeps the name and MIME type
+        edit(table_oid, column_oid, "Scan", image_type, "", true, false, false, false, None).unwrap();
+        let file = table_data::get_file(table_oid, row_oid, column_oid).unwrap().unwrap();
+        assert_eq!(file.name.as_deref(), Some("scan.png"));
+        assert_eq!(file.mime_type.as_deref(), Some("image/png"));
+        assert_eq!(file.data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn dropdown_changes_refresh_display_values() {
+        let _db = test_util::open_test_db();
+
+        // Create a table keyed by a dropdown, caching its display values both in memory and in the table
+        let table_oid = table::create(String::from("Fruit"), &vec![]).unwrap();
+        let column_oid = create(table_oid, "Kind", data_type::MetadataColumnType::SingleSelectDropdown(0), None, "", true, false, true, None).unwrap();
+        let dropdown_oid = get_dropdown_type_oid(&db::open().unwrap(), column_oid).unwrap();
+        table::set_display_value_materialized(table_oid, true).unwrap();
+        let row_oid = table_data::push(table_oid).unwrap();
+        {
+            let conn = db::open().unwrap();
+            conn.execute(&format!("INSERT INTO TABLE{dropdown_oid} (OID, VALUE, ORDERING) VALUES (1, 'Apple', 1), (2, 'Pear', 2);"), []).unwrap();
+            conn.execute(&format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = 1 WHERE OID = ?1;"), params![row_oid]).unwrap();
+            table::refresh_display_value_cache(&conn, table_oid, None).unwrap();
+        }
+        let get_cached_display_value = || db::open().unwrap()
+            .query_one(&format!("SELECT DISPLAY_VALUE_CACHE FROM TABLE{table_oid} WHERE OID = ?1;"), params![row_oid], |row| row.get::<_, Option<String>>(0))
+            .unwrap();
+        assert_eq!(table::get_surrogate_display(table_oid, row_oid).unwrap().as_deref(), Some("Apple"));
+        assert_eq!(get_cached_display_value().as_deref(), Some("Apple"));
+
+        // Merging the row's value into another shows the other value
+        let merge = merge_dropdown_values_tracked(column_oid, 1, 2).unwrap();
+        assert_eq!(table::get_surrogate_display(table_oid, row_oid).unwrap().as_deref(), Some("Pear"));
+        assert_eq!(get_cached_display_value().as_deref(), Some("Pear"));
+
+        // Undoing the merge shows the original value again
+        split_dropdown_values(column_oid, 1, 2, &merge).unwrap();
+        assert_eq!(table::get_surrogate_display(table_oid, row_oid).unwrap().as_deref(), Some("Apple"));
+        assert_eq!(get_cached_display_value().as_deref(), Some("Apple"));
+
+        // Renaming the row's value shows the new name
+        set_table_column_dropdown_values(column_oid, vec![
+            DropdownValue { true_value: Some(String::from("1")), display_value: Some(String::from("Apricot")) },
+            DropdownValue { true_value: Some(String::from("2")), display_value: Some(String::from("Pear")) }
+        ]).unwrap();
+        assert_eq!(table::get_surrogate_display(table_oid, row_oid).unwrap().as_deref(), Some("Apricot"));
+        assert_eq!(get_cached_display_value().as_deref(), Some("Apricot"));
+    }
+
+    #[test]
+    fn deleting_owner_deletes_child_object() {
+        let _db = test_util::open_test_db();
+
+        // Create a table owning an object and referencing another table
+        let engine_oid = obj_type::create(String::from("Engine"), &vec![]).unwrap();
+        let maker_oid = table::create(String::from("Makers"), &vec![]).unwrap();
+        let car_oid = table::create(String::from("Cars"), &vec![]).unwrap();
+        let engine_column_oid = create(car_oid, "Engine", data_type::MetadataColumnType::ChildObject(engine_oid), None, "", true, false, false, None).unwrap();
+        let maker_column_oid = create(car_oid, "Maker", data_type::MetadataColumnType::Reference(maker_oid), None, "", true, false, false, None).unwrap();
+        let conn = db::open().unwrap();
+        let count_rows = |table_oid: i64| conn.query_one(&format!("SELECT COUNT(*) FROM TABLE{table_oid};"), [], |row| row.get::<_, i64>(0)).unwrap();
+        let get_cell = |column_oid: i64, row_oid: i64| conn.query_one(&format!("SELECT COLUMN{column_oid} FROM TABLE{car_oid} WHERE OID = ?1;"), params![row_oid], |row| row.get::<_, Option<i64>>(0)).unwrap();
+        conn.execute_batch(&format!("
+        INSERT INTO TABLE{engine_oid} (OID) VALUES (1), (2);
+        INSERT INTO TABLE{maker_oid} (OID) VALUES (1);
+        INSERT INTO TABLE{car_oid} (OID, COLUMN{engine_column_oid}, COLUMN{maker_column_oid}) VALUES (1, 1, 1), (2, 2, 1);")).unwrap();
+
+        // Deleting a referenced row clears the reference
+        table_data::delete(maker_oid, 1).unwrap();
+        assert_eq!(get_cell(maker_column_oid, 1), None);
+        assert_eq!(count_rows(car_oid), 2);
+
+        // Deleting a child object clears the cell of its owner, without deleting the owner
+        table_data::delete(engine_oid, 2).unwrap();
+        assert_eq!(get_cell(engine_column_oid, 2), None);
+        assert_eq!(count_rows(car_oid), 2);
+
+        // Deleting the owner deletes its child object, and undoing the delete restores both
+        let snapshot = table_data::delete(car_oid, 1).unwrap();
+        assert_eq!(count_rows(engine_oid), 0);
+        assert_eq!(count_rows(car_oid), 1);
+        table_data::restore_deleted(snapshot).unwrap();
+        assert_eq!(count_rows(engine_oid), 1);
+        assert_eq!(get_cell(engine_column_oid, 1), Some(1));
+    }
+
+    #[test]
+    fn reordered_columns_are_sent_in_their_new_order() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+        let table_oid = table::create(String::from("Cities"), &vec![]).unwrap();
+        let column_oids: Vec<i64> = [("City", true), ("Country", true), ("Population", false)].into_iter()
+            .map(|(name, is_primary_key)| create(table_oid, name, text_type.clone(), None, "", true, false, is_primary_key, None).unwrap())
+            .collect();
+        db::open().unwrap().execute(&format!("INSERT INTO TABLE{table_oid} (OID, COLUMN{}, COLUMN{}) VALUES (1, 'Paris', 'France');", column_oids[0], column_oids[1]), []).unwrap();
+        let get_column_order = || {
+            let (column_channel, packets) = test_util::collect_channel::<Metadata>();
+            send_metadata_list(table_oid, column_channel).unwrap();
+            return packets.lock().unwrap().iter().map(|packet| packet["oid"].as_i64().unwrap()).collect::<Vec<i64>>();
+        };
+        let get_display_value = || db::open().unwrap()
+            .query_one(&format!("SELECT DISPLAY_VALUE FROM TABLE{table_oid}_SURROGATE WHERE OID = 1;"), [], |row| row.get::<_, String>(0))
+            .unwrap();
+        assert!(get_display_value().starts_with(r#"{ "City""#));
+
+        // Moving the middle column to the front shifts the first column over, and reorders the display value of each row
+        let first_ordering: i64 = db::open().unwrap().query_one("SELECT COLUMN_ORDERING FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", params![column_oids[0]], |row| row.get(0)).unwrap();
+        let prior_ordering = reorder(table_oid, column_oids[1], first_ordering).unwrap();
+        assert_eq!(get_column_order(), vec![column_oids[1], column_oids[0], column_oids[2]]);
+        assert!(get_display_value().starts_with(r#"{ "Country""#));
+
+        // Undoing the move puts every column back where it was
+        reorder(table_oid, column_oids[1], prior_ordering).unwrap();
+        assert_eq!(get_column_order(), column_oids);
+        assert!(get_display_value().starts_with(r#"{ "City""#));
+    }
+
+    /// Gets the largest OID of any type, so that a type created afterwards can be recognized.
+    fn get_max_type_oid() -> i64 {
+        return db::open().unwrap().query_one("SELECT MAX(OID) FROM METADATA_TYPE;", [], |row| row.get(0)).unwrap();
+    }
+
+    /// Checks whether a table exists in the database.
+    fn table_exists(table_name: &str) -> bool {
+        return db::open().unwrap()
+            .query_one("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1);", params![table_name], |row| row.get(0))
+            .unwrap();
+    }
+
+    #[test]
+    fn failed_dropdown_column_leaves_no_type_behind() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Rows"), &vec![]).unwrap();
+        let max_type_oid = get_max_type_oid();
+
+        // The dropdown's type and value tables are created before the column is added to a table that does not exist
+        let dropdown_type = data_type::MetadataColumnType::MultiSelectDropdown(0);
+        assert!(create(table_oid + 1000, "Tags", dropdown_type.clone(), None, "", true, false, false, None).is_err());
+        assert_eq!(get_max_type_oid(), max_type_oid);
+        assert!(!table_exists(&format!("TABLE{}", max_type_oid + 1)));
+        assert!(!table_exists(&format!("TABLE{}_MULTISELECT", max_type_oid + 1)));
+
+        // The same column can be created once the table exists, with its type taking the OID that was freed up
+        let column_oid = create(table_oid, "Tags", dropdown_type, None, "", true, false, false, None).unwrap();
+        let column_type_oid: i64 = db::open().unwrap()
+            .query_one("SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", params![column_oid], |row| row.get(0))
+            .unwrap();
+        assert_eq!(column_type_oid, max_type_oid + 1);
+        assert!(table_exists(&format!("TABLE{column_type_oid}")));
+        assert!(table_exists(&format!("TABLE{column_type_oid}_MULTISELECT")));
+    }
+
+    /// Gets the OIDs of the object types offered as the type of a child object column in a table.
+    fn get_child_object_type_oids(table_oid: i64) -> Vec<i64> {
+        let (type_channel, packets) = test_util::collect_channel::<BasicTypeMetadata>();
+        send_child_object_type_list(table_oid, type_channel).unwrap();
+        return packets.lock().unwrap().iter().map(|packet| packet["oid"].as_i64().unwrap()).collect();
+    }
+
+    #[test]
+    fn cyclic_child_object_types_are_not_offered() {
+        let _db = test_util::open_test_db();
+
+        // B is a child object of A, and C inherits the columns of A
+        let a_oid = obj_type::create(String::from("A"), &vec![]).unwrap();
+        let b_oid = obj_type::create(String::from("B"), &vec![]).unwrap();
+        let c_oid = obj_type::create(String::from("C"), &vec![a_oid]).unwrap();
+        let d_oid = obj_type::create(String::from("D"), &vec![]).unwrap();
+        create(a_oid, "B", data_type::MetadataColumnType::ChildObject(b_oid), None, "", true, false, false, None).unwrap();
+
+        // Neither A nor C can be used in B, since both already contain B
+        let b_options = get_child_object_type_oids(b_oid);
+        assert!(!b_options.contains(&a_oid));
+        assert!(!b_options.contains(&c_oid));
+        assert!(!b_options.contains(&b_oid));
+        assert!(b_options.contains(&d_oid));
+
+        // B can still be used in A, and every other type can be used in D
+        assert!(get_child_object_type_oids(a_oid).contains(&b_oid));
+        let d_options = get_child_object_type_oids(d_oid);
+        assert!(d_options.contains(&a_oid) && d_options.contains(&b_oid) && d_options.contains(&c_oid));
+    }
+
+    #[test]
+    fn reference_types_can_exclude_a_table() {
+        let _db = test_util::open_test_db();
+        let first_oid = table::create(String::from("First"), &vec![]).unwrap();
+        let second_oid = table::create(String::from("Second"), &vec![]).unwrap();
+
+        // Every table is offered unless it is the one excluded
+        let get_reference_type_oids = |excluded_table_oid: Option<i64>| -> Vec<i64> {
+            let (type_channel, packets) = test_util::collect_channel::<BasicTypeMetadata>();
+            send_reference_type_list(excluded_table_oid, type_channel).unwrap();
+            return packets.lock().unwrap().iter().map(|packet| packet["oid"].as_i64().unwrap()).collect();
+        };
+        assert_eq!(get_reference_type_oids(None), vec![first_oid, second_oid]);
+        assert_eq!(get_reference_type_oids(Some(first_oid)), vec![second_oid]);
+    }
+}
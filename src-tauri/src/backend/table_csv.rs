@@ -0,0 +1,516 @@
+use std::io::Read;
+use std::time::Instant;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use time::Date;
+use time::UtcDateTime;
+use time::format_description::well_known;
+use crate::backend::{data_type, db, table, table_column, table_data};
+use crate::util::error;
+
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all="camelCase")]
+/// The characters used to separate and quote the fields of a CSV file.
+pub struct CsvDialect {
+    pub delimiter: u8,
+    pub quote: u8
+}
+
+impl Default for CsvDialect {
+    /// Comma-separated fields, quoted by double quotes.
+    fn default() -> Self {
+        return CsvDialect {
+            delimiter: b',',
+            quote: b'"'
+        };
+    }
+}
+
+impl CsvDialect {
+    /// Checks that the dialect can be used to unambiguously read and write a file.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        if self.delimiter == self.quote {
+            return Err(error::Error::AdhocError("The delimiter and quote character of a CSV file must be different."));
+        }
+        if !self.delimiter.is_ascii() || !self.quote.is_ascii() {
+            return Err(error::Error::AdhocError("The delimiter and quote character of a CSV file must be ASCII characters."));
+        }
+        if [b'\r', b'\n'].contains(&self.delimiter) || [b'\r', b'\n'].contains(&self.quote) {
+            return Err(error::Error::AdhocError("The delimiter and quote character of a CSV file cannot be line breaks."));
+        }
+        return Ok(());
+    }
+
+    /// Appends a record to the text of a CSV file, quoting any fields that need it.
+    pub fn write_record(&self, out: &mut String, fields: &Vec<Option<String>>) {
+        let delimiter = self.delimiter as char;
+        let quote = self.quote as char;
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                out.push(delimiter);
+            }
+            let field: &str = match field {
+                Some(f) => f,
+                None => ""
+            };
+            if field.contains(|c| c == delimiter || c == quote || c == '\r' || c == '\n') {
+                out.push(quote);
+                for c in field.chars() {
+                    if c == quote {
+                        out.push(quote);
+                    }
+                    out.push(c);
+                }
+                out.push(quote);
+            } else {
+                out.push_str(field);
+            }
+        }
+        out.push_str("\r\n");
+    }
+
+    /// Splits the text of a CSV file into records of fields.
+    /// Blank lines are skipped.
+    pub fn parse(&self, text: &str) -> Vec<Vec<String>> {
+        let delimiter = self.delimiter as char;
+        let quote = self.quote as char;
+        let mut records: Vec<Vec<String>> = Vec::new();
+        let mut record: Vec<String> = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut field_started = false;
+
+        let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if c == quote {
+                    if chars.peek() == Some(&quote) {
+                        // A doubled quote is a literal quote
+                        field.push(quote);
+                        chars.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    field.push(c);
+                }
+            } else if c == quote && !field_started {
+                in_quotes = true;
+                field_started = true;
+            } else if c == delimiter {
+                record.push(std::mem::take(&mut field));
+                field_started = false;
+            } else if c == '\r' || c == '\n' {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                if field_started || !field.is_empty() || !record.is_empty() {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                field_started = false;
+            } else {
+                field.push(c);
+                field_started = true;
+            }
+        }
+        if field_started || !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+        return records;
+    }
+}
+
+/// A column of a table that is read from or written to a CSV file.
+struct CsvColumn {
+    oid: i64,
+    name: String,
+    column_type: data_type::MetadataColumnType
+}
+
+/// Gets the columns of a table, in the order they are displayed.
+fn get_csv_columns(trans: &Connection, table_oid: i64) -> Result<Vec<CsvColumn>, error::Error> {
+    let mut columns: Vec<CsvColumn> = Vec::new();
+    db::query_iterate(trans,
+        "SELECT
+            c.OID,
+            c.NAME,
+            c.TYPE_OID,
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0
+        ORDER BY c.COLUMN_ORDERING, c.OID;",
+        params![table_oid],
+        &mut |row| {
+            columns.push(CsvColumn {
+                oid: row.get("OID")?,
+                name: row.get("NAME")?,
+                column_type: data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?)
+            });
+            return Ok(());
+        }
+    )?;
+    return Ok(columns);
+}
+
+/// Checks whether a column can be filled in from a CSV file.
+fn is_importable(column: &CsvColumn) -> bool {
+    return match column.column_type {
+        data_type::MetadataColumnType::Primitive(ref prim) => !prim.is_file(),
+        data_type::MetadataColumnType::SingleSelectDropdown(_) => true,
+        _ => false
+    };
+}
+
+/// Matches each field of a header row to the importable column of the table with the same name, ignoring case.
+fn map_header<'a>(columns: &'a Vec<CsvColumn>, header: &Vec<String>) -> Vec<Option<&'a CsvColumn>> {
+    return header.iter()
+        .map(|name| columns.iter().find(|c| c.name.trim().eq_ignore_ascii_case(name.trim())))
+        .map(|c| c.filter(|c| is_importable(c)))
+        .collect();
+}
+
+/// Checks whether a field of a CSV file can be converted into the type of a column.
+/// Returns the reason it cannot, if any.
+fn check_field_compatibility(column: &CsvColumn, field: &str) -> Option<&'static str> {
+    let field = field.trim();
+    if field.is_empty() {
+        return None;
+    }
+    return match column.column_type {
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer) 
+        | data_type::MetadataColumnType::Primitive(data_type::Primitive::Number) => {
+            field.parse::<f64>().err().map(|_| "Value is not a number.")
+        },
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Date) => {
+            Date::parse(field, &well_known::Iso8601::DATE).err().map(|_| "Value is not a date.")
+        },
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => {
+            UtcDateTime::parse(field, &well_known::Iso8601::DATE_TIME).err().map(|_| "Value is not a timestamp.")
+        },
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::JSON) => {
+            serde_json::from_str::<serde_json::Value>(field).err().map(|_| "Value is not valid JSON.")
+        },
+        _ => None
+    };
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all="camelCase")]
+/// An explicit choice of the column of a table that a column of a CSV file is imported into.
+/// If no column is given, the column of the CSV file is skipped.
+pub struct CsvColumnOverride {
+    pub csv_column_index: usize,
+    pub column_oid: Option<i64>
+}
+
+/// Applies explicit choices of columns over the columns matched by name.
+fn apply_overrides<'a>(columns: &'a Vec<CsvColumn>, field_columns: &mut Vec<Option<&'a CsvColumn>>, overrides: &Vec<CsvColumnOverride>) -> Result<(), error::Error> {
+    for o in overrides.iter() {
+        if o.csv_column_index >= field_columns.len() {
+            field_columns.resize(o.csv_column_index + 1, None);
+        }
+        field_columns[o.csv_column_index] = match o.column_oid {
+            Some(column_oid) => {
+                match columns.iter().find(|c| c.oid == column_oid) {
+                    Some(c) if is_importable(c) => Some(c),
+                    Some(_) => {
+                        return Err(error::Error::AdhocError("Values cannot be imported from a CSV file into a column of that type."));
+                    },
+                    None => {
+                        return Err(error::Error::AdhocError("The column to import into does not belong to the table."));
+                    }
+                }
+            },
+            None => None
+        };
+    }
+    return Ok(());
+}
+
+/// How many rows of a CSV file are included in a preview of an import.
+const PREVIEW_SAMPLE_ROWS: usize = 10;
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// The column of a table that a column of a CSV file would be imported into.
+pub struct ImportColumnMapping {
+    pub csv_column_index: usize,
+    pub csv_column_name: String,
+    pub column_oid: Option<i64>,
+    pub column_name: Option<String>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A column of a CSV file with values that cannot be converted into the type of the column they would be imported into.
+pub struct ImportColumnWarning {
+    pub csv_column_index: usize,
+    pub message: String,
+    pub invalid_count: i64,
+    pub first_invalid_row: i64
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// What importing a CSV file into a table would do.
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub mapping: Vec<ImportColumnMapping>,
+    pub sample_rows: Vec<Vec<String>>,
+    pub row_count: i64,
+    pub warnings: Vec<ImportColumnWarning>
+}
+
+/// Reads a CSV file and describes how it would be imported into a table, without modifying the database.
+/// If the file has no header row, its columns are matched to the importable columns of the table in the order they are displayed.
+pub fn preview_import(table_oid: i64, reader: &mut impl Read, dialect: CsvDialect, has_header: bool) -> Result<ImportPreview, error::Error> {
+    dialect.validate()?;
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut records = dialect.parse(&text).into_iter().peekable();
+
+    let action = db::begin_readonly_db_action()?;
+    let columns = get_csv_columns(&action, table_oid)?;
+
+    // Determine the headers, and the column each would be imported into
+    let (headers, field_columns): (Vec<String>, Vec<Option<&CsvColumn>>) = if has_header {
+        let header = records.next().unwrap_or_default();
+        let field_columns = map_header(&columns, &header);
+        (header, field_columns)
+    } else {
+        let width = match records.peek() {
+            Some(r) => r.len(),
+            None => 0
+        };
+        let mut importable = columns.iter().filter(|c| is_importable(c));
+        let header = (1..=width).map(|i| format!("Column {i}")).collect();
+        let field_columns = (0..width).map(|_| importable.next()).collect();
+        (header, field_columns)
+    };
+    let mapping: Vec<ImportColumnMapping> = headers.iter().zip(field_columns.iter()).enumerate()
+        .map(|(i, (name, column))| ImportColumnMapping {
+            csv_column_index: i,
+            csv_column_name: name.clone(),
+            column_oid: column.map(|c| c.oid),
+            column_name: column.map(|c| c.name.clone())
+        })
+        .collect();
+
+    // Check every record against the types of the columns, keeping a sample of the first few
+    let mut sample_rows: Vec<Vec<String>> = Vec::new();
+    let mut warnings: Vec<ImportColumnWarning> = Vec::new();
+    let mut row_count: i64 = 0;
+    for record in records {
+        row_count += 1;
+        for (i, (field, column)) in record.iter().zip(field_columns.iter()).enumerate() {
+            let column = match column {
+                Some(c) => c,
+                None => {
+                    continue;
+                }
+            };
+            if let Some(message) = check_field_compatibility(column, field) {
+                match warnings.iter_mut().find(|w| w.csv_column_index == i) {
+                    Some(warning) => {
+                        warning.invalid_count += 1;
+                    },
+                    None => {
+                        warnings.push(ImportColumnWarning {
+                            csv_column_index: i,
+                            message: String::from(message),
+                            invalid_count: 1,
+                            first_invalid_row: row_count
+                        });
+                    }
+                }
+            }
+        }
+        if sample_rows.len() < PREVIEW_SAMPLE_ROWS {
+            sample_rows.push(record);
+        }
+    }
+    return Ok(ImportPreview {
+        headers,
+        mapping,
+        sample_rows,
+        row_count,
+        warnings
+    });
+}
+
+/// Writes the rows of a table to a CSV file, with a header row of column names.
+/// Each cell is written as its display value.
+/// If a progress channel is provided, progress is reported to it as rows are written.
+/// If an operation ID is provided, the export stops without writing the file once the operation is cancelled.
+/// Returns the number of rows written.
+pub fn export_table(table_oid: i64, path: &str, dialect: CsvDialect, view: &table_data::ExportView, progress: Option<Channel<table_data::ExportProgress>>, operation_id: Option<i64>) -> Result<i64, error::Error> {
+    let cancellation = table_data::CancellationToken::new(operation_id);
+    dialect.validate()?;
+    let start = Instant::now();
+    let action = db::begin_readonly_db_action()?;
+
+    // Write the header
+    let columns = get_csv_columns(&action, table_oid)?;
+    let mut out = String::new();
+    dialect.write_record(&mut out, &columns.iter().map(|c| Some(c.name.clone())).collect());
+
+    // Write each row
+    let mut row_count: i64 = 0;
+    if columns.len() > 0 {
+        let select_cols: Vec<String> = columns.iter().map(|c| table_data::construct_display_value_expr(c.oid, &c.column_type, "t")).collect();
+        let (where_cmd, order_cmd, values) = table_data::construct_export_view_clauses(&action, table_oid, view)?;
+        let select_cmd = format!("SELECT {} FROM TABLE{table_oid} t {where_cmd} {order_cmd};", select_cols.join(", "));
+        let count_cmd = format!("SELECT COUNT(*) FROM TABLE{table_oid} t {where_cmd};");
+        let total: i64 = action.query_one(&count_cmd, rusqlite::params_from_iter(values.iter()), |row| row.get(0))?;
+        table_data::report_progress(&progress, 0, total)?;
+        db::query_iterate(&action, &select_cmd, rusqlite::params_from_iter(values.iter()),
+            &mut |row| {
+                cancellation.check()?;
+                let mut fields: Vec<Option<String>> = Vec::new();
+                for i in 0..columns.len() {
+                    fields.push(row.get(i)?);
+                }
+                dialect.write_record(&mut out, &fields);
+                row_count += 1;
+                table_data::report_progress(&progress, row_count, total)?;
+                return Ok(());
+            }
+        )?;
+    }
+    std::fs::write(path, out)?;
+    log::debug!("Exported {row_count} rows of TABLE{table_oid} to CSV in {:?}.", start.elapsed());
+    return Ok(row_count);
+}
+
+/// Reads the rows of a CSV file into a table, matching the header row of the file to the names of the table's columns.
+/// Only columns that hold a primitive value or a single-select dropdown value are filled in; other columns, and fields that do not match a column, are ignored.
+/// If a progress channel is provided, progress is reported to it as rows are read, and the import is rolled back if a report cannot be sent.
+/// Any explicit choices of columns in the mapping override the columns matched by name.
+/// If an operation ID is provided, the import is rolled back once the operation is cancelled.
+/// Returns the number of rows imported.
+pub fn import_table(table_oid: i64, path: &str, dialect: CsvDialect, mapping: Vec<CsvColumnOverride>, progress: Option<Channel<table_data::ExportProgress>>, operation_id: Option<i64>) -> Result<i64, error::Error> {
+    let cancellation = table_data::CancellationToken::new(operation_id);
+    dialect.validate()?;
+    let start = Instant::now();
+    let text = std::fs::read_to_string(path)?;
+    let action = db::begin_db_action()?;
+    let result = action.finish(db::with_deferred_fk(&action, |trans| import_table_in(trans, table_oid, &text, dialect, &mapping, &progress, &cancellation)));
+    log::debug!("Imported {:?} rows into TABLE{table_oid} from CSV in {:?}.", result.as_ref().ok(), start.elapsed());
+    return result;
+}
+
+/// Reads the rows of CSV text into a table, as part of an ongoing action.
+fn import_table_in(trans: &Connection, table_oid: i64, text: &str, dialect: CsvDialect, mapping: &Vec<CsvColumnOverride>, progress: &Option<Channel<table_data::ExportProgress>>, cancellation: &table_data::CancellationToken) -> Result<i64, error::Error> {
+    let mut records = dialect.parse(text).into_iter();
+    let header = match records.next() {
+        Some(h) => h,
+        None => {
+            return Ok(0);
+        }
+    };
+
+    // Match each field of the header to a column of the table
+    let columns = get_csv_columns(trans, table_oid)?;
+    let mut field_columns = map_header(&columns, &header);
+    apply_overrides(&columns, &mut field_columns, mapping)?;
+
+    // Insert each record as a row
+    let total = records.len() as i64;
+    table_data::report_progress(progress, 0, total)?;
+    let mut row_count: i64 = 0;
+    for record in records {
+        cancellation.check()?;
+        let row_oid = table_data::push_in(trans, table_oid)?;
+        for (field, column) in record.into_iter().zip(field_columns.iter()) {
+            let column = match column {
+                Some(c) => c,
+                None => {
+                    continue;
+                }
+            };
+            if field.is_empty() {
+                continue;
+            }
+            match column.column_type {
+                data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
+                    // Look up the dropdown value, adding it if it does not exist yet
+                    table_column::import_dropdown_values_in(trans, column.oid, vec![field.clone()])?;
+                    let select_cmd = format!("SELECT OID FROM TABLE{column_type_oid} WHERE VALUE = ?1 AND TRASH = 0;");
+                    let value_oid: Option<i64> = trans.query_one(&select_cmd, params![field.trim()], |row| row.get(0)).optional()?;
+                    let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{} = ?1 WHERE OID = ?2;", column.oid);
+                    trans.execute(&update_cmd, params![value_oid, row_oid])?;
+                },
+                _ => {
+                    table_data::try_update_primitive_value_in(trans, table_oid, row_oid, column.oid, Some(field))?;
+                }
+            }
+        }
+        table::refresh_display_value_cache(trans, table_oid, Some(row_oid))?;
+        row_count += 1;
+        table_data::report_progress(progress, row_count, total)?;
+    }
+    return Ok(row_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::test_util;
+
+    #[test]
+    fn csv_round_trips_in_each_dialect() {
+        let db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+        let values: Vec<(Option<String>, Option<String>)> = vec![
+            (Some(String::from("Plain")), None),
+            (Some(String::from("Comma, semicolon; and\ttab")), Some(String::from("\"Double\" and 'single' quotes"))),
+            (Some(String::from("Line\nbreak")), Some(String::from("")))
+        ];
+
+        // Create a table to export from, and a table with the same columns for each dialect to import into
+        let create_table = |name: &str| {
+            let table_oid = table::create(String::from(name), &vec![]).unwrap();
+            let name_column_oid = table_column::create(table_oid, "Name", text_type.clone(), None, "", true, false, false, None).unwrap();
+            let note_column_oid = table_column::create(table_oid, "Note", text_type.clone(), None, "", true, false, false, None).unwrap();
+            return (table_oid, name_column_oid, note_column_oid);
+        };
+        let get_values = |(table_oid, name_column_oid, note_column_oid): (i64, i64, i64)| {
+            let mut table_values: Vec<(Option<String>, Option<String>)> = Vec::new();
+            db::query_iterate(&db::open().unwrap(), &format!("SELECT COLUMN{name_column_oid}, COLUMN{note_column_oid} FROM TABLE{table_oid} ORDER BY ORDER_KEY;"), [], &mut |row| {
+                table_values.push((row.get(0)?, row.get(1)?));
+                return Ok(());
+            }).unwrap();
+            return table_values;
+        };
+        let source = create_table("Source");
+        for (name, note) in values.iter() {
+            let row_oid = table_data::push(source.0).unwrap();
+            table_data::try_update_primitive_value(source.0, row_oid, source.1, name.clone()).unwrap();
+            table_data::try_update_primitive_value(source.0, row_oid, source.2, note.clone()).unwrap();
+        }
+
+        // Each dialect writes its own delimiter, and reads back exactly what it wrote, with empty fields read as empty cells
+        let path = format!("{}.csv", db.path.display());
+        for (i, (delimiter, quote)) in [(b',', b'"'), (b';', b'\''), (b'\t', b'"')].into_iter().enumerate() {
+            let dialect = CsvDialect { delimiter, quote };
+            assert_eq!(export_table(source.0, &path, dialect, &table_data::ExportView::default(), None, None).unwrap(), 3);
+            let text = std::fs::read_to_string(&path).unwrap();
+            assert!(text.starts_with(&format!("Name{}Note", delimiter as char)));
+
+            let target = create_table(&format!("Target {i}"));
+            assert_eq!(import_table(target.0, &path, dialect, vec![], None, None).unwrap(), 3);
+            let mut expected_values = values.clone();
+            expected_values[2].1 = None;
+            assert_eq!(get_values(target), expected_values);
+        }
+        let _ = std::fs::remove_file(&path);
+
+        // A dialect that quotes with its delimiter is rejected
+        assert!(CsvDialect { delimiter: b';', quote: b';' }.validate().is_err());
+    }
+}
@@ -1,7 +1,13 @@
-use std::collections::{HashMap, HashSet, LinkedList};
+use std::collections::{BTreeMap, HashMap, HashSet, LinkedList};
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::time::{Duration, Instant};
 use serde_json::{Result as SerdeJsonResult, Value};
-use rusqlite::{Error as RusqliteError, OptionalExtension, Row, Transaction, params};
-use serde::Serialize;
+use rusqlite::{MAIN_DB, DropBehavior, Error as RusqliteError, OptionalExtension, Row, Connection, Transaction, params};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
 use tauri::ipc::Channel;
 use time::format_description::well_known;
 use time::macros::{time};
@@ -16,7 +22,8 @@ use crate::util::error;
 pub enum Cell {
     RowStart {
         row_oid: i64,
-        row_index: i64
+        row_index: i64,
+        row_style: Option<String>
     },
     ColumnValue {
         table_oid: i64,
@@ -25,7 +32,8 @@ pub enum Cell {
         column_type: data_type::MetadataColumnType,
         true_value: Option<String>,
         display_value: Option<String>,
-        failed_validations: Vec<error::FailedValidation>
+        failed_validations: Vec<error::FailedValidation>,
+        has_comment: bool
     }
 }
 
@@ -45,16 +53,22 @@ pub enum RowCell {
         column_ordering: i64,
         true_value: Option<String>,
         display_value: Option<String>,
-        failed_validations: Vec<error::FailedValidation>
+        failed_validations: Vec<error::FailedValidation>,
+        has_comment: bool
     }
 }
 
-/// Insert a row into the data such that the OID places it before any existing rows with that OID.
+/// Insert a row into the data such that it is placed before the row with the given OID.
+/// If no row has that OID, the new row takes that OID, and is placed before the next row by OID.
+/// Rather than shifting the OIDs of every following row, the new row is given an ORDER_KEY between the rows around it.
 pub fn insert(table_oid: i64, row_oid: i64) -> Result<i64, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(insert_in(&action, table_oid, row_oid));
+}
 
-    // If OID is already in database, shift every row with OID >= row_oid up by 1
+/// Insert a row into the data such that it is placed before the row with the given OID, as part of an ongoing action.
+fn insert_in(trans: &Connection, table_oid: i64, row_oid: i64) -> Result<i64, error::Error> {
+    // Check whether the OID is already in use
     let select_cmd = format!("SELECT OID FROM TABLE{table_oid} WHERE OID = ?1;");
     let existing_row_oid = trans.query_one(&select_cmd, params![row_oid], 
         |row| {
@@ -62,124 +76,558 @@ pub fn insert(table_oid: i64, row_oid: i64) -> Result<i64, error::Error> {
         }
     ).optional()?;
 
-    match existing_row_oid {
-        None => {
-            // Insert with OID = row_oid
-            let insert_cmd = format!("INSERT INTO TABLE{table_oid} (OID) VALUES (?1);");
-            trans.execute(&insert_cmd, params![row_oid])?;
-            let row_oid = trans.last_insert_rowid();
+    // Find the row that the new row is placed before, if any
+    let select_next_cmd = format!("SELECT OID FROM TABLE{table_oid} WHERE OID >= ?1 ORDER BY OID LIMIT 1;");
+    let next_row_oid = trans.query_one(&select_next_cmd, params![row_oid], |row| row.get::<_, i64>(0)).optional()?;
+    let order_key = order_key_before_in(&trans, table_oid, next_row_oid)?;
 
-            // Return the row_oid
-            trans.commit()?;
-            return Ok(row_oid);
+    // Insert the row
+    let row_oid = match existing_row_oid {
+        None => {
+            let insert_cmd = format!("INSERT INTO TABLE{table_oid} (OID, ORDER_KEY) VALUES (?1, ?2);");
+            trans.execute(&insert_cmd, params![row_oid, order_key])?;
+            row_oid
         },
         Some(_) => {
-            let existing_prev_row_oid = trans.query_one(&select_cmd, params![row_oid - 1], 
-                |row| {
-                    return Ok(row.get::<_, i64>(0)?);
-                }
-            ).optional()?;
-            
-            match existing_prev_row_oid {
-                None => {
-                    // Insert with OID = row_oid - 1
-                    let insert_cmd = format!("INSERT INTO TABLE{table_oid} (OID) VALUES (?1);");
-                    trans.execute(&insert_cmd, params![row_oid - 1])?;
-                    let row_oid = trans.last_insert_rowid();
-
-                    // Return the row_oid
-                    trans.commit()?;
-                    return Ok(row_oid);
-                },
-                Some(_) => {
-                    // Increment every OID >= row_oid up by 1 to make room for the new row
-                    let select_all_cmd = format!("SELECT OID FROM TABLE{table_oid} WHERE OID >= ?1 ORDER BY OID DESC;");
-                    db::query_iterate(&trans, &select_all_cmd, params![row_oid], 
-                        &mut |row| {
-                            let update_cmd = format!("UPDATE TABLE{table_oid} SET OID = OID + 1 WHERE OID = ?1;");
-                            trans.execute(&update_cmd, params![row.get::<_, i64>(0)?])?;
-                            return Ok(());
-                        }
-                    )?;
+            let insert_cmd = format!("INSERT INTO TABLE{table_oid} (ORDER_KEY) VALUES (?1);");
+            trans.execute(&insert_cmd, params![order_key])?;
+            trans.last_insert_rowid()
+        }
+    };
+    apply_default_values_in(&trans, table_oid, row_oid)?;
+    table::refresh_display_value_cache(&trans, table_oid, Some(row_oid))?;
 
-                    // Insert the row
-                    let insert_cmd = format!("INSERT INTO TABLE{table_oid} (OID) VALUES (?1);");
-                    trans.execute(&insert_cmd, params![row_oid])?;
-                    let row_oid = trans.last_insert_rowid();
+    // Return the row_oid
+    return Ok(row_oid);
+}
 
-                    // Return the row_oid
-                    trans.commit()?;
-                    return Ok(row_oid);
-                }
-            }
+/// Picks an ORDER_KEY that places a row immediately before the given row, or after every row if no row is given, as part of an ongoing action.
+/// If there is no room left between the keys of two adjacent rows, the keys of the whole table are renumbered first.
+fn order_key_before_in(trans: &Connection, table_oid: i64, next_row_oid: Option<i64>) -> Result<f64, error::Error> {
+    let next_row_oid = match next_row_oid {
+        Some(o) => o,
+        None => {
+            let select_max_cmd = format!("SELECT COALESCE(MAX(ORDER_KEY), 0) + 1 FROM TABLE{table_oid};");
+            return Ok(trans.query_one(&select_max_cmd, [], |row| row.get(0))?);
+        }
+    };
+    let select_keys_cmd = format!("SELECT 
+            n.ORDER_KEY,
+            (SELECT MAX(p.ORDER_KEY) FROM TABLE{table_oid} p WHERE p.ORDER_KEY < n.ORDER_KEY)
+        FROM TABLE{table_oid} n 
+        WHERE n.OID = ?1;");
+    for _ in 0..2 {
+        let (next_key, prev_key): (f64, Option<f64>) = trans.query_one(&select_keys_cmd, params![next_row_oid], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let order_key = match prev_key {
+            Some(prev_key) => prev_key + (next_key - prev_key) / 2.0,
+            None => next_key - 1.0
+        };
+        if order_key < next_key && prev_key.map_or(true, |prev_key| order_key > prev_key) {
+            return Ok(order_key);
         }
+
+        // Spread the keys back out to whole numbers, then try again
+        let renumber_cmd = format!("UPDATE TABLE{table_oid} 
+            SET ORDER_KEY = r.ROW_INDEX
+            FROM (SELECT OID, ROW_NUMBER() OVER (ORDER BY ORDER_KEY, OID) AS ROW_INDEX FROM TABLE{table_oid}) AS r
+            WHERE TABLE{table_oid}.OID = r.OID;");
+        trans.execute(&renumber_cmd, [])?;
     }
+    return Err(error::Error::AdhocError("Unable to find a place to insert the row."));
+}
+
+/// Fills in the empty columns of a newly-inserted row with their default values, as part of an ongoing action.
+/// Tokens (i.e. @now, @today) are evaluated at insert time, while any other default value is copied as it is stored, which for dates and timestamps is already a julian day.
+fn apply_default_values_in(trans: &Connection, table_oid: i64, row_oid: i64) -> Result<(), error::Error> {
+    db::query_iterate(trans, 
+        "SELECT c.OID, c.DEFAULT_VALUE 
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0 AND c.DEFAULT_VALUE IS NOT NULL AND t.MODE IN (0, 1, 3);", 
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            let default_value: rusqlite::types::Value = row.get("DEFAULT_VALUE")?;
+            let (value_expr, bound_value): (&str, Option<rusqlite::types::Value>) = match default_value {
+                rusqlite::types::Value::Text(t) if t == table_column::DEFAULT_NOW => {
+                    // Timestamps are stored as fractional julian days in UTC
+                    ("julianday('now')", None)
+                },
+                rusqlite::types::Value::Text(t) if t == table_column::DEFAULT_TODAY => {
+                    // Dates are stored as the julian day number of the local date
+                    ("CAST(julianday('now', 'localtime', 'start of day') + 0.5 AS INTEGER)", None)
+                },
+                v => ("?2", Some(v))
+            };
+            let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = {value_expr} WHERE OID = ?1 AND COLUMN{column_oid} IS NULL;");
+            match bound_value {
+                Some(v) => trans.execute(&update_cmd, params![row_oid, v])?,
+                None => trans.execute(&update_cmd, params![row_oid])?
+            };
+            return Ok(());
+        }
+    )?;
+    return Ok(());
+}
+
+/// Sets each automatically-updated timestamp column of a row to the current time, other than the column that was edited, as part of an ongoing action.
+fn touch_auto_update_columns_in(trans: &Connection, table_oid: i64, row_oid: i64, edited_column_oid: i64) -> Result<(), error::Error> {
+    db::query_iterate(trans, 
+        "SELECT OID FROM METADATA_TABLE_COLUMN WHERE TABLE_OID = ?1 AND TRASH = 0 AND AUTO_UPDATE = 1 AND OID <> ?2;", 
+        params![table_oid, edited_column_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = julianday('now') WHERE OID = ?1;");
+            trans.execute(&update_cmd, params![row_oid])?;
+            return Ok(());
+        }
+    )?;
+    return Ok(());
 }
 
 /// Push a row into the table with a default OID.
 pub fn push(table_oid: i64) -> Result<i64, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(push_in(&action, table_oid));
+}
 
-    // Insert the row
-    let insert_cmd = format!("INSERT INTO TABLE{table_oid} DEFAULT VALUES;");
+/// Push a row into the table with a default OID, as part of an ongoing action.
+pub fn push_in(trans: &Connection, table_oid: i64) -> Result<i64, error::Error> {
+    let insert_cmd = format!("INSERT INTO TABLE{table_oid} (ORDER_KEY) SELECT COALESCE(MAX(ORDER_KEY), 0) + 1 FROM TABLE{table_oid};");
     trans.execute(&insert_cmd, [])?;
     let row_oid = trans.last_insert_rowid();
-
-    // Return the row OID
-    trans.commit()?;
+    apply_default_values_in(trans, table_oid, row_oid)?;
+    table::refresh_display_value_cache(trans, table_oid, Some(row_oid))?;
     return Ok(row_oid);
 }
 
+/// Inserts many rows into a table at once, after every existing row, returning the OIDs of the new rows.
+/// Each row maps column OIDs to the values of its cells, and any column left out of a row is given its default value, or left NULL if it has none.
+/// If validation is turned off, values are written exactly as given, so they must already be in the form they are stored in (e.g. dates as julian day numbers).
+/// This is not recorded as an action that can be undone.
+pub fn bulk_insert(table_oid: i64, rows: Vec<HashMap<i64, Option<String>>>, validate: bool) -> Result<Vec<i64>, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(db::with_deferred_fk(&action, |trans| bulk_insert_in(trans, table_oid, &rows, validate)));
+    log::debug!("Inserted {} rows into TABLE{table_oid} in {:?}.", rows.len(), start.elapsed());
+    return result;
+}
+
+/// Inserts many rows into a table at once, as part of an ongoing action.
+fn bulk_insert_in(trans: &Connection, table_oid: i64, rows: &Vec<HashMap<i64, Option<String>>>, validate: bool) -> Result<Vec<i64>, error::Error> {
+    // Check that every column is stored in the table itself
+    let mut column_oids: Vec<i64> = rows.iter().flat_map(|row| row.keys().cloned()).collect::<HashSet<i64>>().into_iter().collect();
+    column_oids.sort();
+    for column_oid in column_oids.iter() {
+        let is_stored: bool = trans.query_one(
+            "SELECT EXISTS (
+                SELECT 1 
+                FROM METADATA_TABLE_COLUMN c
+                INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+                WHERE c.OID = ?1 AND c.TABLE_OID = ?2 AND c.TRASH = 0 AND t.MODE IN (0, 1, 3, 4)
+            );", 
+            params![column_oid, table_oid], 
+            |row| row.get(0)
+        )?;
+        if !is_stored {
+            return Err(error::Error::AdhocError("Values can only be inserted in bulk into columns of the table that are not multi-select dropdowns or child tables."));
+        }
+    }
+
+    let mut row_oids: Vec<i64> = Vec::with_capacity(rows.len());
+    if validate {
+        // Insert each row, then parse and set each value as if it had been entered by hand
+        for row in rows.iter() {
+            let row_oid = push_in(trans, table_oid)?;
+            for column_oid in column_oids.iter() {
+                if let Some(value) = row.get(column_oid) {
+                    try_update_primitive_value_in(trans, table_oid, row_oid, *column_oid, value.clone())?;
+                }
+            }
+            row_oids.push(row_oid);
+        }
+    } else {
+        // Insert every row with a single prepared statement
+        let first_order_key: f64 = trans.query_one(&format!("SELECT COALESCE(MAX(ORDER_KEY), 0) + 1 FROM TABLE{table_oid};"), [], |row| row.get(0))?;
+        let insert_cols: String = column_oids.iter().map(|o| format!(", COLUMN{o}")).collect();
+        let insert_params: String = (0..column_oids.len()).map(|i| format!(", ?{}", i + 2)).collect();
+        let insert_cmd = format!("INSERT INTO TABLE{table_oid} (ORDER_KEY{insert_cols}) VALUES (?1{insert_params});");
+        let mut insert_stmt = trans.prepare(&insert_cmd)?;
+        for (i, row) in rows.iter().enumerate() {
+            let mut values: Vec<rusqlite::types::Value> = vec![rusqlite::types::Value::Real(first_order_key + i as f64)];
+            for column_oid in column_oids.iter() {
+                values.push(match row.get(column_oid).cloned().flatten() {
+                    Some(v) => rusqlite::types::Value::Text(v),
+                    None => rusqlite::types::Value::Null
+                });
+            }
+            insert_stmt.execute(rusqlite::params_from_iter(values))?;
+            let row_oid = trans.last_insert_rowid();
+            apply_default_values_in(trans, table_oid, row_oid)?;
+            row_oids.push(row_oid);
+        }
+        table::refresh_display_value_cache(trans, table_oid, None)?;
+    }
+    return Ok(row_oids);
+}
+
+/// Renumbers the rows of a table to a contiguous sequence of OIDs starting from 1, keeping their order.
+/// References to the rows, multi-select values, and child table rows follow along through their foreign keys, as do any comments and tags on the rows.
+/// This changes the OIDs of the rows, so anything outside the database that refers to a row by its OID will no longer find it.
+pub fn compact_oids(table_oid: i64) -> Result<(), error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(compact_oids_in(&action, table_oid));
+    log::debug!("Compacted the OIDs of TABLE{table_oid} in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Renumbers the rows of a table, as part of an ongoing action.
+fn compact_oids_in(trans: &Connection, table_oid: i64) -> Result<(), error::Error> {
+    // Get the OID of every row, in order
+    let mut row_oids: Vec<i64> = Vec::new();
+    let select_cmd = format!("SELECT OID FROM TABLE{table_oid} ORDER BY OID;");
+    db::query_iterate(trans, &select_cmd, [], &mut |row| {
+        row_oids.push(row.get(0)?);
+        return Ok(());
+    })?;
+
+    // Move each row down to its position in the sequence
+    // Since the rows are renumbered in ascending order, the OID each row moves into has always been vacated already
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET OID = ?1 WHERE OID = ?2;");
+    for (i, row_oid) in row_oids.into_iter().enumerate() {
+        let new_row_oid = (i as i64) + 1;
+        if new_row_oid == row_oid {
+            continue;
+        }
+        trans.execute(&update_cmd, params![new_row_oid, row_oid])?;
+        trans.execute(
+            "UPDATE METADATA_CELL_COMMENT SET ROW_OID = ?1 WHERE TABLE_OID = ?2 AND ROW_OID = ?3;", 
+            params![new_row_oid, table_oid, row_oid]
+        )?;
+        trans.execute(
+            "UPDATE METADATA_ROW_TAG SET ROW_OID = ?1 WHERE TABLE_OID = ?2 AND ROW_OID = ?3;", 
+            params![new_row_oid, table_oid, row_oid]
+        )?;
+    }
+
+    // Rebuild the surrogate views of the table and any that depend on it
+    table::update_surrogate_view(trans, table_oid)?;
+    return Ok(());
+}
+
 /// Marks a row as trash.
 pub fn move_trash(table_oid: i64, row_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(move_trash_in(&action, table_oid, row_oid));
+}
 
+/// Marks a row as trash, as part of an ongoing action.
+fn move_trash_in(trans: &Connection, table_oid: i64, row_oid: i64) -> Result<(), error::Error> {
     // Move the row to the trash bin
     let update_cmd = format!("UPDATE TABLE{table_oid} SET TRASH = 1 WHERE OID = ?1;");
     trans.execute(&update_cmd, params![row_oid])?;
 
     // Return the row OID
-    trans.commit()?;
     return Ok(());
 }
 
 /// Unmarks a row as trash.
 pub fn unmove_trash(table_oid: i64, row_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let action = db::begin_db_action()?;
+    return action.finish(unmove_trash_in(&action, table_oid, row_oid));
+}
 
+/// Unmarks a row as trash, as part of an ongoing action.
+fn unmove_trash_in(trans: &Connection, table_oid: i64, row_oid: i64) -> Result<(), error::Error> {
     // Move the row to the trash bin
     let update_cmd = format!("UPDATE TABLE{table_oid} SET TRASH = 0 WHERE OID = ?1;");
     trans.execute(&update_cmd, params![row_oid])?;
 
     // Return the row OID
-    trans.commit()?;
     return Ok(());
 }
 
-/// Delete the row with the given OID.
-pub fn delete(table_oid: i64, row_oid: i64) -> Result<(), error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// A value of a column, as stored in the database.
+pub enum SnapshotValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>)
+}
+
+impl From<rusqlite::types::Value> for SnapshotValue {
+    fn from(value: rusqlite::types::Value) -> Self {
+        return match value {
+            rusqlite::types::Value::Null => Self::Null,
+            rusqlite::types::Value::Integer(i) => Self::Integer(i),
+            rusqlite::types::Value::Real(r) => Self::Real(r),
+            rusqlite::types::Value::Text(t) => Self::Text(t),
+            rusqlite::types::Value::Blob(b) => Self::Blob(b)
+        };
+    }
+}
+
+impl Into<rusqlite::types::Value> for SnapshotValue {
+    fn into(self) -> rusqlite::types::Value {
+        return match self {
+            Self::Null => rusqlite::types::Value::Null,
+            Self::Integer(i) => rusqlite::types::Value::Integer(i),
+            Self::Real(r) => rusqlite::types::Value::Real(r),
+            Self::Text(t) => rusqlite::types::Value::Text(t),
+            Self::Blob(b) => rusqlite::types::Value::Blob(b)
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// A copy of a row of any table in the database, which can be inserted again exactly as it was.
+pub struct SnapshotRow {
+    pub table_name: String,
+    /// The OID of the data table the row belongs to, if it belongs to one, so that its display values can be refreshed once it is restored.
+    pub data_table_oid: Option<i64>,
+    pub values: Vec<(String, SnapshotValue)>
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// A reference to a deleted row that was set to NULL when the row was deleted.
+pub struct NulledReference {
+    pub table_oid: i64,
+    pub column_oid: i64,
+    pub row_oid: i64,
+    pub referenced_row_oid: i64
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+/// Everything removed from the database by permanently deleting a row, in the order it must be restored.
+pub struct RowSnapshot {
+    pub rows: Vec<SnapshotRow>,
+    pub nulled_references: Vec<NulledReference>
+}
+
+/// Copies the rows of a table matching a condition into a snapshot, as part of an ongoing action.
+/// Returns the OIDs of the copied rows, if the table has an OID column.
+fn capture_rows_in(trans: &Connection, table_name: &str, data_table_oid: Option<i64>, where_clause: &str, param: i64, snapshot: &mut RowSnapshot) -> Result<Vec<i64>, error::Error> {
+    let select_cmd = format!("SELECT * FROM {table_name} WHERE {where_clause};");
+    let mut stmt = trans.prepare(&select_cmd)?;
+    let column_names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt.query(params![param])?;
+    let mut row_oids: Vec<i64> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut values: Vec<(String, SnapshotValue)> = Vec::new();
+        for (i, column_name) in column_names.iter().enumerate() {
+            let value: rusqlite::types::Value = row.get(i)?;
+            if column_name == "OID" {
+                if let rusqlite::types::Value::Integer(row_oid) = value {
+                    row_oids.push(row_oid);
+                }
+            }
+            values.push((column_name.clone(), value.into()));
+        }
+        snapshot.rows.push(SnapshotRow {
+            table_name: String::from(table_name),
+            data_table_oid,
+            values
+        });
+    }
+    return Ok(row_oids);
+}
+
+/// Copies a row of a data table into a snapshot, followed by everything that would be deleted or changed along with it, as part of an ongoing action.
+/// This includes multi-select values, comments, and tags of the row, the rows of its child tables, the rows of tables that inherit from it, and references to it from other tables.
+fn snapshot_row_in(trans: &Connection, table_oid: i64, row_oid: i64, snapshot: &mut RowSnapshot, visited: &mut HashSet<(i64, i64)>) -> Result<(), error::Error> {
+    if !visited.insert((table_oid, row_oid)) {
+        return Ok(());
+    }
+
+    // Copy the row itself, along with its comments and tags
+    capture_rows_in(trans, &format!("TABLE{table_oid}"), Some(table_oid), "OID = ?1", row_oid, snapshot)?;
+    capture_rows_in(trans, "METADATA_CELL_COMMENT", None, &format!("TABLE_OID = {table_oid} AND ROW_OID = ?1"), row_oid, snapshot)?;
+    capture_rows_in(trans, "METADATA_ROW_TAG", None, &format!("TABLE_OID = {table_oid} AND ROW_OID = ?1"), row_oid, snapshot)?;
+
+    // Copy the selected values of each multi-select dropdown, the object owned by each child object column, and the rows of each child table
+    let mut child_table_oids: Vec<i64> = Vec::new();
+    let mut multiselect_type_oids: Vec<i64> = Vec::new();
+    let mut object_columns: Vec<(i64, i64)> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT c.OID, c.TYPE_OID, t.MODE 
+        FROM METADATA_TABLE_COLUMN c 
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID 
+        WHERE c.TABLE_OID = ?1 AND t.MODE IN (2, 4, 5)
+            AND (t.MODE <> 4 OR EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || c.TABLE_OID) p WHERE p.name = 'COLUMN' || c.OID));", 
+        params![table_oid], 
+        &mut |row| {
+            let column_type_oid: i64 = row.get("TYPE_OID")?;
+            match row.get::<_, i64>("MODE")? {
+                2 => multiselect_type_oids.push(column_type_oid),
+                4 => object_columns.push((row.get("OID")?, column_type_oid)),
+                _ => child_table_oids.push(column_type_oid)
+            }
+            return Ok(());
+        }
+    )?;
+    for column_type_oid in multiselect_type_oids {
+        capture_rows_in(trans, &format!("TABLE{column_type_oid}_MULTISELECT"), None, "ROW_OID = ?1", row_oid, snapshot)?;
+    }
+    let mut dependents: Vec<(i64, i64)> = Vec::new();
+    for (column_oid, object_table_oid) in object_columns {
+        let select_cmd = format!("SELECT COLUMN{column_oid} FROM TABLE{table_oid} WHERE OID = ?1 AND COLUMN{column_oid} IS NOT NULL;");
+        db::query_iterate(trans, &select_cmd, params![row_oid], &mut |row| {
+            dependents.push((object_table_oid, row.get(0)?));
+            return Ok(());
+        })?;
+    }
+    for child_table_oid in child_table_oids {
+        let select_cmd = format!("SELECT OID FROM TABLE{child_table_oid} WHERE PARENT_OID = ?1;");
+        db::query_iterate(trans, &select_cmd, params![row_oid], &mut |row| {
+            dependents.push((child_table_oid, row.get(0)?));
+            return Ok(());
+        })?;
+    }
+
+    // Copy the rows of any tables inheriting from this one
+    let mut inheritor_table_oids: Vec<i64> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT INHERITOR_TABLE_OID FROM METADATA_TABLE_INHERITANCE WHERE MASTER_TABLE_OID = ?1 AND TRASH = 0;", 
+        params![table_oid], 
+        &mut |row| {
+            inheritor_table_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+    for inheritor_table_oid in inheritor_table_oids {
+        let select_cmd = format!("SELECT OID FROM TABLE{inheritor_table_oid} WHERE MASTER{table_oid}_OID = ?1;");
+        db::query_iterate(trans, &select_cmd, params![row_oid], &mut |row| {
+            dependents.push((inheritor_table_oid, row.get(0)?));
+            return Ok(());
+        })?;
+    }
+
+    // Find references to the row from other tables, including from the row owning it if it is a child object, which are set to NULL
+    let mut referencing_columns: Vec<(i64, i64)> = Vec::new();
+    db::query_iterate(trans, 
+        "SELECT 
+            c.TABLE_OID, 
+            c.OID 
+        FROM METADATA_TABLE_COLUMN c 
+        WHERE c.TYPE_OID = ?1 
+            AND EXISTS (SELECT 1 FROM pragma_table_info('TABLE' || c.TABLE_OID) p WHERE p.name = 'COLUMN' || c.OID);", 
+        params![table_oid], 
+        &mut |row| {
+            referencing_columns.push((row.get("TABLE_OID")?, row.get("OID")?));
+            return Ok(());
+        }
+    )?;
+    for (referencing_table_oid, column_oid) in referencing_columns {
+        let select_cmd = format!("SELECT OID FROM TABLE{referencing_table_oid} WHERE COLUMN{column_oid} = ?1;");
+        db::query_iterate(trans, &select_cmd, params![row_oid], &mut |row| {
+            // The row owning a deleted child object is also listed here, which is harmless if it is deleted too, as it is restored with the reference intact
+            let referencing_row_oid: i64 = row.get(0)?;
+            snapshot.nulled_references.push(NulledReference {
+                table_oid: referencing_table_oid,
+                column_oid,
+                row_oid: referencing_row_oid,
+                referenced_row_oid: row_oid
+            });
+            return Ok(());
+        })?;
+    }
+
+    // Copy each dependent row after the row it depends on
+    for (dependent_table_oid, dependent_row_oid) in dependents {
+        snapshot_row_in(trans, dependent_table_oid, dependent_row_oid, snapshot, visited)?;
+    }
+    return Ok(());
+}
+
+/// Permanently deletes the row with the given OID, along with everything that depends on it.
+/// Returns a snapshot of everything that was removed, so that the deletion can be undone.
+pub fn delete(table_oid: i64, row_oid: i64) -> Result<RowSnapshot, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(delete_in(&action, table_oid, row_oid));
+}
+
+/// Permanently deletes a row, as part of an ongoing action.
+fn delete_in(trans: &Connection, table_oid: i64, row_oid: i64) -> Result<RowSnapshot, error::Error> {
+    // Copy the row and everything depending on it before it is deleted
+    let mut snapshot = RowSnapshot::default();
+    let mut visited: HashSet<(i64, i64)> = HashSet::new();
+    snapshot_row_in(trans, table_oid, row_oid, &mut snapshot, &mut visited)?;
 
-    // Delete the row
+    // Delete the row, which deletes or clears its dependents through their foreign keys
     let delete_cmd = format!("DELETE FROM TABLE{table_oid} WHERE OID = ?1;");
     trans.execute(&delete_cmd, params![row_oid])?;
+    table::invalidate_surrogate_cache(trans, table_oid, Some(row_oid))?;
 
-    // Return the row OID
-    trans.commit()?;
+    // Delete any comments and tags on the row and the rows deleted along with it
+    for (deleted_table_oid, deleted_row_oid) in visited {
+        trans.execute(
+            "DELETE FROM METADATA_CELL_COMMENT WHERE TABLE_OID = ?1 AND ROW_OID = ?2;", 
+            params![deleted_table_oid, deleted_row_oid]
+        )?;
+        trans.execute(
+            "DELETE FROM METADATA_ROW_TAG WHERE TABLE_OID = ?1 AND ROW_OID = ?2;", 
+            params![deleted_table_oid, deleted_row_oid]
+        )?;
+    }
+    return Ok(snapshot);
+}
+
+/// Restores a permanently deleted row from a snapshot taken when it was deleted.
+pub fn restore_deleted(snapshot: RowSnapshot) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(db::with_deferred_fk(&action, |trans| restore_deleted_in(trans, snapshot)));
+}
+
+/// Restores a permanently deleted row from a snapshot, as part of an ongoing action.
+fn restore_deleted_in(trans: &Connection, snapshot: RowSnapshot) -> Result<(), error::Error> {
+    let mut refreshed_table_oids: HashSet<i64> = HashSet::new();
+
+    // Insert each row again
+    for snapshot_row in snapshot.rows {
+        let column_names: Vec<String> = snapshot_row.values.iter().map(|(name, _)| name.clone()).collect();
+        let param_list: Vec<String> = (1..=column_names.len()).map(|i| format!("?{i}")).collect();
+        let insert_cmd = format!("INSERT INTO {} ({}) VALUES ({});", snapshot_row.table_name, column_names.join(", "), param_list.join(", "));
+        let values: Vec<rusqlite::types::Value> = snapshot_row.values.into_iter().map(|(_, value)| value.into()).collect();
+        trans.execute(&insert_cmd, rusqlite::params_from_iter(values))?;
+        if let Some(data_table_oid) = snapshot_row.data_table_oid {
+            refreshed_table_oids.insert(data_table_oid);
+        }
+    }
+
+    // Point each cleared reference back at the restored row
+    for nulled_reference in snapshot.nulled_references {
+        let update_cmd = format!("UPDATE TABLE{} SET COLUMN{} = ?1 WHERE OID = ?2;", nulled_reference.table_oid, nulled_reference.column_oid);
+        trans.execute(&update_cmd, params![nulled_reference.referenced_row_oid, nulled_reference.row_oid])?;
+        refreshed_table_oids.insert(nulled_reference.table_oid);
+    }
+
+    // Recompute the cached display values of every affected table
+    for table_oid in refreshed_table_oids {
+        table::refresh_display_value_cache(trans, table_oid, None)?;
+    }
     return Ok(());
 }
 
 /// Attempts to update a value represented by a primitive in a table.
 /// This applies to primitive types, single-select dropdown types, reference types, and object types.
 /// Returns the previous value of the cell.
-pub fn try_update_primitive_value(table_oid: i64, row_oid: i64, column_oid: i64, mut new_value: Option<String>) -> Result<Option<String>, error::Error> {
-    let mut conn = db::open()?;
-    let trans = conn.transaction()?;
-    
+pub fn try_update_primitive_value(table_oid: i64, row_oid: i64, column_oid: i64, new_value: Option<String>) -> Result<Option<String>, error::Error> {
+    let start = Instant::now();
+    let action = db::begin_db_action()?;
+    let result = action.finish(try_update_primitive_value_in(&action, table_oid, row_oid, column_oid, new_value));
+    log::debug!("Updated value of COLUMN{column_oid} in row {row_oid} of TABLE{table_oid} in {:?}.", start.elapsed());
+    return result;
+}
+
+/// Attempts to update a value represented by a primitive in a table, as part of an ongoing action.
+pub fn try_update_primitive_value_in(trans: &Connection, table_oid: i64, row_oid: i64, column_oid: i64, mut new_value: Option<String>) -> Result<Option<String>, error::Error> {
     // Verify that the column has a primitive type
     let column_type = trans.query_one(
         "SELECT
@@ -210,6 +658,20 @@ pub fn try_update_primitive_value(table_oid: i64, row_oid: i64, column_oid: i64,
                         None => {}
                     }
                 },
+                data_type::Primitive::StringList => {
+                    // If column holds a list of strings, validate that the value is a JSON array of strings
+                    match new_value.clone() {
+                        Some(list_str) => {
+                            match serde_json::from_str::<Vec<String>>(&list_str) {
+                                Ok(_) => {},
+                                Err(_) => {
+                                    return Err(error::Error::AdhocError("The provided value is not a list of strings."));
+                                }
+                            }
+                        },
+                        None => {}
+                    }
+                },
                 data_type::Primitive::Integer => {
                     match new_value.clone() {
                         Some(num_str) => {
@@ -270,6 +732,20 @@ pub fn try_update_primitive_value(table_oid: i64, row_oid: i64, column_oid: i64,
         }
     }
 
+    // Reject a value that another row of a unique column already holds
+    if new_value.is_some() {
+        let is_unique: bool = trans.prepare_cached("SELECT IS_UNIQUE FROM METADATA_TABLE_COLUMN WHERE OID = ?1;")?
+            .query_one(params![column_oid], |row| row.get(0))?;
+        if is_unique {
+            let check_unique_cmd = format!("SELECT EXISTS (SELECT 1 FROM TABLE{table_oid} WHERE COLUMN{column_oid} = ?1 AND OID <> ?2);");
+            let is_duplicate: bool = trans.prepare_cached(&check_unique_cmd)?
+                .query_one(params![new_value, row_oid], |row| row.get(0))?;
+            if is_duplicate {
+                return Err(error::Error::Conflict("Another row already has the same value in this column."));
+            }
+        }
+    }
+
     // Retrieve the previous value
     let select_prev_value_cmd = format!("SELECT CAST(COLUMN{column_oid} AS TEXT) AS PRIOR_VALUE FROM TABLE{table_oid} WHERE OID = ?1;");
     let prev_value: Option<String> = trans.query_one(&select_prev_value_cmd, params![row_oid],
@@ -277,118 +753,1400 @@ pub fn try_update_primitive_value(table_oid: i64, row_oid: i64, column_oid: i64,
 
     // Update the value
     let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE OID = ?2;");
-    trans.execute(
+    match trans.execute(
         &update_cmd,
         params![new_value, row_oid]
-    )?;
+    ) {
+        Ok(_) => {},
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE => {
+            return Err(error::Error::Conflict("Another row already has the same primary key."));
+        },
+        Err(e) => {
+            return Err(e.into());
+        }
+    }
+
+    // Set any automatically-updated timestamp columns of the row to the current time
+    touch_auto_update_columns_in(trans, table_oid, row_oid, column_oid)?;
+
+    // Recompute the cached display value of the row
+    table::refresh_display_value_cache(trans, table_oid, Some(row_oid))?;
 
     // Return OK
-    trans.commit()?;
     return Ok(prev_value);
 }
 
-
-struct Column {
-    true_ord: Option<String>,
-    display_ord: String,
-    table_oid: i64,
-    row_ord: String,
-    column_oid: i64,
-    column_name: String,
-    column_type: data_type::MetadataColumnType,
-    column_ordering: i64,
-    is_nullable: bool,
-    is_primary_key: bool,
-    invalid_nonunique_oid: HashSet<i64>
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// The value that a cell held before it was rewritten.
+pub struct PriorCellValue {
+    pub row_oid: i64,
+    pub value: Option<String>
 }
 
-/// Construct a SELECT query to get data from a table
-fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_clause: bool, include_parent_row_oid_clause: bool) -> Result<(String, LinkedList<Column>), error::Error> {
-    // Build the SELECT query
-    let (mut select_cols_cmd, mut select_tbls_cmd) = trans.query_one(
-        "WITH RECURSIVE SUPERTYPE_QUERY (LEVEL, FINAL_TYPE_OID, SUPERTYPE_OID, INHERITOR_TYPE_OID) AS (
-            SELECT
-                1 AS LEVEL,
-                u.INHERITOR_TABLE_OID AS FINAL_TYPE_OID,
-                u.MASTER_TABLE_OID AS SUPERTYPE_OID,
-                u.INHERITOR_TABLE_OID AS INHERITOR_TYPE_OID
-            FROM METADATA_TABLE_INHERITANCE u ON 
-            WHERE u.TRASH = 0 AND u.INHERITOR_TABLE_OID = ?1
-            UNION
-            SELECT
-                s.LEVEL + 1 AS LEVEL,
-                s.FINAL_TYPE_OID,
-                u.MASTER_TABLE_OID AS SUPERTYPE_OID,
-                u.INHERITOR_TABLE_OID AS INHERITOR_TYPE_OID
-            FROM SUPERTYPE_QUERY s
-            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.INHERITOR_TABLE_OID = s.SUPERTYPE_OID
-            WHERE u.TRASH = 0
-        ),
-        CONDENSED_SUPERTYPE_QUERY (MAX_LEVEL, FINAL_TYPE_OID, SUPERTYPE_OID, JOIN_CLAUSE) AS (
-            SELECT
-                MAX(LEVEL) AS MAX_LEVEL,
-                FINAL_TYPE_OID,
-                SUPERTYPE_OID,
-                'INNER JOIN TABLE' || FORMAT('%d', SUPERTYPE_OID') || ' m' || FORMAT('%d', SUPERTYPE_OID) || ' ' || GROUP_CONCAT(
-                    CASE WHEN INHERITOR_TYPE_OID = FINAL_TYPE_OID THEN 't'
-                    ELSE 'm' || FORMAT('%d', INHERITOR_TYPE_OID)
-                    END || '.MASTER' || FORMAT('%d', SUPERTYPE_OID) || '_OID = m' || FORMAT('%d', SUPERTYPE_OID) || '.OID',
-                    ' AND '
-                ) AS JOIN_CLAUSE
-            FROM SUPERTYPE_QUERY
-            GROUP BY
-                FINAL_TYPE_OID,
-                SUPERTYPE_OID
-        )
-        SELECT
-            'ROW_NUMBER() OVER (ORDER BY t.OID) AS ROW_INDEX, t.OID AS t_OID' || COALESCE(', ' || GROUP_CONCAT('m' || FORMAT('%d', SUPERTYPE_OID) || '.OID AS m' || FORMAT('%d', SUPERTYPE_OID) || '_OID', ', '), '') AS OID_CLAUSE,
-            'FROM TABLE' || FORMAT('%d', FINAL_TYPE_OID) || ' t ' || COALESCE(GROUP_CONCAT(JOIN_CLAUSE, ' ' ORDER BY MAX_LEVEL ASC), '') AS FROM_CLAUSE
-        FROM CONDENSED_SUPERTYPE_QUERY
-        GROUP BY FINAL_TYPE_OID", 
-        params![table_oid], 
-        |row| { 
-            Ok((row.get("OID_CLAUSE")?, row.get("FROM_CLAUSE")?))
-        }
-    )?;
-    let mut columns = LinkedList::<Column>::new();
-    let mut tbl_count: usize = 1;
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The result of a find and replace across a column.
+pub struct FindReplaceResult {
+    pub changed_row_count: i64,
+    pub prior_values: Vec<PriorCellValue>
+}
 
-    db::query_iterate(trans,
-        "WITH RECURSIVE SUPERTYPE_QUERY (TYPE_OID) AS (
-            SELECT
-                ?1
-            UNION
-            SELECT
-                u.MASTER_TABLE_OID AS TYPE_OID
-            FROM SUPERTYPE_QUERY s
-            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.INHERITOR_TABLE_OID = s.TYPE_OID
-            WHERE u.TRASH = 0
-        )
-        SELECT 
-            c.OID,
-            c.TABLE_OID,
+/// Verifies that a column stores text.
+fn verify_text_column(trans: &Connection, column_oid: i64) -> Result<(), error::Error> {
+    let column_type = trans.query_one(
+        "SELECT
             c.TYPE_OID,
-            t.MODE,
-            c.IS_NULLABLE,
-            c.IS_UNIQUE,
-            c.IS_PRIMARY_KEY,
-            c.NAME,
-            c.COLUMN_ORDERING
+            t.MODE
         FROM METADATA_TABLE_COLUMN c
         INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
-        WHERE c.TABLE_OID IN (SELECT * FROM SUPERTYPE_QUERY) AND c.TRASH = 0
-        ORDER BY c.COLUMN_ORDERING;",
-        params![table_oid], 
-        &mut |row| {
-            let column_oid: i64 = row.get("OID")?;
-            let column_type: data_type::MetadataColumnType = data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?);
-            let column_ordering: i64 = row.get("COLUMN_ORDERING")?;
+        WHERE c.OID = ?1", 
+        params![column_oid], 
+        |row| {
+            Ok(data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?))
+        }
+    )?;
+    return match column_type {
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Text)
+        | data_type::MetadataColumnType::Primitive(data_type::Primitive::JSON) => Ok(()),
+        _ => Err(error::Error::AdhocError("Column does not store text."))
+    };
+}
+
+/// Rewrites every value of a text column in the rows that are not in the trash, as part of an ongoing action.
+/// Returns the prior value of each cell that was changed.
+fn rewrite_text_column_in<F: Fn(&str) -> String>(trans: &Connection, table_oid: i64, column_oid: i64, rewrite: F) -> Result<Vec<PriorCellValue>, error::Error> {
+    verify_text_column(trans, column_oid)?;
+
+    // Compute the new value of each cell
+    let mut prior_values: Vec<PriorCellValue> = Vec::new();
+    let mut new_values: Vec<String> = Vec::new();
+    let select_cmd = format!("SELECT OID, COLUMN{column_oid} FROM TABLE{table_oid} WHERE TRASH = 0 AND COLUMN{column_oid} IS NOT NULL;");
+    db::query_iterate(trans, 
+        &select_cmd, 
+        [], 
+        &mut |row| {
+            let prior_value: String = row.get(1)?;
+            let new_value: String = rewrite(&prior_value);
+            if new_value != prior_value {
+                prior_values.push(PriorCellValue {
+                    row_oid: row.get(0)?,
+                    value: Some(prior_value)
+                });
+                new_values.push(new_value);
+            }
+            return Ok(());
+        }
+    )?;
+
+    // Update each cell that changed
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE OID = ?2;");
+    let mut update_stmt = trans.prepare(&update_cmd)?;
+    for (prior_value, new_value) in prior_values.iter().zip(new_values.iter()) {
+        update_stmt.execute(params![new_value, prior_value.row_oid])?;
+    }
+
+    // Recompute the cached display values of the table
+    if !prior_values.is_empty() {
+        table::refresh_display_value_cache(trans, table_oid, None)?;
+    }
+    return Ok(prior_values);
+}
+
+/// Replaces every instance of a case-insensitive substring within a string.
+fn replace_case_insensitive(value: &str, find: &str, replace: &str) -> String {
+    let find_lower: Vec<char> = find.chars().flat_map(|c| c.to_lowercase()).collect();
+    let mut result = String::with_capacity(value.len());
+    let mut remainder: &str = value;
+    'outer: while !remainder.is_empty() {
+        // Check whether the remainder starts with the substring
+        let mut remainder_lower = remainder.char_indices().flat_map(|(i, c)| c.to_lowercase().map(move |l| (i, c, l)));
+        let mut matched_len: usize = 0;
+        for find_char in find_lower.iter() {
+            match remainder_lower.next() {
+                Some((i, c, l)) if l == *find_char => {
+                    matched_len = i + c.len_utf8();
+                },
+                _ => {
+                    // Move on by one character
+                    let c = remainder.chars().next().unwrap();
+                    result.push(c);
+                    remainder = &remainder[c.len_utf8()..];
+                    continue 'outer;
+                }
+            }
+        }
+        result.push_str(replace);
+        remainder = &remainder[matched_len..];
+    }
+    return result;
+}
+
+/// Replaces every instance of a substring in a text column.
+pub fn find_replace(table_oid: i64, column_oid: i64, find: &str, replace: &str, case_sensitive: bool) -> Result<FindReplaceResult, error::Error> {
+    if find.is_empty() {
+        return Err(error::Error::AdhocError("The text to find cannot be empty."));
+    }
+
+    let action = db::begin_db_action()?;
+    let prior_values = action.finish(rewrite_text_column_in(&action, table_oid, column_oid, |value| {
+        if case_sensitive {
+            value.replace(find, replace)
+        } else {
+            replace_case_insensitive(value, find, replace)
+        }
+    }))?;
+    return Ok(FindReplaceResult {
+        changed_row_count: prior_values.len() as i64,
+        prior_values
+    });
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// An operation that normalizes the text in a cell.
+pub enum NormalizeOp {
+    TrimWhitespace,
+    CollapseSpaces,
+    ToUpper,
+    ToLower,
+    ToTitleCase
+}
+
+impl NormalizeOp {
+    /// Applies the operation to a string.
+    fn apply(&self, value: &str) -> String {
+        return match self {
+            Self::TrimWhitespace => String::from(value.trim()),
+            Self::CollapseSpaces => {
+                // Replace each run of whitespace with a single space
+                let mut result = String::with_capacity(value.len());
+                let mut is_prev_whitespace = false;
+                for c in value.chars() {
+                    if c.is_whitespace() {
+                        if !is_prev_whitespace {
+                            result.push(' ');
+                        }
+                        is_prev_whitespace = true;
+                    } else {
+                        result.push(c);
+                        is_prev_whitespace = false;
+                    }
+                }
+                result
+            },
+            Self::ToUpper => value.to_uppercase(),
+            Self::ToLower => value.to_lowercase(),
+            Self::ToTitleCase => {
+                // Capitalize the first letter of each word, and lowercase the rest
+                let mut result = String::with_capacity(value.len());
+                let mut is_word_start = true;
+                for c in value.chars() {
+                    if c.is_whitespace() {
+                        result.push(c);
+                        is_word_start = true;
+                    } else if is_word_start {
+                        result.extend(c.to_uppercase());
+                        is_word_start = false;
+                    } else {
+                        result.extend(c.to_lowercase());
+                    }
+                }
+                result
+            }
+        };
+    }
+}
+
+/// Applies a sequence of normalization operations to every value of a text column.
+/// Returns the prior value of each cell that was changed, so that the normalization can be undone.
+pub fn normalize_column_tracked(table_oid: i64, column_oid: i64, ops: &Vec<NormalizeOp>) -> Result<Vec<PriorCellValue>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(rewrite_text_column_in(&action, table_oid, column_oid, |value| {
+        let mut new_value = String::from(value);
+        for op in ops.iter() {
+            new_value = op.apply(&new_value);
+        }
+        new_value
+    }));
+}
+
+/// Fills each empty cell of a column with the nearest non-empty value above it, in the order the rows are displayed.
+/// Returns the prior value of each cell that was filled, so that the fill can be undone.
+pub fn fill_down_tracked(table_oid: i64, column_oid: i64) -> Result<Vec<PriorCellValue>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(fill_down_in(&action, table_oid, column_oid));
+}
+
+/// Fills each empty cell of a column with the nearest non-empty value above it, as part of an ongoing action.
+fn fill_down_in(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<Vec<PriorCellValue>, error::Error> {
+    // Only columns holding a single value in the table itself can be filled
+    match get_table_column_type_in(trans, table_oid, column_oid)? {
+        data_type::MetadataColumnType::Primitive(prim) if !prim.is_file() => {},
+        data_type::MetadataColumnType::SingleSelectDropdown(_)
+        | data_type::MetadataColumnType::Reference(_) => {},
+        _ => {
+            return Err(error::Error::AdhocError("Only columns holding a single value that can be shared between rows can be filled down."));
+        }
+    }
+
+    // The rows of a child table are filled separately for each parent row, so that values do not carry over from one parent row to the next
+    let is_child_table: bool = trans.query_one(
+        "SELECT PARENT_TABLE_OID IS NOT NULL FROM METADATA_TABLE WHERE TYPE_OID = ?1;", 
+        params![table_oid], 
+        |row| row.get(0)
+    ).optional()?.unwrap_or(false);
+    let select_cmd = if is_child_table {
+        format!("SELECT OID, COLUMN{column_oid}, PARENT_OID FROM TABLE{table_oid} WHERE TRASH = 0 ORDER BY PARENT_OID, ORDER_KEY, OID;")
+    } else {
+        format!("SELECT OID, COLUMN{column_oid}, NULL AS PARENT_OID FROM TABLE{table_oid} WHERE TRASH = 0 ORDER BY ORDER_KEY, OID;")
+    };
+
+    // Find the value above each empty cell
+    let mut prior_values: Vec<PriorCellValue> = Vec::new();
+    let mut new_values: Vec<rusqlite::types::Value> = Vec::new();
+    let mut last_value: Option<rusqlite::types::Value> = None;
+    let mut last_parent_oid: Option<i64> = None;
+    db::query_iterate(trans, &select_cmd, [], &mut |row| {
+        let parent_oid: Option<i64> = row.get(2)?;
+        if parent_oid != last_parent_oid {
+            last_value = None;
+            last_parent_oid = parent_oid;
+        }
+        let value: rusqlite::types::Value = row.get(1)?;
+        match value {
+            rusqlite::types::Value::Null => {
+                if let Some(v) = &last_value {
+                    prior_values.push(PriorCellValue {
+                        row_oid: row.get(0)?,
+                        value: None
+                    });
+                    new_values.push(v.clone());
+                }
+            },
+            v => {
+                last_value = Some(v);
+            }
+        }
+        return Ok(());
+    })?;
+
+    // Fill each empty cell
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE OID = ?2;");
+    let mut update_stmt = trans.prepare(&update_cmd)?;
+    for (prior_value, new_value) in prior_values.iter().zip(new_values.into_iter()) {
+        update_stmt.execute(params![new_value, prior_value.row_oid])?;
+    }
+
+    // Recompute the cached display values of the table
+    if !prior_values.is_empty() {
+        table::refresh_display_value_cache(trans, table_oid, None)?;
+    }
+    return Ok(prior_values);
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+/// The values that cells of a single column held before they were rewritten.
+pub struct ColumnPriorValues {
+    pub column_oid: i64,
+    pub prior_values: Vec<PriorCellValue>
+}
+
+/// The changes made by splitting a column, which are needed to undo the split.
+pub struct SplitColumnChanges {
+    pub affected_row_count: i64,
+    pub created_column_oids: Vec<i64>,
+    pub prior_values: Vec<ColumnPriorValues>
+}
+
+/// Splits the text of each cell in a column on a delimiter, writing the parts into the target columns.
+/// If more parts are found than there are target columns, the remaining text is kept together in the last target column,
+/// unless new text columns are to be created to hold the extra parts.
+/// Returns the columns that were created and the prior values of the existing target columns, so that the split can be undone.
+pub fn split_column_tracked(table_oid: i64, source_column_oid: i64, delimiter: &str, into_column_oids: &Vec<i64>, create_columns: bool) -> Result<SplitColumnChanges, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(split_column_in(&action, table_oid, source_column_oid, delimiter, into_column_oids, create_columns));
+}
+
+/// Checks whether a column can hold arbitrary text.
+fn is_text_compatible(column_type: &data_type::MetadataColumnType) -> bool {
+    return match column_type {
+        data_type::MetadataColumnType::Primitive(Primitive::Text)
+        | data_type::MetadataColumnType::Primitive(Primitive::Any) => true,
+        _ => false
+    };
+}
+
+/// Splits the text of each cell in a column on a delimiter, writing the parts into the target columns, as part of an ongoing action.
+pub fn split_column_in(trans: &Connection, table_oid: i64, source_column_oid: i64, delimiter: &str, into_column_oids: &Vec<i64>, create_columns: bool) -> Result<SplitColumnChanges, error::Error> {
+    if delimiter.is_empty() {
+        return Err(error::Error::AdhocError("The delimiter to split on cannot be empty."));
+    }
+    if into_column_oids.is_empty() && !create_columns {
+        return Err(error::Error::AdhocError("At least one column must be given to split into."));
+    }
+    if into_column_oids.iter().collect::<HashSet<_>>().len() != into_column_oids.len() {
+        return Err(error::Error::AdhocError("The same column cannot be split into more than once."));
+    }
+
+    // Verify that the source and target columns hold text
+    if !is_text_compatible(&get_table_column_type_in(trans, table_oid, source_column_oid)?) {
+        return Err(error::Error::AdhocError("Only a text column can be split."));
+    }
+    for column_oid in into_column_oids.iter() {
+        if !is_text_compatible(&get_table_column_type_in(trans, table_oid, *column_oid)?) {
+            return Err(error::Error::AdhocError("A column can only be split into text columns."));
+        }
+    }
+
+    // Split the text of each cell
+    let mut split_rows: Vec<(i64, Vec<String>)> = Vec::new();
+    let mut max_part_count: usize = 0;
+    let select_cmd = format!("SELECT OID, COLUMN{source_column_oid} FROM TABLE{table_oid} WHERE TRASH = 0 AND COLUMN{source_column_oid} IS NOT NULL;");
+    db::query_iterate(trans, &select_cmd, [], &mut |row| {
+        let text: String = row.get(1)?;
+        let parts: Vec<String> = text.split(delimiter).map(|part| String::from(part)).collect();
+        max_part_count = max_part_count.max(parts.len());
+        split_rows.push((row.get(0)?, parts));
+        return Ok(());
+    })?;
+
+    // Create text columns to hold any parts beyond the given target columns
+    let mut target_column_oids: Vec<i64> = into_column_oids.clone();
+    let mut created_column_oids: Vec<i64> = Vec::new();
+    if create_columns && max_part_count > target_column_oids.len() {
+        let source_column_name: String = trans.query_one(
+            "SELECT NAME FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", 
+            params![source_column_oid], 
+            |row| row.get(0)
+        )?;
+        for part_index in target_column_oids.len()..max_part_count {
+            let column_name = format!("{source_column_name} ({})", part_index + 1);
+            let column_oid = table_column::create_in(trans, table_oid, &column_name, data_type::MetadataColumnType::Primitive(Primitive::Text), None, "", true, false, false)?;
+            target_column_oids.push(column_oid);
+            created_column_oids.push(column_oid);
+        }
+    }
+    if target_column_oids.is_empty() {
+        return Ok(SplitColumnChanges {
+            affected_row_count: 0,
+            created_column_oids,
+            prior_values: Vec::new()
+        });
+    }
+
+    // Record the prior values of the existing target columns
+    let mut prior_values: Vec<ColumnPriorValues> = Vec::new();
+    for column_oid in into_column_oids.iter() {
+        let prior_select_cmd = format!("SELECT CAST(COLUMN{column_oid} AS TEXT) FROM TABLE{table_oid} WHERE OID = ?1;");
+        let mut column_prior_values: Vec<PriorCellValue> = Vec::new();
+        for (row_oid, _) in split_rows.iter() {
+            column_prior_values.push(PriorCellValue {
+                row_oid: *row_oid,
+                value: trans.prepare_cached(&prior_select_cmd)?.query_row(params![row_oid], |row| row.get(0))?
+            });
+        }
+        prior_values.push(ColumnPriorValues {
+            column_oid: *column_oid,
+            prior_values: column_prior_values
+        });
+    }
+
+    // Write the parts into the target columns, keeping any remaining parts together in the last column
+    let target_count = target_column_oids.len();
+    for (row_oid, parts) in split_rows.iter() {
+        for (target_index, column_oid) in target_column_oids.iter().enumerate() {
+            let part: Option<String> = if target_index + 1 == target_count && parts.len() > target_count {
+                Some(parts[target_index..].join(delimiter))
+            } else {
+                parts.get(target_index).cloned()
+            };
+            let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE OID = ?2;");
+            trans.prepare_cached(&update_cmd)?.execute(params![part.filter(|p| !p.is_empty()), row_oid])?;
+        }
+    }
+
+    // Recompute the cached display values of the table
+    table::refresh_display_value_cache(trans, table_oid, None)?;
+    return Ok(SplitColumnChanges {
+        affected_row_count: split_rows.len() as i64,
+        created_column_oids,
+        prior_values
+    });
+}
+
+/// Concatenates the display values of several columns with a separator, writing the result into a target text column.
+/// Empty cells are either skipped or treated as empty text.
+/// Returns the prior value of each target cell that changed, so that the merge can be undone.
+pub fn merge_columns_tracked(table_oid: i64, source_column_oids: &Vec<i64>, separator: &str, into_column_oid: i64, skip_nulls: bool) -> Result<Vec<PriorCellValue>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(merge_columns_in(&action, table_oid, source_column_oids, separator, into_column_oid, skip_nulls));
+}
+
+/// Concatenates the display values of several columns with a separator, writing the result into a target text column, as part of an ongoing action.
+pub fn merge_columns_in(trans: &Connection, table_oid: i64, source_column_oids: &Vec<i64>, separator: &str, into_column_oid: i64, skip_nulls: bool) -> Result<Vec<PriorCellValue>, error::Error> {
+    if source_column_oids.is_empty() {
+        return Err(error::Error::AdhocError("At least one column must be given to merge."));
+    }
+
+    // Verify that the target column holds text
+    if !is_text_compatible(&get_table_column_type_in(trans, table_oid, into_column_oid)?) {
+        return Err(error::Error::AdhocError("Columns can only be merged into a text column."));
+    }
+
+    // Construct the display value of each source column
+    let mut display_exprs: Vec<String> = Vec::new();
+    for column_oid in source_column_oids.iter() {
+        let column_type = get_table_column_type_in(trans, table_oid, *column_oid)?;
+        display_exprs.push(construct_display_value_expr(*column_oid, &column_type, "t"));
+    }
+
+    // Concatenate the display values of each row
+    let source_count = source_column_oids.len();
+    let mut prior_values: Vec<PriorCellValue> = Vec::new();
+    let mut new_values: Vec<Option<String>> = Vec::new();
+    let select_cmd = format!("SELECT t.OID, CAST(t.COLUMN{into_column_oid} AS TEXT), {} FROM TABLE{table_oid} t WHERE t.TRASH = 0;", display_exprs.join(", "));
+    db::query_iterate(trans, &select_cmd, [], &mut |row| {
+        let mut parts: Vec<String> = Vec::new();
+        for source_index in 0..source_count {
+            let display_value: Option<String> = row.get(source_index + 2)?;
+            match display_value {
+                Some(v) => parts.push(v),
+                None => if !skip_nulls {
+                    parts.push(String::new());
+                }
+            }
+        }
+        let merged_value = Some(parts.join(separator)).filter(|v| !v.is_empty());
+        let prior_value: Option<String> = row.get(1)?;
+        if merged_value != prior_value {
+            prior_values.push(PriorCellValue {
+                row_oid: row.get(0)?,
+                value: prior_value
+            });
+            new_values.push(merged_value);
+        }
+        return Ok(());
+    })?;
+
+    // Write the merged values into the target column
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{into_column_oid} = ?1 WHERE OID = ?2;");
+    let mut update_stmt = trans.prepare(&update_cmd)?;
+    for (prior_value, new_value) in prior_values.iter().zip(new_values.into_iter()) {
+        update_stmt.execute(params![new_value, prior_value.row_oid])?;
+    }
+
+    // Recompute the cached display values of the table
+    if !prior_values.is_empty() {
+        table::refresh_display_value_cache(trans, table_oid, None)?;
+    }
+    return Ok(prior_values);
+}
+
+/// Undoes a split of a column, restoring the prior values of the target columns and trashing any columns the split created.
+pub fn restore_split_column(table_oid: i64, created_column_oids: &Vec<i64>, prior_values: &Vec<ColumnPriorValues>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(restore_split_column_in(&action, table_oid, created_column_oids, prior_values));
+}
+
+/// Undoes a split of a column, as part of an ongoing action.
+fn restore_split_column_in(trans: &Connection, table_oid: i64, created_column_oids: &Vec<i64>, prior_values: &Vec<ColumnPriorValues>) -> Result<(), error::Error> {
+    // Restore the prior values of the existing target columns
+    for column_prior_values in prior_values.iter() {
+        restore_column_values_in(trans, table_oid, column_prior_values.column_oid, &column_prior_values.prior_values)?;
+    }
+
+    // Trash the columns created by the split
+    for column_oid in created_column_oids.iter() {
+        table_column::move_trash_in(trans, table_oid, *column_oid)?;
+    }
+    return Ok(());
+}
+
+/// Restores the values of cells in a column.
+/// Returns the values that the cells held before being restored.
+pub fn restore_column_values(table_oid: i64, column_oid: i64, prior_values: &Vec<PriorCellValue>) -> Result<Vec<PriorCellValue>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(restore_column_values_in(&action, table_oid, column_oid, prior_values));
+}
+
+/// Restores the values of cells in a column, as part of an ongoing action.
+fn restore_column_values_in(trans: &Connection, table_oid: i64, column_oid: i64, prior_values: &Vec<PriorCellValue>) -> Result<Vec<PriorCellValue>, error::Error> {
+    let select_cmd = format!("SELECT CAST(COLUMN{column_oid} AS TEXT) FROM TABLE{table_oid} WHERE OID = ?1;");
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1 WHERE OID = ?2;");
+    let mut replaced_values: Vec<PriorCellValue> = Vec::new();
+    for prior_value in prior_values.iter() {
+        // Record the current value
+        replaced_values.push(PriorCellValue {
+            row_oid: prior_value.row_oid,
+            value: trans.prepare_cached(&select_cmd)?.query_row(params![prior_value.row_oid], |row| row.get(0))?
+        });
+
+        // Restore the prior value
+        trans.prepare_cached(&update_cmd)?.execute(params![prior_value.value, prior_value.row_oid])?;
+    }
+
+    // Recompute the cached display values of the table
+    table::refresh_display_value_cache(trans, table_oid, None)?;
+    return Ok(replaced_values);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A file stored in a cell, along with its original name and MIME type.
+pub struct File {
+    pub name: Option<String>,
+    pub mime_type: Option<String>,
+    pub data: Vec<u8>
+}
+
+/// Verifies that a column stores files.
+fn verify_file_column(trans: &Connection, column_oid: i64) -> Result<(), error::Error> {
+    let column_type = trans.query_one(
+        "SELECT
+            c.TYPE_OID,
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.OID = ?1", 
+        params![column_oid], 
+        |row| {
+            Ok(data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?))
+        }
+    )?;
+    return match column_type {
+        data_type::MetadataColumnType::Primitive(prim) if prim.is_file() => Ok(()),
+        _ => Err(error::Error::AdhocError("Column does not store files."))
+    };
+}
+
+/// Guesses the MIME type of a file from the extension of its name.
+pub fn guess_mime_type(file_name: &str) -> Option<String> {
+    let extension: String = match file_name.rsplit_once('.') {
+        Some((_, ext)) => ext.to_lowercase(),
+        None => { return None; }
+    };
+    let mime_type: &str = match &*extension {
+        "txt" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        _ => { return None; }
+    };
+    return Some(String::from(mime_type));
+}
+
+/// Stores a file in a cell, along with its name and MIME type.
+/// Returns the file that was previously stored in the cell.
+pub fn set_file(table_oid: i64, row_oid: i64, column_oid: i64, file: Option<File>) -> Result<Option<File>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_file_in(&action, table_oid, row_oid, column_oid, file));
+}
+
+/// Stores a file in a cell, along with its name and MIME type, as part of an ongoing action.
+fn set_file_in(trans: &Connection, table_oid: i64, row_oid: i64, column_oid: i64, file: Option<File>) -> Result<Option<File>, error::Error> {
+    verify_file_column(&trans, column_oid)?;
+
+    // Retrieve the previous file
+    let select_prev_cmd = format!("SELECT COLUMN{column_oid} AS DATA, COLUMN{column_oid}_NAME AS NAME, COLUMN{column_oid}_MIME AS MIME FROM TABLE{table_oid} WHERE OID = ?1;");
+    let prev_file: Option<File> = trans.query_one(&select_prev_cmd, params![row_oid], 
+        |row| {
+            return Ok(match row.get::<_, Option<Vec<u8>>>("DATA")? {
+                Some(data) => Some(File { 
+                    name: row.get("NAME")?, 
+                    mime_type: row.get("MIME")?, 
+                    data 
+                }),
+                None => None
+            });
+        }
+    )?;
+
+    // Update the file
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET COLUMN{column_oid} = ?1, COLUMN{column_oid}_NAME = ?2, COLUMN{column_oid}_MIME = ?3 WHERE OID = ?4;");
+    match file {
+        Some(f) => {
+            trans.execute(&update_cmd, params![f.data, f.name, f.mime_type, row_oid])?;
+        },
+        None => {
+            trans.execute(&update_cmd, params![None::<Vec<u8>>, None::<String>, None::<String>, row_oid])?;
+        }
+    }
+
+    return Ok(prev_file);
+}
+
+/// Retrieves the file stored in a cell, along with its name and MIME type.
+pub fn get_file(table_oid: i64, row_oid: i64, column_oid: i64) -> Result<Option<File>, error::Error> {
+    let action = db::begin_readonly_db_action()?;
+    verify_file_column(&action, column_oid)?;
+
+    let select_cmd = format!("SELECT COLUMN{column_oid} AS DATA, COLUMN{column_oid}_NAME AS NAME, COLUMN{column_oid}_MIME AS MIME FROM TABLE{table_oid} WHERE OID = ?1;");
+    return Ok(action.query_one(&select_cmd, params![row_oid], 
+        |row| {
+            return Ok(match row.get::<_, Option<Vec<u8>>>("DATA")? {
+                Some(data) => Some(File { 
+                    name: row.get("NAME")?, 
+                    mime_type: row.get("MIME")?, 
+                    data 
+                }),
+                None => None
+            });
+        }
+    ).optional()?.flatten());
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The name, MIME type, and size of a file stored in a cell.
+pub struct FileMeta {
+    name: Option<String>,
+    mime_type: Option<String>,
+    byte_length: i64
+}
+
+/// Retrieves the name, MIME type, and size of the file stored in a cell, without reading the file's contents.
+pub fn get_file_metadata(table_oid: i64, row_oid: i64, column_oid: i64) -> Result<Option<FileMeta>, error::Error> {
+    let action = db::begin_readonly_db_action()?;
+    verify_file_column(&action, column_oid)?;
+
+    let select_cmd = format!("SELECT LENGTH(COLUMN{column_oid}) AS BYTE_LENGTH, COLUMN{column_oid}_NAME AS NAME, COLUMN{column_oid}_MIME AS MIME FROM TABLE{table_oid} WHERE OID = ?1;");
+    return Ok(action.query_one(&select_cmd, params![row_oid], 
+        |row| {
+            return Ok(match row.get::<_, Option<i64>>("BYTE_LENGTH")? {
+                Some(byte_length) => Some(FileMeta { 
+                    name: row.get("NAME")?, 
+                    mime_type: row.get("MIME")?, 
+                    byte_length 
+                }),
+                None => None
+            });
+        }
+    ).optional()?.flatten());
+}
+
+/// The number of bytes sent at a time when streaming a file.
+const FILE_CHUNK_SIZE: usize = 65536;
+
+/// Sends the contents of the file stored in a cell through a channel, a chunk at a time.
+/// This avoids loading the entire file into memory at once.
+pub fn stream_file(table_oid: i64, row_oid: i64, column_oid: i64, file_channel: Channel<Vec<u8>>) -> Result<(), error::Error> {
+    let action = db::begin_readonly_db_action()?;
+    verify_file_column(&action, column_oid)?;
+
+    // Check that there is a file to send
+    let select_length_cmd = format!("SELECT LENGTH(COLUMN{column_oid}) FROM TABLE{table_oid} WHERE OID = ?1;");
+    let byte_length: Option<i64> = action.query_one(&select_length_cmd, params![row_oid], |row| row.get(0)).optional()?.flatten();
+    if byte_length == None {
+        return Ok(());
+    }
+
+    // Read the file incrementally, sending each chunk to the frontend
+    let table_name = format!("TABLE{table_oid}");
+    let column_name = format!("COLUMN{column_oid}");
+    let mut blob = action.blob_open(MAIN_DB, &*table_name, &*column_name, row_oid, true)?;
+    let mut buffer: Vec<u8> = vec![0; FILE_CHUNK_SIZE];
+    loop {
+        let bytes_read: usize = blob.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        file_channel.send(buffer[..bytes_read].to_vec())?;
+    }
+    return Ok(());
+}
+
+
+struct Column {
+    true_ord: Option<String>,
+    display_ord: String,
+    table_oid: i64,
+    row_ord: String,
+    column_oid: i64,
+    column_name: String,
+    column_type: data_type::MetadataColumnType,
+    column_ordering: i64,
+    is_nullable: bool,
+    is_primary_key: bool,
+    invalid_nonunique_oid: HashSet<i64>,
+    invalid_key_oid: HashSet<i64>,
+    invalid_numeric_oid: HashSet<i64>,
+    date_bound: Option<table_column::DateBound>
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+/// Which row of a group of duplicates is kept.
+pub enum KeepStrategy {
+    First,
+    Last
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// The rows that were moved to the trash by removing duplicates.
+pub struct DedupeReport {
+    pub trashed_row_count: i64,
+    pub trashed_row_oids: Vec<i64>
+}
+
+/// Moves to the trash every row that has the same display values in the key columns as another row, keeping either the first or last row of each group by OID.
+pub fn deduplicate(table_oid: i64, key_column_oids: Vec<i64>, keep: KeepStrategy) -> Result<DedupeReport, error::Error> {
+    let action = db::begin_db_action()?;
+    let trashed_row_oids = action.finish(deduplicate_in(&action, table_oid, &key_column_oids, keep))?;
+    return Ok(DedupeReport {
+        trashed_row_count: trashed_row_oids.len() as i64,
+        trashed_row_oids
+    });
+}
+
+/// Moves duplicate rows to the trash, as part of an ongoing action.
+pub fn deduplicate_in(trans: &Connection, table_oid: i64, key_column_oids: &Vec<i64>, keep: KeepStrategy) -> Result<Vec<i64>, error::Error> {
+    if key_column_oids.is_empty() {
+        return Err(error::Error::AdhocError("At least one column must be selected to find duplicate rows."));
+    }
+
+    // Construct the display value of each key column
+    let mut key_exprs: Vec<String> = Vec::new();
+    for column_oid in key_column_oids.iter() {
+        let column_type = trans.query_one(
+            "SELECT
+                c.TYPE_OID,
+                t.MODE
+            FROM METADATA_TABLE_COLUMN c
+            INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+            WHERE c.OID = ?1 AND c.TABLE_OID = ?2 AND c.TRASH = 0;",
+            params![column_oid, table_oid],
+            |row| Ok(data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?))
+        ).optional()?;
+        match column_type {
+            Some(column_type) => {
+                key_exprs.push(construct_display_value_expr(*column_oid, &column_type, "t"));
+            },
+            None => {
+                return Err(error::Error::AdhocError("A column to find duplicate rows by does not belong to the table."));
+            }
+        }
+    }
+
+    // Find every row after the one kept from its group
+    let order = match keep {
+        KeepStrategy::First => "ASC",
+        KeepStrategy::Last => "DESC"
+    };
+    let select_cmd = format!("SELECT OID FROM (
+            SELECT t.OID, ROW_NUMBER() OVER (PARTITION BY {} ORDER BY t.OID {order}) AS GROUP_INDEX
+            FROM TABLE{table_oid} t
+            WHERE t.TRASH = 0
+        )
+        WHERE GROUP_INDEX > 1
+        ORDER BY OID;", key_exprs.join(", "));
+    let mut trashed_row_oids: Vec<i64> = Vec::new();
+    db::query_iterate(trans, &select_cmd, [], &mut |row| {
+        trashed_row_oids.push(row.get(0)?);
+        return Ok(());
+    })?;
+
+    // Move the duplicate rows to the trash
+    move_trash_rows_in(trans, table_oid, &trashed_row_oids)?;
+    return Ok(trashed_row_oids);
+}
+
+/// Marks every row of a table as trash.
+/// Returns the rows that were marked, so that they can be restored.
+pub fn clear(table_oid: i64) -> Result<Vec<i64>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(clear_in(&action, table_oid));
+}
+
+/// Marks every row of a table as trash, as part of an ongoing action.
+pub fn clear_in(trans: &Connection, table_oid: i64) -> Result<Vec<i64>, error::Error> {
+    // Find every row that is not already trash
+    let mut row_oids: Vec<i64> = Vec::new();
+    let select_cmd = format!("SELECT OID FROM TABLE{table_oid} WHERE TRASH = 0 ORDER BY OID;");
+    db::query_iterate(trans, &select_cmd, [], &mut |row| {
+        row_oids.push(row.get(0)?);
+        return Ok(());
+    })?;
+
+    // Move the rows to the trash
+    move_trash_rows_in(trans, table_oid, &row_oids)?;
+    return Ok(row_oids);
+}
+
+/// Marks several rows as trash.
+pub fn move_trash_rows(table_oid: i64, row_oids: Vec<i64>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(move_trash_rows_in(&action, table_oid, &row_oids));
+}
+
+/// Marks several rows as trash, as part of an ongoing action.
+fn move_trash_rows_in(trans: &Connection, table_oid: i64, row_oids: &Vec<i64>) -> Result<(), error::Error> {
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET TRASH = 1 WHERE OID = ?1;");
+    for row_oid in row_oids.iter() {
+        trans.execute(&update_cmd, params![row_oid])?;
+    }
+    return Ok(());
+}
+
+/// Unmarks several rows as trash.
+pub fn unmove_trash_rows(table_oid: i64, row_oids: Vec<i64>) -> Result<(), error::Error> {
+    let action = db::begin_db_action()?;
+    let update_cmd = format!("UPDATE TABLE{table_oid} SET TRASH = 0 WHERE OID = ?1;");
+    let result = row_oids.iter().try_for_each(|row_oid| -> Result<(), error::Error> {
+        action.execute(&update_cmd, params![row_oid])?;
+        return Ok(());
+    });
+    return action.finish(result);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A cell that refers to a row that does not exist.
+pub struct IntegrityViolation {
+    pub row_oid: i64,
+    pub column_oid: i64,
+    pub stale_value: String
+}
+
+/// Finds every cell of a reference or child object column in a table whose value does not match a row of the table it refers to.
+pub fn integrity_report(table_oid: i64) -> Result<Vec<IntegrityViolation>, error::Error> {
+    let conn = db::open()?;
+    return integrity_report_in(&conn, table_oid);
+}
+
+/// Finds every cell of a table that refers to a missing row, as part of an ongoing action.
+pub fn integrity_report_in(trans: &Connection, table_oid: i64) -> Result<Vec<IntegrityViolation>, error::Error> {
+    // Get every column of the table that refers to another table
+    let mut columns: Vec<(i64, i64)> = Vec::new();
+    db::query_iterate(trans,
+        "SELECT
+            c.OID,
+            c.TYPE_OID
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0 AND t.MODE IN (3, 4)
+        ORDER BY c.OID;",
+        params![table_oid],
+        &mut |row| {
+            columns.push((row.get("OID")?, row.get("TYPE_OID")?));
+            return Ok(());
+        }
+    )?;
+
+    // Find the cells of each column that refer to a missing row
+    let mut violations: Vec<IntegrityViolation> = Vec::new();
+    for (column_oid, referenced_table_oid) in columns {
+        let select_cmd = format!("SELECT 
+                t.OID,
+                CAST(t.COLUMN{column_oid} AS TEXT) AS STALE_VALUE
+            FROM TABLE{table_oid} t
+            WHERE t.COLUMN{column_oid} IS NOT NULL 
+                AND NOT EXISTS (SELECT 1 FROM TABLE{referenced_table_oid} r WHERE r.OID = t.COLUMN{column_oid})
+            ORDER BY t.OID;");
+        db::query_iterate(trans, &select_cmd, [], &mut |row| {
+            violations.push(IntegrityViolation {
+                row_oid: row.get("OID")?,
+                column_oid,
+                stale_value: row.get("STALE_VALUE")?
+            });
+            return Ok(());
+        })?;
+    }
+    return Ok(violations);
+}
+
+/// Sets the list of strings held by a cell.
+/// Returns the prior value of the cell, as a JSON array.
+pub fn set_string_list(table_oid: i64, row_oid: i64, column_oid: i64, values: Vec<String>) -> Result<Option<String>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_string_list_in(&action, table_oid, row_oid, column_oid, values));
+}
+
+/// Sets the list of strings held by a cell, as part of an ongoing action.
+fn set_string_list_in(trans: &Connection, table_oid: i64, row_oid: i64, column_oid: i64, values: Vec<String>) -> Result<Option<String>, error::Error> {
+    match get_table_column_type_in(trans, table_oid, column_oid)? {
+        data_type::MetadataColumnType::Primitive(Primitive::StringList) => {},
+        _ => {
+            return Err(error::Error::AdhocError("The column does not hold lists of strings."));
+        }
+    }
+
+    // An empty list is stored as NULL
+    let new_value: Option<String> = if values.is_empty() { None } else { Some(serde_json::to_string(&values)?) };
+    return try_update_primitive_value_in(trans, table_oid, row_oid, column_oid, new_value);
+}
+
+/// Constructs an expression for the display value of a cell, using subqueries rather than joins to look up any referenced values.
+pub fn construct_display_value_expr(column_oid: i64, column_type: &data_type::MetadataColumnType, source_alias: &str) -> String {
+    return match column_type {
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Date) => format!("DATE({source_alias}.COLUMN{column_oid}, 'julianday')"),
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => format!("STRFTIME('%FT%TZ', {source_alias}.COLUMN{column_oid}, 'julianday')"),
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::File) => format!("CASE WHEN {source_alias}.COLUMN{column_oid} IS NULL THEN NULL ELSE FORMAT('%.1f KB', LENGTH({source_alias}.COLUMN{column_oid}) * 0.001) END"),
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Image) => format!("CASE WHEN {source_alias}.COLUMN{column_oid} IS NULL THEN NULL ELSE 'Thumbnail' END"),
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::StringList) => format!("(SELECT '[' || GROUP_CONCAT(j.value) || ']' FROM json_each({source_alias}.COLUMN{column_oid}) j)"),
+        data_type::MetadataColumnType::Primitive(_) => format!("CAST({source_alias}.COLUMN{column_oid} AS TEXT)"),
+        data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => format!("(SELECT v.VALUE FROM TABLE{column_type_oid} v WHERE v.OID = {source_alias}.COLUMN{column_oid})"),
+        data_type::MetadataColumnType::MultiSelectDropdown(column_type_oid) => format!("(SELECT '[' || GROUP_CONCAT(b.VALUE) || ']' FROM TABLE{column_type_oid}_MULTISELECT a INNER JOIN TABLE{column_type_oid} b ON b.OID = a.VALUE_OID WHERE a.ROW_OID = {source_alias}.OID)"),
+        data_type::MetadataColumnType::Reference(referenced_table_oid)
+        | data_type::MetadataColumnType::ChildObject(referenced_table_oid) => format!("COALESCE((SELECT s.DISPLAY_VALUE FROM TABLE{referenced_table_oid}_SURROGATE s WHERE s.OID = {source_alias}.COLUMN{column_oid}), CASE WHEN {source_alias}.COLUMN{column_oid} IS NOT NULL THEN '— DELETED —' ELSE NULL END)"),
+        data_type::MetadataColumnType::ChildTable(column_type_oid) => format!("(SELECT '[' || GROUP_CONCAT(a.DISPLAY_VALUE) || ']' FROM TABLE{column_type_oid}_SURROGATE a WHERE a.PARENT_OID = {source_alias}.OID)")
+    };
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all="camelCase")]
+/// How far along a long-running import or export is.
+pub struct ExportProgress {
+    pub rows_done: i64,
+    pub total: i64
+}
+
+/// How many rows are processed between each report of progress.
+const PROGRESS_INTERVAL: i64 = 500;
+
+/// Reports how far along an import or export is, if a channel was provided, every so many rows and once it is complete.
+/// An error sending the report is returned, so that the operation is aborted if nobody is listening anymore.
+pub fn report_progress(progress: &Option<Channel<ExportProgress>>, rows_done: i64, total: i64) -> Result<(), error::Error> {
+    if let Some(channel) = progress {
+        if rows_done % PROGRESS_INTERVAL == 0 || rows_done == total {
+            channel.send(ExportProgress { rows_done, total })?;
+        }
+    }
+    return Ok(());
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all="camelCase")]
+/// Which rows of a table are exported and in what order, so that an export matches what is shown in the grid.
+pub struct ExportView {
+    #[serde(default)]
+    pub parent_row_oid: Option<i64>,
+    #[serde(default)]
+    pub sort_column_oid: Option<i64>,
+    #[serde(default)]
+    pub sort_ascending: bool,
+    #[serde(default)]
+    pub filter_text: Option<String>
+}
+
+/// Constructs the WHERE and ORDER BY clauses selecting the rows of a table in view, along with the values to bind to them.
+/// The table is expected to be aliased as t.
+/// A row passes the filter if the display value of any of its columns contains the filter text, ignoring case.
+pub fn construct_export_view_clauses(trans: &Connection, table_oid: i64, view: &ExportView) -> Result<(String, String, Vec<rusqlite::types::Value>), error::Error> {
+    let mut where_cmd = String::from("WHERE t.TRASH = 0");
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+    // Scope a child table to the rows of its parent
+    if let Some(parent_row_oid) = view.parent_row_oid {
+        values.push(rusqlite::types::Value::Integer(parent_row_oid));
+        where_cmd = format!("{where_cmd} AND t.PARENT_OID = ?{}", values.len());
+    }
+
+    // Filter on the display value of each column
+    if let Some(filter_text) = view.filter_text.as_ref().filter(|f| !f.trim().is_empty()) {
+        values.push(rusqlite::types::Value::Text(filter_text.trim().to_lowercase()));
+        let filter_param = values.len();
+        let mut filter_exprs: Vec<String> = Vec::new();
+        db::query_iterate(trans, 
+            "SELECT c.OID, c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.TABLE_OID = ?1 AND c.TRASH = 0;", 
+            params![table_oid], 
+            &mut |row| {
+                let column_type = data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?);
+                let display_expr = construct_display_value_expr(row.get("OID")?, &column_type, "t");
+                filter_exprs.push(format!("INSTR(LOWER({display_expr}), ?{filter_param}) > 0"));
+                return Ok(());
+            }
+        )?;
+        if filter_exprs.len() > 0 {
+            where_cmd = format!("{where_cmd} AND ({})", filter_exprs.join(" OR "));
+        }
+    }
+
+    // Sort on the stored value of primitive columns, and on the display value of everything else
+    let order_cmd = match view.sort_column_oid {
+        Some(sort_column_oid) => {
+            let column_type: data_type::MetadataColumnType = trans.query_one(
+                "SELECT c.TYPE_OID, t.MODE FROM METADATA_TABLE_COLUMN c INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID WHERE c.OID = ?1 AND c.TABLE_OID = ?2 AND c.TRASH = 0;", 
+                params![sort_column_oid, table_oid], 
+                |row| Ok(data_type::MetadataColumnType::from_database(row.get(0)?, row.get(1)?))
+            ).optional()?.ok_or(error::Error::AdhocError("The column to sort by does not belong to the table."))?;
+            let sort_expr = match &column_type {
+                data_type::MetadataColumnType::Primitive(_) => format!("t.COLUMN{sort_column_oid}"),
+                _ => construct_display_value_expr(sort_column_oid, &column_type, "t")
+            };
+            let direction = if view.sort_ascending { "ASC" } else { "DESC" };
+            format!("ORDER BY {sort_expr} IS NULL, {sort_expr} {direction}, t.ORDER_KEY, t.OID")
+        },
+        None => String::from("ORDER BY t.ORDER_KEY, t.OID")
+    };
+    return Ok((where_cmd, order_cmd, values));
+}
+
+static NEXT_OPERATION_ID: AtomicI64 = AtomicI64::new(1);
+static OPERATIONS: Mutex<BTreeMap<i64, Arc<AtomicBool>>> = Mutex::new(BTreeMap::new());
+
+/// Registers a long-running import or export that can be cancelled, returning the ID that identifies it.
+pub fn begin_operation() -> i64 {
+    let operation_id = NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed);
+    OPERATIONS.lock().unwrap().insert(operation_id, Arc::new(AtomicBool::new(false)));
+    return operation_id;
+}
+
+/// Requests that a long-running import or export is cancelled.
+/// Returns false if no operation with that ID is running.
+pub fn cancel_operation(operation_id: i64) -> bool {
+    return match OPERATIONS.lock().unwrap().get(&operation_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        },
+        None => false
+    };
+}
+
+/// Checked by a long-running import or export to see whether it has been cancelled.
+/// The operation is unregistered once the token is dropped.
+pub struct CancellationToken {
+    operation_id: Option<i64>,
+    cancelled: Option<Arc<AtomicBool>>
+}
+
+impl CancellationToken {
+    /// Gets the token for a registered operation, or a token that is never cancelled if no ID is provided.
+    pub fn new(operation_id: Option<i64>) -> Self {
+        let cancelled = operation_id.and_then(|id| OPERATIONS.lock().unwrap().get(&id).cloned());
+        return CancellationToken { operation_id, cancelled };
+    }
+
+    /// Returns an error if the operation has been cancelled.
+    pub fn check(&self) -> Result<(), error::Error> {
+        if let Some(cancelled) = &self.cancelled {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(error::Error::Cancelled);
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl Drop for CancellationToken {
+    fn drop(&mut self) {
+        if let Some(operation_id) = self.operation_id {
+            OPERATIONS.lock().unwrap().remove(&operation_id);
+        }
+    }
+}
+
+/// Julian day of the moment that Excel counts its dates from (midnight at the start of 1899-12-30).
+const EXCEL_EPOCH_JULIAN_DAY: f64 = 2415018.5;
+
+/// Writes the rows of a table to an Excel workbook, with a header row of column names.
+/// Numbers, booleans, dates, and timestamps are written as typed cells, and everything else is written as its display value.
+/// If a progress channel is provided, progress is reported to it as rows are written.
+/// If an operation ID is provided, the export stops without writing the file once the operation is cancelled.
+pub fn export_xlsx(table_oid: i64, path: &Path, view: &ExportView, progress: Option<Channel<ExportProgress>>, operation_id: Option<i64>) -> Result<(), error::Error> {
+    let cancellation = CancellationToken::new(operation_id);
+    let start = Instant::now();
+    let action = db::begin_readonly_db_action()?;
+
+    // Get the name and columns of the table
+    let table_name: String = action.query_one("SELECT NAME FROM METADATA_TABLE WHERE TYPE_OID = ?1;", params![table_oid], |row| row.get(0))?;
+    let mut columns: Vec<(i64, String, data_type::MetadataColumnType)> = Vec::new();
+    db::query_iterate(&action, 
+        "SELECT
+            c.OID,
+            c.NAME,
+            c.TYPE_OID,
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID = ?1 AND c.TRASH = 0
+        ORDER BY c.COLUMN_ORDERING, c.OID;", 
+        params![table_oid], 
+        &mut |row| {
+            columns.push((row.get("OID")?, row.get("NAME")?, data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?)));
+            return Ok(());
+        }
+    )?;
+
+    // Set up the worksheet, whose name cannot contain certain characters or exceed 31 characters
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let sheet_name: String = table_name.chars().filter(|c| !"[]:*?/\\".contains(*c)).take(31).collect();
+    if !sheet_name.trim().is_empty() {
+        worksheet.set_name(sheet_name.trim())?;
+    }
+    let header_format = Format::new().set_bold();
+    let number_format = Format::new().set_num_format("0.00");
+    let date_format = Format::new().set_num_format("yyyy-mm-dd");
+    let timestamp_format = Format::new().set_num_format("yyyy-mm-dd hh:mm:ss");
+
+    // Write the header
+    for (col_idx, (_, column_name, _)) in columns.iter().enumerate() {
+        worksheet.write_string_with_format(0, col_idx as u16, column_name, &header_format)?;
+    }
+
+    // Select the stored value of typed columns, and the display value of everything else
+    if columns.len() > 0 {
+        let select_cols: Vec<String> = columns.iter().map(|(column_oid, _, column_type)| match column_type {
+            data_type::MetadataColumnType::Primitive(data_type::Primitive::Boolean)
+            | data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer)
+            | data_type::MetadataColumnType::Primitive(data_type::Primitive::Number)
+            | data_type::MetadataColumnType::Primitive(data_type::Primitive::Date)
+            | data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => format!("t.COLUMN{column_oid}"),
+            _ => construct_display_value_expr(*column_oid, column_type, "t")
+        }).collect();
+        let (where_cmd, order_cmd, values) = construct_export_view_clauses(&action, table_oid, view)?;
+        let select_cmd = format!("SELECT {} FROM TABLE{table_oid} t {where_cmd} {order_cmd};", select_cols.join(", "));
+        let count_cmd = format!("SELECT COUNT(*) FROM TABLE{table_oid} t {where_cmd};");
+        let total: i64 = action.query_one(&count_cmd, rusqlite::params_from_iter(values.iter()), |row| row.get(0))?;
+        report_progress(&progress, 0, total)?;
+        let mut row_idx: u32 = 1;
+        db::query_iterate(&action, &select_cmd, rusqlite::params_from_iter(values.iter()), 
+            &mut |row| {
+                for (col_idx, (_, _, column_type)) in columns.iter().enumerate() {
+                    let col_idx = col_idx as u16;
+                    match column_type {
+                        data_type::MetadataColumnType::Primitive(data_type::Primitive::Boolean) => {
+                            if let Some(b) = row.get::<_, Option<bool>>(col_idx as usize)? {
+                                worksheet.write_boolean(row_idx, col_idx, b)?;
+                            }
+                        },
+                        data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer) => {
+                            if let Some(n) = row.get::<_, Option<f64>>(col_idx as usize)? {
+                                worksheet.write_number(row_idx, col_idx, n)?;
+                            }
+                        },
+                        data_type::MetadataColumnType::Primitive(data_type::Primitive::Number) => {
+                            if let Some(n) = row.get::<_, Option<f64>>(col_idx as usize)? {
+                                worksheet.write_number_with_format(row_idx, col_idx, n, &number_format)?;
+                            }
+                        },
+                        data_type::MetadataColumnType::Primitive(data_type::Primitive::Date) => {
+                            // Dates are stored as the julian day number, which starts at noon
+                            if let Some(julian_day) = row.get::<_, Option<f64>>(col_idx as usize)? {
+                                worksheet.write_number_with_format(row_idx, col_idx, julian_day.floor() - 0.5 - EXCEL_EPOCH_JULIAN_DAY, &date_format)?;
+                            }
+                        },
+                        data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp) => {
+                            if let Some(julian_day) = row.get::<_, Option<f64>>(col_idx as usize)? {
+                                worksheet.write_number_with_format(row_idx, col_idx, julian_day - EXCEL_EPOCH_JULIAN_DAY, &timestamp_format)?;
+                            }
+                        },
+                        _ => {
+                            if let Some(s) = row.get::<_, Option<String>>(col_idx as usize)? {
+                                worksheet.write_string(row_idx, col_idx, &s)?;
+                            }
+                        }
+                    }
+                }
+                cancellation.check()?;
+                report_progress(&progress, row_idx as i64, total)?;
+                row_idx += 1;
+                return Ok(());
+            }
+        )?;
+    }
+
+    workbook.save(path)?;
+    log::debug!("Exported TABLE{table_oid} to Excel in {:?}.", start.elapsed());
+    return Ok(());
+}
+
+/// Gets the display values of only the specified columns in a row, keyed by column OID.
+/// Columns that do not belong to the table or any of its supertypes are left out, as is everything if the row does not exist.
+pub fn get_cell_values(table_oid: i64, row_oid: i64, column_oids: Vec<i64>) -> Result<HashMap<i64, Option<String>>, error::Error> {
+    let action = db::begin_readonly_db_action()?;
+
+    // Get the type of each requested column
+    let mut select_cols: Vec<String> = Vec::new();
+    let mut selected_column_oids: Vec<i64> = Vec::new();
+    db::query_iterate_cached(&action, 
+        "WITH RECURSIVE SUPERTYPE_QUERY (TYPE_OID) AS (
+            SELECT
+                ?1
+            UNION
+            SELECT
+                u.MASTER_TABLE_OID AS TYPE_OID
+            FROM SUPERTYPE_QUERY s
+            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.INHERITOR_TABLE_OID = s.TYPE_OID
+            WHERE u.TRASH = 0
+        )
+        SELECT 
+            c.OID,
+            c.TABLE_OID,
+            c.TYPE_OID,
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID IN (SELECT * FROM SUPERTYPE_QUERY) AND c.TRASH = 0;", 
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            if !column_oids.contains(&column_oid) {
+                return Ok(());
+            }
+            let column_type = data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?);
+            let column_source_table_oid: i64 = row.get("TABLE_OID")?;
+            let source_alias: String = if column_source_table_oid == table_oid { String::from("t") } else { format!("m{column_source_table_oid}") };
+            select_cols.push(format!("{} AS COLUMN{column_oid}", construct_display_value_expr(column_oid, &column_type, &source_alias)));
+            selected_column_oids.push(column_oid);
+            return Ok(());
+        }
+    )?;
+
+    let mut cell_values: HashMap<i64, Option<String>> = HashMap::new();
+    if selected_column_oids.is_empty() {
+        return Ok(cell_values);
+    }
+
+    // Query only the requested columns of the row
+    let (_, select_tbls_cmd) = construct_supertype_clauses(&action, table_oid)?;
+    let select_cmd = format!("SELECT {} {select_tbls_cmd} WHERE t.OID = ?1;", select_cols.join(", "));
+    action.query_row(&select_cmd, params![row_oid], |row| {
+        for (i, column_oid) in selected_column_oids.iter().enumerate() {
+            cell_values.insert(*column_oid, row.get(i)?);
+        }
+        return Ok(());
+    }).optional()?;
+    return Ok(cell_values);
+}
+
+/// Constructs the OID and FROM clauses that join a table to the tables of all its supertypes.
+/// The table is aliased as t, and each supertype table is aliased as m{SUPERTYPE_OID}.
+pub fn construct_supertype_clauses(trans: &Connection, table_oid: i64) -> Result<(String, String), error::Error> {
+    let clauses: Option<(String, String)> = trans.prepare_cached(
+        "WITH RECURSIVE SUPERTYPE_QUERY (LEVEL, FINAL_TYPE_OID, SUPERTYPE_OID, INHERITOR_TYPE_OID) AS (
+            SELECT
+                1 AS LEVEL,
+                u.INHERITOR_TABLE_OID AS FINAL_TYPE_OID,
+                u.MASTER_TABLE_OID AS SUPERTYPE_OID,
+                u.INHERITOR_TABLE_OID AS INHERITOR_TYPE_OID
+            FROM METADATA_TABLE_INHERITANCE u
+            WHERE u.TRASH = 0 AND u.INHERITOR_TABLE_OID = ?1
+            UNION
+            SELECT
+                s.LEVEL + 1 AS LEVEL,
+                s.FINAL_TYPE_OID,
+                u.MASTER_TABLE_OID AS SUPERTYPE_OID,
+                u.INHERITOR_TABLE_OID AS INHERITOR_TYPE_OID
+            FROM SUPERTYPE_QUERY s
+            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.INHERITOR_TABLE_OID = s.SUPERTYPE_OID
+            WHERE u.TRASH = 0
+        ),
+        CONDENSED_SUPERTYPE_QUERY (MAX_LEVEL, FINAL_TYPE_OID, SUPERTYPE_OID, JOIN_CLAUSE) AS (
+            SELECT
+                MAX(LEVEL) AS MAX_LEVEL,
+                FINAL_TYPE_OID,
+                SUPERTYPE_OID,
+                'INNER JOIN TABLE' || FORMAT('%d', SUPERTYPE_OID) || ' m' || FORMAT('%d', SUPERTYPE_OID) || ' ON ' || GROUP_CONCAT(
+                    CASE WHEN INHERITOR_TYPE_OID = FINAL_TYPE_OID THEN 't'
+                    ELSE 'm' || FORMAT('%d', INHERITOR_TYPE_OID)
+                    END || '.MASTER' || FORMAT('%d', SUPERTYPE_OID) || '_OID = m' || FORMAT('%d', SUPERTYPE_OID) || '.OID',
+                    ' AND '
+                ) AS JOIN_CLAUSE
+            FROM SUPERTYPE_QUERY
+            GROUP BY
+                FINAL_TYPE_OID,
+                SUPERTYPE_OID
+        )
+        SELECT
+            'ROW_NUMBER() OVER (ORDER BY t.ORDER_KEY, t.OID) AS ROW_INDEX, t.OID AS t_OID' || COALESCE(', ' || GROUP_CONCAT('m' || FORMAT('%d', SUPERTYPE_OID) || '.OID AS m' || FORMAT('%d', SUPERTYPE_OID) || '_OID', ', '), '') AS OID_CLAUSE,
+            'FROM TABLE' || FORMAT('%d', FINAL_TYPE_OID) || ' t ' || COALESCE(GROUP_CONCAT(JOIN_CLAUSE, ' ' ORDER BY MAX_LEVEL ASC), '') AS FROM_CLAUSE
+        FROM CONDENSED_SUPERTYPE_QUERY
+        GROUP BY FINAL_TYPE_OID"
+    )?.query_row(
+        params![table_oid], 
+        |row| { 
+            Ok((row.get("OID_CLAUSE")?, row.get("FROM_CLAUSE")?))
+        }
+    ).optional()?;
+    return match clauses {
+        Some(c) => Ok(c),
+        None => Ok((
+            String::from("ROW_NUMBER() OVER (ORDER BY t.ORDER_KEY, t.OID) AS ROW_INDEX, t.OID AS t_OID"),
+            format!("FROM TABLE{table_oid} t")
+        ))
+    };
+}
+
+/// Starts the transaction for loading data from a table.
+/// The transaction commits when it is dropped, so that any surrogate view rebuilt while building the query is kept.
+fn begin_data_query(conn: &mut Connection) -> Result<Transaction<'_>, error::Error> {
+    let mut trans = conn.transaction()?;
+    trans.set_drop_behavior(DropBehavior::Commit);
+    return Ok(trans);
+}
+
+/// The number of tables joined into a data query before any further dropdown and reference columns are looked up with correlated subqueries instead.
+/// Joins are faster for narrow tables, but SQLite cannot join more than 64 tables in a single SELECT statement.
+/// The joined supertype tables share that limit, and SQLite flattens each joined surrogate view into the tables it reads from,
+/// so only half of the limit is given to dropdown and reference columns to leave room for views over subtypes.
+/// Loading a page of a table with 70 reference columns takes about as long either way (see `wide_reference_table_benchmark`).
+const MAX_JOINED_TABLES: usize = 32;
+
+/// Construct a SELECT query to get data from a table
+fn construct_data_query(trans: &Connection, table_oid: i64, include_row_oid_clause: bool, include_parent_row_oid_clause: bool) -> Result<(String, LinkedList<Column>), error::Error> {
+    let start = Instant::now();
+
+    // Build the SELECT query
+    let (mut select_cols_cmd, mut select_tbls_cmd) = construct_supertype_clauses(trans, table_oid)?;
+    let mut columns = LinkedList::<Column>::new();
+    let mut tbl_count: usize = 1;
+    let supertype_count: usize = select_tbls_cmd.matches(" JOIN ").count();
+    let supertype_tbls_cmd: String = select_tbls_cmd.clone();
+    let mut key_exprs: Vec<String> = Vec::new();
+
+    db::query_iterate_cached(trans,
+        "WITH RECURSIVE SUPERTYPE_QUERY (TYPE_OID) AS (
+            SELECT
+                ?1
+            UNION
+            SELECT
+                u.MASTER_TABLE_OID AS TYPE_OID
+            FROM SUPERTYPE_QUERY s
+            INNER JOIN METADATA_TABLE_INHERITANCE u ON u.INHERITOR_TABLE_OID = s.TYPE_OID
+            WHERE u.TRASH = 0
+        )
+        SELECT 
+            c.OID,
+            c.TABLE_OID,
+            c.TYPE_OID,
+            t.MODE,
+            c.IS_NULLABLE,
+            c.IS_UNIQUE,
+            c.IS_PRIMARY_KEY,
+            c.NAME,
+            c.COLUMN_ORDERING,
+            c.DATE_BOUND,
+            c.DECIMAL_PLACES
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.TABLE_OID IN (SELECT * FROM SUPERTYPE_QUERY) AND c.TRASH = 0
+        ORDER BY c.COLUMN_ORDERING;",
+        params![table_oid], 
+        &mut |row| {
+            let column_oid: i64 = row.get("OID")?;
+            let column_type: data_type::MetadataColumnType = data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?);
+            let column_ordering: i64 = row.get("COLUMN_ORDERING")?;
 
             let column_source_table_oid: i64 = row.get("TABLE_OID")?;
             let source_alias: String = if column_source_table_oid == table_oid { String::from("t") } else { format!("m{column_source_table_oid}") };
             
             let enforce_uniqueness: bool = row.get("IS_UNIQUE")?;
             let mut invalid_nonunique_oid: HashSet<i64> = HashSet::<i64>::new();
+            let mut invalid_numeric_oid: HashSet<i64> = HashSet::<i64>::new();
 
             let display_ord: String = format!("COLUMN{column_oid}");
             let true_ord: Option<String>;
@@ -399,11 +2157,25 @@ fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_cla
                         data_type::Primitive::Any 
                         | data_type::Primitive::Boolean
                         | data_type::Primitive::Integer
-                        | data_type::Primitive::Number
                         | data_type::Primitive::Text
                         | data_type::Primitive::JSON => {
                             select_cols_cmd = format!("{select_cols_cmd}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS COLUMN{column_oid}");
                         },
+                        data_type::Primitive::Number => {
+                            // Without a set number of decimal places, SQLite's own conversion of REAL to TEXT gives the shortest representation that reads back as the same value
+                            let decimal_places: Option<i64> = row.get("DECIMAL_PLACES")?;
+                            match decimal_places {
+                                Some(n) => {
+                                    select_cols_cmd = format!("{select_cols_cmd}, PRINTF('%.{n}f', {source_alias}.COLUMN{column_oid}) AS COLUMN{column_oid}");
+                                },
+                                None => {
+                                    select_cols_cmd = format!("{select_cols_cmd}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS COLUMN{column_oid}");
+                                }
+                            }
+                        },
+                        data_type::Primitive::StringList => {
+                            select_cols_cmd = format!("{select_cols_cmd}, (SELECT '[' || GROUP_CONCAT(j.value) || ']' FROM json_each({source_alias}.COLUMN{column_oid}) j) AS COLUMN{column_oid}");
+                        },
                         data_type::Primitive::Date => {
                             select_cols_cmd = format!("{select_cols_cmd}, DATE({source_alias}.COLUMN{column_oid}, 'julianday') AS COLUMN{column_oid}");
                         },
@@ -425,7 +2197,14 @@ fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_cla
                             select_cols_cmd = format!("{select_cols_cmd}, CASE WHEN {source_alias}.COLUMN{column_oid} IS NULL THEN NULL ELSE 'Thumbnail' END AS COLUMN{column_oid}");
                         }
                     }
-                    true_ord = Some(display_ord.clone());
+                    true_ord = match prim {
+                        data_type::Primitive::Number => {
+                            // The raw value is kept alongside the formatted one, so that editing does not lose precision
+                            select_cols_cmd = format!("{select_cols_cmd}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS _COLUMN{column_oid}");
+                            Some(format!("_COLUMN{column_oid}"))
+                        },
+                        _ => Some(display_ord.clone())
+                    };
                     
                     // Check for invalid nonunique rows
                     if enforce_uniqueness {
@@ -445,11 +2224,34 @@ fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_cla
                             }
                         )?;
                     }
+
+                    // Check for values in numeric columns that are not numbers, which can be written by paths that bypass the usual validation
+                    match prim {
+                        data_type::Primitive::Integer
+                        | data_type::Primitive::Number => {
+                            let check_numeric_cmd = format!("
+                                SELECT OID FROM TABLE{column_source_table_oid}
+                                WHERE COLUMN{column_oid} IS NOT NULL AND TYPEOF(COLUMN{column_oid}) NOT IN ('integer', 'real')
+                            ");
+                            db::query_iterate(trans, &check_numeric_cmd, [], 
+                                &mut |row| {
+                                    invalid_numeric_oid.insert(row.get(0)?);
+                                    return Ok(());
+                                }
+                            )?;
+                        },
+                        _ => {}
+                    }
                 },
                 data_type::MetadataColumnType::SingleSelectDropdown(column_type_oid) => {
-                    select_cols_cmd = format!("{select_cols_cmd}, t{tbl_count}.VALUE AS COLUMN{column_oid}, CAST(t{tbl_count}.OID AS TEXT) AS _COLUMN{column_oid}");
-                    select_tbls_cmd = format!("{select_tbls_cmd} LEFT JOIN TABLE{column_type_oid} t{tbl_count} ON t{tbl_count}.OID = {source_alias}.COLUMN{column_oid}");
-                    tbl_count += 1;
+                    if supertype_count + tbl_count < MAX_JOINED_TABLES {
+                        select_cols_cmd = format!("{select_cols_cmd}, t{tbl_count}.VALUE AS COLUMN{column_oid}, CAST(t{tbl_count}.OID AS TEXT) AS _COLUMN{column_oid}");
+                        select_tbls_cmd = format!("{select_tbls_cmd} LEFT JOIN TABLE{column_type_oid} t{tbl_count} ON t{tbl_count}.OID = {source_alias}.COLUMN{column_oid}");
+                        tbl_count += 1;
+                    } else {
+                        // Look up the value with a subquery once too many tables have been joined
+                        select_cols_cmd = format!("{select_cols_cmd}, (SELECT v.VALUE FROM TABLE{column_type_oid} v WHERE v.OID = {source_alias}.COLUMN{column_oid}) AS COLUMN{column_oid}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS _COLUMN{column_oid}");
+                    }
                     true_ord = Some(format!("_COLUMN{column_oid}"));
                     
                     // Check for invalid nonunique rows
@@ -514,9 +2316,20 @@ fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_cla
                 },
                 data_type::MetadataColumnType::Reference(referenced_table_oid) 
                 | data_type::MetadataColumnType::ChildObject(referenced_table_oid) => {
-                    select_cols_cmd = format!("{select_cols_cmd}, COALESCE(t{tbl_count}.DISPLAY_VALUE, CASE WHEN {source_alias}.COLUMN{column_oid} IS NOT NULL THEN '— DELETED —' ELSE NULL END) AS COLUMN{column_oid}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS _COLUMN{column_oid}");
-                    select_tbls_cmd = format!("{select_tbls_cmd} LEFT JOIN TABLE{referenced_table_oid}_SURROGATE t{tbl_count} ON t{tbl_count}.OID = {source_alias}.COLUMN{column_oid}");
-                    tbl_count += 1;
+                    table::ensure_surrogate_view_in(trans, *referenced_table_oid)?;
+                    if supertype_count + tbl_count < MAX_JOINED_TABLES && table::is_display_value_materialized(trans, *referenced_table_oid)? {
+                        // Read the cached display value directly from the referenced table
+                        select_cols_cmd = format!("{select_cols_cmd}, COALESCE(CASE WHEN t{tbl_count}.TRASH = 0 THEN t{tbl_count}.DISPLAY_VALUE_CACHE ELSE '— DELETED —' END, CASE WHEN {source_alias}.COLUMN{column_oid} IS NOT NULL THEN '— DELETED —' ELSE NULL END) AS COLUMN{column_oid}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS _COLUMN{column_oid}");
+                        select_tbls_cmd = format!("{select_tbls_cmd} LEFT JOIN TABLE{referenced_table_oid} t{tbl_count} ON t{tbl_count}.OID = {source_alias}.COLUMN{column_oid}");
+                        tbl_count += 1;
+                    } else if supertype_count + tbl_count < MAX_JOINED_TABLES {
+                        select_cols_cmd = format!("{select_cols_cmd}, COALESCE(t{tbl_count}.DISPLAY_VALUE, CASE WHEN {source_alias}.COLUMN{column_oid} IS NOT NULL THEN '— DELETED —' ELSE NULL END) AS COLUMN{column_oid}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS _COLUMN{column_oid}");
+                        select_tbls_cmd = format!("{select_tbls_cmd} LEFT JOIN TABLE{referenced_table_oid}_SURROGATE t{tbl_count} ON t{tbl_count}.OID = {source_alias}.COLUMN{column_oid}");
+                        tbl_count += 1;
+                    } else {
+                        // Look up the display value with a subquery once too many tables have been joined
+                        select_cols_cmd = format!("{select_cols_cmd}, COALESCE((SELECT s.DISPLAY_VALUE FROM TABLE{referenced_table_oid}_SURROGATE s WHERE s.OID = {source_alias}.COLUMN{column_oid}), CASE WHEN {source_alias}.COLUMN{column_oid} IS NOT NULL THEN '— DELETED —' ELSE NULL END) AS COLUMN{column_oid}, CAST({source_alias}.COLUMN{column_oid} AS TEXT) AS _COLUMN{column_oid}");
+                    }
                     true_ord = Some(format!("_COLUMN{column_oid}"));
                     
                     // Check for invalid nonunique rows
@@ -539,11 +2352,24 @@ fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_cla
                     }
                 },
                 data_type::MetadataColumnType::ChildTable(column_type_oid) => {
+                    table::ensure_surrogate_view_in(trans, *column_type_oid)?;
                     select_cols_cmd = format!("{select_cols_cmd}, (SELECT '[' || GROUP_CONCAT(a.DISPLAY_VALUE) || ']' FROM TABLE{column_type_oid}_SURROGATE a WHERE a.PARENT_OID = {source_alias}.OID GROUP BY a.PARENT_OID) AS COLUMN{column_oid}");
                     true_ord = None;
                 }
             }
 
+            // Remember the primary key columns that are stored as a column of the table (i.e. not multi-select dropdowns or child tables)
+            let is_primary_key: bool = row.get("IS_PRIMARY_KEY")?;
+            if is_primary_key {
+                match &column_type {
+                    data_type::MetadataColumnType::MultiSelectDropdown(_)
+                    | data_type::MetadataColumnType::ChildTable(_) => {},
+                    _ => {
+                        key_exprs.push(format!("{source_alias}.COLUMN{column_oid}"));
+                    }
+                }
+            }
+
             // Push the column information
             columns.push_back(Column {
                 true_ord: true_ord, 
@@ -556,108 +2382,525 @@ fn construct_data_query(trans: &Transaction, table_oid: i64, include_row_oid_cla
                 column_ordering,
                 is_nullable: row.get("IS_NULLABLE")?,
                 invalid_nonunique_oid: invalid_nonunique_oid,
-                is_primary_key: row.get("IS_PRIMARY_KEY")?
+                invalid_key_oid: HashSet::<i64>::new(),
+                invalid_numeric_oid: invalid_numeric_oid,
+                date_bound: table_column::parse_date_bound(row.get("DATE_BOUND")?),
+                is_primary_key
             });
             return Ok(());
         }
     )?;
-    return Ok((
+
+    // Check for rows whose primary key collides with another row, treating the key columns of the table and its supertypes as a single tuple
+    if key_exprs.len() > 0 {
+        let mut invalid_key_oid: HashSet<i64> = HashSet::<i64>::new();
+        let check_key_cmd = format!("
+            SELECT k.t_OID FROM (
+                SELECT t.OID AS t_OID, COUNT(*) OVER (PARTITION BY {}) AS KEY_COUNT
+                {supertype_tbls_cmd}
+                WHERE t.TRASH = 0 AND {}
+            ) k
+            WHERE k.KEY_COUNT > 1
+        ", 
+            key_exprs.join(", "),
+            key_exprs.iter().map(|e| format!("{e} IS NOT NULL")).collect::<Vec<String>>().join(" AND ")
+        );
+        db::query_iterate(trans, &check_key_cmd, [], 
+            &mut |row| {
+                invalid_key_oid.insert(row.get(0)?);
+                return Ok(());
+            }
+        )?;
+        for column in columns.iter_mut().filter(|c| c.is_primary_key) {
+            column.invalid_key_oid = invalid_key_oid.clone();
+        }
+    }
+    let query = (
         format!(
             "SELECT {select_cols_cmd} {select_tbls_cmd} WHERE t.TRASH = 0 {}",
             if include_row_oid_clause { 
                 "AND t.OID = ?1"
             } else if include_parent_row_oid_clause { 
-                "AND t.PARENT_OID = ?1 LIMIT ?1 OFFSET ?2"
+                // The parent row is bound first, followed by the page size and offset
+                "AND t.PARENT_OID = ?1 ORDER BY ROW_INDEX LIMIT ?2 OFFSET ?3"
             } else {
-                "LIMIT ?1 OFFSET ?2"
+                "ORDER BY ROW_INDEX LIMIT ?1 OFFSET ?2"
             }
         ), 
         columns
-    ));
+    );
+    log::debug!("Built data query for TABLE{table_oid} in {:?}.", start.elapsed());
+    return Ok(query);
+}
+
+/// Gets the SQL of the query used to get a page of data from a table.
+pub fn explain_query(table_oid: i64, parent_row_oid: Option<i64>) -> Result<String, error::Error> {
+    let conn = db::open()?;
+    let (table_select_cmd, _) = construct_data_query(&conn, table_oid, false, parent_row_oid.is_some())?;
+    return Ok(table_select_cmd);
+}
+
+#[derive(Serialize)]
+#[serde(rename_all="camelCase")]
+/// A step in the plan SQLite uses to execute a query.
+pub struct QueryPlanStep {
+    id: i64,
+    parent_id: i64,
+    detail: String
+}
+
+/// Gets the plan that SQLite uses to execute the query for a page of data from a table.
+pub fn explain_query_plan(table_oid: i64, parent_row_oid: Option<i64>) -> Result<Vec<QueryPlanStep>, error::Error> {
+    let conn = db::open()?;
+    let (table_select_cmd, _) = construct_data_query(&conn, table_oid, false, parent_row_oid.is_some())?;
+    let explain_cmd = format!("EXPLAIN QUERY PLAN {table_select_cmd}");
+    let explain_cmd_params: Vec<i64> = match parent_row_oid {
+        Some(o) => vec![o, 1, 0],
+        None => vec![1, 0]
+    };
+
+    // Query the plan, step by step
+    let mut plan: Vec<QueryPlanStep> = Vec::new();
+    db::query_iterate(&conn, 
+        &explain_cmd, 
+        rusqlite::params_from_iter(explain_cmd_params), 
+        &mut |row| {
+            plan.push(QueryPlanStep { 
+                id: row.get(0)?, 
+                parent_id: row.get(1)?, 
+                detail: row.get(3)? 
+            });
+            return Ok(());
+        }
+    )?;
+    return Ok(plan);
+}
+
+/// Gets the cells with comments in the given columns, as pairs of column OID and row OID.
+/// If a row OID is provided, only the cells of that row are retrieved.
+fn get_commented_cells(trans: &Connection, columns: &LinkedList<Column>, row_oid: Option<i64>) -> Result<HashSet<(i64, i64)>, error::Error> {
+    let mut commented_cells: HashSet<(i64, i64)> = HashSet::new();
+    if columns.is_empty() {
+        return Ok(commented_cells);
+    }
+    let column_oid_list: String = columns.iter().map(|c| c.column_oid.to_string()).collect::<Vec<String>>().join(", ");
+    let select_cmd = format!("SELECT COLUMN_OID, ROW_OID FROM METADATA_CELL_COMMENT WHERE COLUMN_OID IN ({column_oid_list}) AND (?1 IS NULL OR ROW_OID = ?1);");
+    db::query_iterate(trans, 
+        &select_cmd, 
+        params![row_oid], 
+        &mut |row| {
+            commented_cells.insert((row.get(0)?, row.get(1)?));
+            return Ok(());
+        }
+    )?;
+    return Ok(commented_cells);
+}
+
+/// The formatting rules of a table, each paired with the ordinal of its condition column's value in a data query.
+/// Rules are evaluated in Rust for each row, against the true value of the column (or its displayed value, for columns without one).
+struct RowFormatRules {
+    rules: Vec<(table::FormatRule, String)>,
+    today: String
+}
+
+impl RowFormatRules {
+    /// Combines the styles of every formatting rule that a row satisfies, so that later rules take precedence.
+    fn get_row_style(&self, row: &Row) -> Result<Option<String>, error::Error> {
+        let mut row_styles: Vec<String> = Vec::new();
+        for (rule, ord) in self.rules.iter() {
+            let cell_value: Option<String> = row.get(&**ord)?;
+            if rule.matches(cell_value.as_deref(), &self.today) {
+                row_styles.push(rule.css_style.clone());
+            }
+        }
+        return Ok(if row_styles.is_empty() { None } else { Some(row_styles.join("; ")) });
+    }
+}
+
+/// Gets the formatting rules of a table whose condition columns are selected by a data query.
+fn get_format_rules_in(trans: &Connection, table_oid: i64, columns: &LinkedList<Column>) -> Result<RowFormatRules, error::Error> {
+    let today: String = trans.query_one("SELECT DATE('now');", [], |row| row.get(0))?;
+    let mut rules: Vec<(table::FormatRule, String)> = Vec::new();
+    for rule in table::get_format_rules_in(trans, table_oid)? {
+        match columns.iter().find(|c| c.column_oid == rule.condition_column_oid) {
+            Some(column) => {
+                let ord = column.true_ord.clone().unwrap_or(column.display_ord.clone());
+                rules.push((rule, ord));
+            },
+            None => {}
+        }
+    }
+    return Ok(RowFormatRules { rules, today });
+}
+
+/// Gets the current time as a timestamp, against which date bounds are validated.
+fn get_validation_time_in(trans: &Connection) -> Result<String, error::Error> {
+    return Ok(trans.query_one("SELECT STRFTIME('%FT%TZ', 'now');", [], |row| row.get(0))?);
+}
+
+/// Gets the cell of each column in a row of the results of a data query, validating each value.
+/// The current time is given as a timestamp, computed once for every row that is loaded together.
+fn get_column_cells(row: &Row, columns: &LinkedList<Column>, commented_cells: &HashSet<(i64, i64)>, now: &str) -> Result<Vec<Cell>, error::Error> {
+    let table_row_oid: i64 = row.get("t_OID")?;
+
+    // Iterate over the columns, getting the displayed value of that cell in the current row for each
+    let mut cells: Vec<Cell> = Vec::new();
+    for column in columns.iter() {
+        let row_oid: i64 = row.get(&*column.row_ord)?;
+
+        let true_value: Option<String> = match column.true_ord.clone() {
+            Some(ord) => row.get::<&str, Option<String>>(&*ord)?,
+            None => None
+        };
+        let display_value: Option<String> = row.get(&*column.display_ord.clone())?;
+        let mut failed_validations: Vec<error::FailedValidation> = Vec::<error::FailedValidation>::new();
+
+        // Nullability validation
+        if !column.is_nullable && display_value == None {
+            failed_validations.push(error::FailedValidation::new("validation.notNull", error::Severity::Error, &[("columnName", &column.column_name)]));
+        }
+
+        // Uniqueness validation
+        if column.invalid_nonunique_oid.contains(&row_oid) {
+            failed_validations.push(error::FailedValidation::new("validation.notUnique", error::Severity::Error, &[("columnName", &column.column_name)]));
+        }
+
+        // Primary key validation
+        if column.is_primary_key && column.invalid_key_oid.contains(&table_row_oid) {
+            failed_validations.push(error::FailedValidation::new("validation.primaryKeyNotUnique", error::Severity::Error, &[]));
+        }
+
+        // Numeric type validation
+        if column.invalid_numeric_oid.contains(&row_oid) {
+            failed_validations.push(error::FailedValidation::new("validation.notANumber", error::Severity::Error, &[("columnName", &column.column_name)]));
+        }
+
+        // Date bound validation
+        match (&column.date_bound, &true_value) {
+            (Some(date_bound), Some(value)) if date_bound.is_violated_by(value, now) => {
+                failed_validations.push(error::FailedValidation::new("validation.dateOutOfBounds", date_bound.get_severity(), &[("columnName", &column.column_name)]));
+            },
+            _ => {}
+        }
+        for failed_validation in failed_validations.iter() {
+            log::debug!("Row {row_oid} of TABLE{} failed validation of COLUMN{}: {failed_validation}", column.table_oid, column.column_oid);
+        }
+
+        cells.push(Cell::ColumnValue {
+            table_oid: column.table_oid,
+            row_oid: row_oid,
+            column_oid: column.column_oid, 
+            column_type: column.column_type.clone(), 
+            true_value: true_value,
+            display_value: display_value,
+            failed_validations: failed_validations,
+            has_comment: commented_cells.contains(&(column.column_oid, row_oid))
+        });
+    }
+    return Ok(cells);
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all="camelCase")]
+/// Timings for each stage of sending a page of table data, in microseconds.
+pub struct TableDataProfile {
+    pub table_oid: i64,
+    pub query_build_micros: u128,
+    pub query_execution_micros: u128,
+    pub cell_send_micros: u128
 }
 
 /// Sends all cells for the table through a channel.
-pub fn send_table_data(table_oid: i64, parent_row_oid: Option<i64>, page_num: i64, page_size: i64, cell_channel: Channel<Cell>) -> Result<(), error::Error> {
+/// Returns how long each stage of the process took.
+pub fn send_table_data(table_oid: i64, parent_row_oid: Option<i64>, page_num: i64, page_size: i64, cell_channel: Channel<Cell>) -> Result<TableDataProfile, error::Error> {
     let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let trans = begin_data_query(&mut conn)?;
+
+    // Build the query
+    let query_build_start = Instant::now();
     let (table_select_cmd, columns) = construct_data_query(&trans, table_oid, false, match parent_row_oid { Some(_) => true, None => false })?;
     let table_select_cmd_params = match parent_row_oid {
         Some(o) => params![o.clone(), page_size, page_size * (page_num - 1)],
         None => params![page_size, page_size * (page_num - 1)]
     };
+    let query_build_duration = query_build_start.elapsed();
+    log::debug!("Querying data for TABLE{table_oid}: {table_select_cmd}");
+    let commented_cells = get_commented_cells(&trans, &columns, None)?;
 
-    println!("{table_select_cmd}");
+    let format_rules = get_format_rules_in(&trans, table_oid, &columns)?;
+    let now: String = get_validation_time_in(&trans)?;
 
     // Iterate over the results, sending each cell to the frontend
+    let query_start = Instant::now();
+    let mut cell_send_duration = Duration::ZERO;
     db::query_iterate(&trans, 
         &table_select_cmd, 
         table_select_cmd_params,
         &mut |row| {
             // Start by sending the index and OID, which are the first and second ordinal respectively
             let row_index: i64 = row.get("ROW_INDEX")?;
+            let row_oid: i64 = row.get("t_OID")?;
+            let send_start = Instant::now();
             cell_channel.send(Cell::RowStart {
-                row_oid: row.get("t_OID")?,
-                row_index: row_index
+                row_oid: row_oid,
+                row_index: row_index,
+                row_style: format_rules.get_row_style(row)?
             })?;
+            cell_send_duration += send_start.elapsed();
 
-            let invalid_key: bool = false; // TODO
-
-            // Iterate over the columns, sending over the displayed value of that cell in the current row for each
-            for column in columns.iter() {
-                let row_oid: i64 = row.get(&*column.row_ord)?;
+            // Send the value of each column in the row
+            let column_cells = get_column_cells(row, &columns, &commented_cells, &now)?;
+            let send_start = Instant::now();
+            for cell in column_cells {
+                cell_channel.send(cell)?;
+            }
+            cell_send_duration += send_start.elapsed();
 
-                let true_value: Option<String> = match column.true_ord.clone() {
-                    Some(ord) => row.get::<&str, Option<String>>(&*ord)?,
-                    None => None
-                };
-                let display_value: Option<String> = row.get(&*column.display_ord.clone())?;
-                let mut failed_validations: Vec<error::FailedValidation> = Vec::<error::FailedValidation>::new();
+            // Conclude the row's iteration
+            return Ok(());
+        }
+    )?;
+    let query_duration = query_start.elapsed();
+    return Ok(TableDataProfile {
+        table_oid,
+        query_build_micros: query_build_duration.as_micros(),
+        query_execution_micros: query_duration.saturating_sub(cell_send_duration).as_micros(),
+        cell_send_micros: cell_send_duration.as_micros()
+    });
+}
 
-                // Nullability validation
-                if !column.is_nullable && display_value == None {
-                    failed_validations.push(error::FailedValidation {
-                        description: format!("{} cannot be NULL!", column.column_name)
-                    });
-                }
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", untagged)]
+/// A packet of table data grouped by the value of a column.
+pub enum GroupedCell {
+    /// Starts a group, which is followed by the cells of each of its rows.
+    GroupStart {
+        group_value: Option<String>,
+        row_count: i64
+    },
+    Cell(Cell)
+}
 
-                // Uniqueness validation
-                if column.invalid_nonunique_oid.contains(&row_oid) {
-                    failed_validations.push(error::FailedValidation {
-                        description: format!("{} value is not unique!", column.column_name)
-                    });
-                }
+/// Sends all cells for the table through a channel, grouped by the displayed value of a column.
+/// Each group is preceded by a header with the value and the number of rows in the group, and groups are sorted by their value, with the group of empty values last.
+/// Rows within each group keep their order in the table.
+pub fn send_grouped_data(table_oid: i64, group_column_oid: i64, cell_channel: Channel<GroupedCell>) -> Result<(), error::Error> {
+    let mut conn = db::open()?;
+    let trans = begin_data_query(&mut conn)?;
 
-                // Primary key validation
-                if column.is_primary_key && invalid_key {
-                    failed_validations.push(error::FailedValidation {
-                        description: format!("Primary key for this row is not unique!")
-                    });
-                }
+    // Build the query, then order its results by the value of the group column
+    let (table_select_cmd, columns) = construct_data_query(&trans, table_oid, false, false)?;
+    let group_ord: String = columns.iter()
+        .find(|c| c.column_oid == group_column_oid)
+        .map(|c| c.display_ord.clone())
+        .ok_or(error::Error::AdhocError("The rows of a table can only be grouped by one of its columns."))?;
+    let grouped_select_cmd = format!("
+    SELECT q.*, COUNT(*) OVER (PARTITION BY q.{group_ord}) AS GROUP_ROW_COUNT 
+    FROM ({table_select_cmd}) q 
+    ORDER BY q.{group_ord} IS NULL, q.{group_ord}, q.ROW_INDEX;");
+    log::debug!("Querying grouped data for TABLE{table_oid}: {grouped_select_cmd}");
+    let commented_cells = get_commented_cells(&trans, &columns, None)?;
+    let format_rules = get_format_rules_in(&trans, table_oid, &columns)?;
+    let now: String = get_validation_time_in(&trans)?;
 
-                // Send the cell value to frontend
-                cell_channel.send(Cell::ColumnValue {
-                    table_oid: column.table_oid,
-                    row_oid: row_oid,
-                    column_oid: column.column_oid, 
-                    column_type: column.column_type.clone(), 
-                    true_value: true_value,
-                    display_value: display_value,
-                    failed_validations: failed_validations
+    // Iterate over the results, sending a header whenever a new group starts
+    let mut current_group_value: Option<Option<String>> = None;
+    db::query_iterate(&trans, 
+        &grouped_select_cmd, 
+        params![-1, 0],
+        &mut |row| {
+            let group_value: Option<String> = row.get(&*group_ord)?;
+            if current_group_value.as_ref() != Some(&group_value) {
+                cell_channel.send(GroupedCell::GroupStart {
+                    group_value: group_value.clone(),
+                    row_count: row.get("GROUP_ROW_COUNT")?
                 })?;
+                current_group_value = Some(group_value);
             }
 
-            // Conclude the row's iteration
+            // Send the row, followed by the value of each of its columns
+            cell_channel.send(GroupedCell::Cell(Cell::RowStart {
+                row_oid: row.get("t_OID")?,
+                row_index: row.get("ROW_INDEX")?,
+                row_style: format_rules.get_row_style(row)?
+            }))?;
+            for cell in get_column_cells(row, &columns, &commented_cells, &now)? {
+                cell_channel.send(GroupedCell::Cell(cell))?;
+            }
             return Ok(());
         }
     )?;
     return Ok(());
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A single field of a row edit form.
+pub struct RowEditField {
+    table_oid: i64,
+    column_oid: i64,
+    column_name: String,
+    column_type: data_type::MetadataColumnType,
+    column_ordering: i64,
+    is_nullable: bool,
+    is_primary_key: bool,
+    true_value: Option<String>,
+    options: Option<Vec<table_column::DropdownValue>>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// Everything needed to display a form for editing a single row.
+pub struct RowEditForm {
+    table_oid: i64,
+    row_oid: i64,
+    fields: Vec<RowEditField>
+}
+
+/// Gets the type of a column that belongs to a table, as part of an ongoing action.
+fn get_table_column_type_in(trans: &Connection, table_oid: i64, column_oid: i64) -> Result<data_type::MetadataColumnType, error::Error> {
+    let column_type = trans.query_one(
+        "SELECT
+            c.TYPE_OID,
+            t.MODE
+        FROM METADATA_TABLE_COLUMN c
+        INNER JOIN METADATA_TYPE t ON t.OID = c.TYPE_OID
+        WHERE c.OID = ?1 AND c.TABLE_OID = ?2 AND c.TRASH = 0;",
+        params![column_oid, table_oid],
+        |row| Ok(data_type::MetadataColumnType::from_database(row.get("TYPE_OID")?, row.get("MODE")?))
+    ).optional()?;
+    return match column_type {
+        Some(t) => Ok(t),
+        None => Err(error::Error::AdhocError("The column does not belong to the table."))
+    };
+}
+
+/// Gets the display values of a single column, in the order the rows are displayed.
+pub fn get_column_values(table_oid: i64, column_oid: i64, limit: i64, offset: i64) -> Result<Vec<Option<String>>, error::Error> {
+    let conn = db::open()?;
+    let column_type = get_table_column_type_in(&conn, table_oid, column_oid)?;
+    let display_expr = construct_display_value_expr(column_oid, &column_type, "t");
+    let select_cmd = format!("SELECT {display_expr} FROM TABLE{table_oid} t WHERE t.TRASH = 0 ORDER BY t.ORDER_KEY, t.OID LIMIT ?1 OFFSET ?2;");
+    let mut values: Vec<Option<String>> = Vec::new();
+    db::query_iterate(&conn, &select_cmd, params![limit, offset], &mut |row| {
+        values.push(row.get(0)?);
+        return Ok(());
+    })?;
+    return Ok(values);
+}
+
+/// Gets the values of a single integer or number column as numbers, in the order the rows are displayed.
+pub fn get_column_numeric_values(table_oid: i64, column_oid: i64, limit: i64, offset: i64) -> Result<Vec<Option<f64>>, error::Error> {
+    let conn = db::open()?;
+    match get_table_column_type_in(&conn, table_oid, column_oid)? {
+        data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer)
+        | data_type::MetadataColumnType::Primitive(data_type::Primitive::Number) => {},
+        _ => {
+            return Err(error::Error::AdhocError("Only integer and number columns have numeric values."));
+        }
+    }
+    let select_cmd = format!("SELECT CAST(t.COLUMN{column_oid} AS REAL) FROM TABLE{table_oid} t WHERE t.TRASH = 0 ORDER BY t.ORDER_KEY, t.OID LIMIT ?1 OFFSET ?2;");
+    let mut values: Vec<Option<f64>> = Vec::new();
+    db::query_iterate(&conn, &select_cmd, params![limit, offset], &mut |row| {
+        values.push(row.get(0)?);
+        return Ok(());
+    })?;
+    return Ok(values);
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+/// The direction to move from a row to its neighbor.
+pub enum Direction {
+    Next,
+    Previous
+}
+
+/// Gets the OID of the row displayed immediately after or before a row, skipping rows in the trash.
+/// If a parent row OID is provided, only rows of the child table belonging to that parent row are considered.
+pub fn adjacent_row(table_oid: i64, row_oid: i64, direction: Direction, parent_row_oid: Option<i64>) -> Result<Option<i64>, error::Error> {
+    let conn = db::open()?;
+    let (comparison, order) = match direction {
+        Direction::Next => (">", "ASC"),
+        Direction::Previous => ("<", "DESC")
+    };
+    let parent_clause = if parent_row_oid.is_some() { "AND n.PARENT_OID = ?2" } else { "" };
+    let select_cmd = format!("SELECT n.OID 
+        FROM TABLE{table_oid} n
+        INNER JOIN TABLE{table_oid} c ON c.OID = ?1
+        WHERE n.TRASH = 0 AND (n.ORDER_KEY, n.OID) {comparison} (c.ORDER_KEY, c.OID) {parent_clause}
+        ORDER BY n.ORDER_KEY {order}, n.OID {order}
+        LIMIT 1;");
+    let adjacent_row_oid = match parent_row_oid {
+        Some(parent_row_oid) => conn.query_one(&select_cmd, params![row_oid, parent_row_oid], |row| row.get(0)),
+        None => conn.query_one(&select_cmd, params![row_oid], |row| row.get(0))
+    }.optional()?;
+    return Ok(adjacent_row_oid);
+}
+
+/// Gets the metadata, current values, and dropdown options for each editable column of a row.
+pub fn get_row_edit_form(table_oid: i64, row_oid: i64) -> Result<RowEditForm, error::Error> {
+    let mut conn = db::open()?;
+    let trans = begin_data_query(&mut conn)?;
+    let (table_select_cmd, columns) = construct_data_query(&trans, table_oid, true, false)?;
+
+    // Query the current values of the row
+    let true_values: Vec<Option<String>> = match trans.query_row_and_then(
+        &table_select_cmd, 
+        params![row_oid], 
+        |row| -> Result<Vec<Option<String>>, error::Error> {
+            let mut true_values: Vec<Option<String>> = Vec::new();
+            for column in columns.iter() {
+                true_values.push(match &column.true_ord {
+                    Some(ord) => row.get::<&str, Option<String>>(&*ord)?,
+                    None => None
+                });
+            }
+            return Ok(true_values);
+        }
+    ) {
+        Ok(v) => v,
+        Err(error::Error::RusqliteError(RusqliteError::QueryReturnedNoRows)) => {
+            return Err(error::Error::AdhocError("The row being edited does not exist."));
+        },
+        Err(e) => {
+            return Err(e);
+        }
+    };
+
+    // Build a field for each column that can be edited in the form
+    let mut fields: Vec<RowEditField> = Vec::new();
+    for (column, true_value) in columns.into_iter().zip(true_values.into_iter()) {
+        if column.true_ord.is_none() {
+            continue;
+        }
+        let options = table_column::get_options_in(&trans, &column.column_type)?;
+        fields.push(RowEditField {
+            table_oid: column.table_oid,
+            column_oid: column.column_oid,
+            column_name: column.column_name,
+            column_type: column.column_type,
+            column_ordering: column.column_ordering,
+            is_nullable: column.is_nullable,
+            is_primary_key: column.is_primary_key,
+            true_value,
+            options
+        });
+    }
+    return Ok(RowEditForm {
+        table_oid,
+        row_oid,
+        fields
+    });
+}
+
 /// Sends all cells for a row in the table through a channel.
 pub fn send_table_row(table_oid: i64, row_oid: i64, cell_channel: Channel<RowCell>) -> Result<(), error::Error> {
     let mut conn = db::open()?;
-    let trans = conn.transaction()?;
+    let trans = begin_data_query(&mut conn)?;
     let (table_select_cmd, columns) = construct_data_query(&trans, table_oid, true, false)?;
+    let commented_cells = get_commented_cells(&trans, &columns, Some(row_oid))?;
+    let now: String = get_validation_time_in(&trans)?;
 
     // Query for the specified row
     match trans.query_row_and_then(
@@ -666,8 +2909,7 @@ pub fn send_table_row(table_oid: i64, row_oid: i64, cell_channel: Channel<RowCel
         |row| -> Result<(), error::Error> {
             // Start by sending message that confirms the row exists
             cell_channel.send(RowCell::RowExists { row_exists: true, table_oid })?;
-
-            let invalid_key = false;
+            let table_row_oid: i64 = row.get("t_OID")?;
 
             // Iterate over the columns, sending over the displayed value of that cell in the current row for each
             for column in columns.iter() {
@@ -682,23 +2924,33 @@ pub fn send_table_row(table_oid: i64, row_oid: i64, cell_channel: Channel<RowCel
 
                 // Nullability validation
                 if !column.is_nullable && display_value == None {
-                    failed_validations.push(error::FailedValidation {
-                        description: format!("{} cannot be NULL!", column.column_name)
-                    });
+                    failed_validations.push(error::FailedValidation::new("validation.notNull", error::Severity::Error, &[("columnName", &column.column_name)]));
                 }
 
                 // Uniqueness validation
                 if column.invalid_nonunique_oid.contains(&row_oid) {
-                    failed_validations.push(error::FailedValidation {
-                        description: format!("{} value is not unique!", column.column_name)
-                    });
+                    failed_validations.push(error::FailedValidation::new("validation.notUnique", error::Severity::Error, &[("columnName", &column.column_name)]));
                 }
 
                 // Primary key validation
-                if column.is_primary_key && invalid_key {
-                    failed_validations.push(error::FailedValidation {
-                        description: format!("Primary key for this row is not unique!")
-                    });
+                if column.is_primary_key && column.invalid_key_oid.contains(&table_row_oid) {
+                    failed_validations.push(error::FailedValidation::new("validation.primaryKeyNotUnique", error::Severity::Error, &[]));
+                }
+
+                // Numeric type validation
+                if column.invalid_numeric_oid.contains(&row_oid) {
+                    failed_validations.push(error::FailedValidation::new("validation.notANumber", error::Severity::Error, &[("columnName", &column.column_name)]));
+                }
+
+                // Date bound validation
+                match (&column.date_bound, &true_value) {
+                    (Some(date_bound), Some(value)) if date_bound.is_violated_by(value, &now) => {
+                        failed_validations.push(error::FailedValidation::new("validation.dateOutOfBounds", date_bound.get_severity(), &[("columnName", &column.column_name)]));
+                    },
+                    _ => {}
+                }
+                for failed_validation in failed_validations.iter() {
+                    log::debug!("Row {row_oid} of TABLE{} failed validation of COLUMN{}: {failed_validation}", column.table_oid, column.column_oid);
                 }
 
                 // Send the cell value to frontend
@@ -711,7 +2963,8 @@ pub fn send_table_row(table_oid: i64, row_oid: i64, cell_channel: Channel<RowCel
                     column_ordering: column.column_ordering,
                     true_value: true_value,
                     display_value: display_value,
-                    failed_validations: failed_validations
+                    failed_validations: failed_validations,
+                    has_comment: commented_cells.contains(&(column.column_oid, row_oid))
                 })?;
             }
 
@@ -737,4 +2990,650 @@ pub fn send_table_row(table_oid: i64, row_oid: i64, cell_channel: Channel<RowCel
             return Ok(());
         }
     }
-}
\ No newline at end of file
+}
+
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A note attached to a cell.
+pub struct CellComment {
+    comment: String,
+    created_at: String
+}
+
+/// Gets the comment attached to a cell, if any.
+pub fn get_cell_comment(table_oid: i64, row_oid: i64, column_oid: i64) -> Result<Option<CellComment>, error::Error> {
+    let conn = db::open()?;
+    return Ok(conn.query_one(
+        "SELECT COMMENT, STRFTIME('%FT%TZ', CREATED_AT, 'julianday') AS CREATED_AT FROM METADATA_CELL_COMMENT WHERE TABLE_OID = ?1 AND ROW_OID = ?2 AND COLUMN_OID = ?3;", 
+        params![table_oid, row_oid, column_oid], 
+        |row| {
+            Ok(CellComment {
+                comment: row.get("COMMENT")?,
+                created_at: row.get("CREATED_AT")?
+            })
+        }
+    ).optional()?);
+}
+
+/// Attaches a comment to a cell, replacing any existing comment, or removes the comment from the cell if None is provided.
+/// Returns the previous comment on the cell.
+pub fn set_cell_comment(table_oid: i64, row_oid: i64, column_oid: i64, comment: Option<String>) -> Result<Option<String>, error::Error> {
+    let action = db::begin_db_action()?;
+    return action.finish(set_cell_comment_in(&action, table_oid, row_oid, column_oid, comment));
+}
+
+/// Attaches a comment to a cell or removes it, as part of an ongoing action.
+fn set_cell_comment_in(trans: &Connection, table_oid: i64, row_oid: i64, column_oid: i64, comment: Option<String>) -> Result<Option<String>, error::Error> {
+    // Retrieve the previous comment
+    let prior_comment: Option<String> = trans.query_one(
+        "SELECT COMMENT FROM METADATA_CELL_COMMENT WHERE TABLE_OID = ?1 AND ROW_OID = ?2 AND COLUMN_OID = ?3;", 
+        params![table_oid, row_oid, column_oid], 
+        |row| row.get(0)
+    ).optional()?;
+
+    match comment {
+        Some(comment) if !comment.trim().is_empty() => {
+            // Insert or replace the comment
+            trans.execute(
+                "INSERT INTO METADATA_CELL_COMMENT (TABLE_OID, ROW_OID, COLUMN_OID, COMMENT) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (TABLE_OID, ROW_OID, COLUMN_OID) DO UPDATE SET COMMENT = excluded.COMMENT;", 
+                params![table_oid, row_oid, column_oid, comment]
+            )?;
+        },
+        _ => {
+            // Remove the comment
+            trans.execute(
+                "DELETE FROM METADATA_CELL_COMMENT WHERE TABLE_OID = ?1 AND ROW_OID = ?2 AND COLUMN_OID = ?3;", 
+                params![table_oid, row_oid, column_oid]
+            )?;
+        }
+    }
+    return Ok(prior_comment);
+}
+
+
+/// Attaches a tag to a row.
+/// Returns whether the tag was added, which is false if the row already had the tag.
+pub fn add_row_tag(table_oid: i64, row_oid: i64, tag: &str) -> Result<bool, error::Error> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(error::Error::AdhocError("A tag cannot be empty."));
+    }
+    let action = db::begin_db_action()?;
+    let inserted_count = action.execute(
+        "INSERT OR IGNORE INTO METADATA_ROW_TAG (TABLE_OID, ROW_OID, TAG) VALUES (?1, ?2, ?3);", 
+        params![table_oid, row_oid, tag]
+    )?;
+    return Ok(inserted_count > 0);
+}
+
+/// Removes a tag from a row.
+/// Returns whether the tag was removed, which is false if the row did not have the tag.
+pub fn remove_row_tag(table_oid: i64, row_oid: i64, tag: &str) -> Result<bool, error::Error> {
+    let action = db::begin_db_action()?;
+    let deleted_count = action.execute(
+        "DELETE FROM METADATA_ROW_TAG WHERE TABLE_OID = ?1 AND ROW_OID = ?2 AND TAG = ?3;", 
+        params![table_oid, row_oid, tag.trim()]
+    )?;
+    return Ok(deleted_count > 0);
+}
+
+/// Gets the OIDs of the rows in a table that have a tag and are not in the trash.
+pub fn get_rows_by_tag(table_oid: i64, tag: &str) -> Result<Vec<i64>, error::Error> {
+    let conn = db::open()?;
+    let mut row_oids: Vec<i64> = Vec::new();
+    let select_cmd = format!("
+    SELECT g.ROW_OID 
+    FROM METADATA_ROW_TAG g 
+    INNER JOIN TABLE{table_oid} t ON t.OID = g.ROW_OID 
+    WHERE g.TABLE_OID = ?1 AND g.TAG = ?2 AND t.TRASH = 0 
+    ORDER BY g.ROW_OID;");
+    db::query_iterate(&conn, 
+        &select_cmd, 
+        params![table_oid, tag.trim()], 
+        &mut |row| {
+            row_oids.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+    return Ok(row_oids);
+}
+
+/// Gets every tag used on the rows of a table, in alphabetical order.
+pub fn get_table_tags(table_oid: i64) -> Result<Vec<String>, error::Error> {
+    let conn = db::open()?;
+    let mut tags: Vec<String> = Vec::new();
+    db::query_iterate(&conn, 
+        "SELECT DISTINCT TAG FROM METADATA_ROW_TAG WHERE TABLE_OID = ?1 ORDER BY TAG;", 
+        params![table_oid], 
+        &mut |row| {
+            tags.push(row.get(0)?);
+            return Ok(());
+        }
+    )?;
+    return Ok(tags);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{obj_type, table_column, test_util};
+
+    #[test]
+    fn fill_down_restarts_for_each_parent_row() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+
+        // Create a table with a child table holding a text column
+        let parent_oid = table::create(String::from("Orders"), &vec![]).unwrap();
+        let child_column_oid = table_column::create(parent_oid, "Items", data_type::MetadataColumnType::ChildTable(0), None, "", true, false, false, None).unwrap();
+        let conn = db::open().unwrap();
+        let child_oid: i64 = conn.query_one("SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", params![child_column_oid], |row| row.get(0)).unwrap();
+        let note_column_oid = table_column::create(child_oid, "Note", text_type, None, "", true, false, false, None).unwrap();
+
+        // Interleave the rows of two parent rows, with the second parent row's first row empty
+        conn.execute_batch(&format!("
+        INSERT INTO TABLE{parent_oid} (OID) VALUES (1), (2);
+        INSERT INTO TABLE{child_oid} (OID, PARENT_OID, ORDER_KEY, COLUMN{note_column_oid}) VALUES 
+            (1, 1, 1, 'Gift'), 
+            (2, 2, 2, NULL), 
+            (3, 1, 3, NULL), 
+            (4, 2, 4, 'Fragile'), 
+            (5, 2, 5, NULL);")).unwrap();
+
+        // Each empty cell is filled from the rows of its own parent row
+        let prior_values = fill_down_tracked(child_oid, note_column_oid).unwrap();
+        assert_eq!(prior_values.iter().map(|p| p.row_oid).collect::<Vec<i64>>(), vec![3, 5]);
+        let mut notes: Vec<Option<String>> = Vec::new();
+        db::query_iterate(&conn, &format!("SELECT COLUMN{note_column_oid} FROM TABLE{child_oid} ORDER BY OID;"), [], &mut |row| {
+            notes.push(row.get(0)?);
+            return Ok(());
+        }).unwrap();
+        assert_eq!(notes, vec![Some(String::from("Gift")), None, Some(String::from("Gift")), Some(String::from("Fragile")), Some(String::from("Fragile"))]);
+    }
+
+    /// Gets the OIDs of a table's rows in the order they are displayed.
+    fn get_row_order(table_oid: i64) -> Vec<i64> {
+        let mut row_oids: Vec<i64> = Vec::new();
+        db::query_iterate(&db::open().unwrap(), &format!("SELECT OID FROM TABLE{table_oid} ORDER BY ORDER_KEY, OID;"), [], &mut |row| {
+            row_oids.push(row.get(0)?);
+            return Ok(());
+        }).unwrap();
+        return row_oids;
+    }
+
+    #[test]
+    fn insert_places_row_without_renumbering() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Rows"), &vec![]).unwrap();
+        for _ in 0..3 {
+            push(table_oid).unwrap();
+        }
+
+        // Inserting before an existing row keeps the OIDs of every other row
+        let front_oid = insert(table_oid, 1).unwrap();
+        let middle_oid = insert(table_oid, 3).unwrap();
+        assert_eq!(get_row_order(table_oid), vec![front_oid, 1, 2, middle_oid, 3]);
+
+        // Inserting at an unused OID takes that OID, and places the row before the next row by OID
+        let unused_oid = insert(table_oid, 100).unwrap();
+        assert_eq!(unused_oid, 100);
+        assert_eq!(get_row_order(table_oid).last(), Some(&100));
+    }
+
+    /// Inserts 1,000 rows at the front of a 10,000-row table, each before the row inserted last.
+    /// Run with `cargo test insert_at_front_benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn insert_at_front_benchmark() {
+        const ROW_COUNT: i64 = 10000;
+        const INSERT_COUNT: usize = 1000;
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Rows"), &vec![]).unwrap();
+        db::open().unwrap().execute(&format!("
+            WITH RECURSIVE n(OID) AS (SELECT 1 UNION ALL SELECT OID + 1 FROM n WHERE OID < ?1)
+            INSERT INTO TABLE{table_oid} (OID, ORDER_KEY) SELECT OID, OID FROM n;"), params![ROW_COUNT]).unwrap();
+
+        // Keep inserting before the first row, which never touches the ORDER_KEY of another row
+        let start = std::time::Instant::now();
+        let mut front_oid: i64 = 1;
+        let mut inserted_oids: Vec<i64> = Vec::new();
+        for _ in 0..INSERT_COUNT {
+            front_oid = insert(table_oid, front_oid).unwrap();
+            inserted_oids.push(front_oid);
+        }
+        println!("{INSERT_COUNT} inserts at the front of {ROW_COUNT} rows: {:?}", start.elapsed());
+
+        // The inserted rows come first, newest first, followed by the original rows in their original order
+        let row_order = get_row_order(table_oid);
+        inserted_oids.reverse();
+        assert_eq!(row_order[..INSERT_COUNT], inserted_oids[..]);
+        assert_eq!(row_order[INSERT_COUNT..], (1..=ROW_COUNT).collect::<Vec<i64>>()[..]);
+    }
+
+    #[test]
+    fn compact_oids_keeps_references() {
+        let _db = test_util::open_test_db();
+
+        // Create a table with a multi-select column and a child table, and another table referencing it
+        let target_oid = table::create(String::from("Targets"), &vec![]).unwrap();
+        let tags_column_oid = table_column::create(target_oid, "Tags", data_type::MetadataColumnType::MultiSelectDropdown(0), None, "", true, false, false, None).unwrap();
+        let items_column_oid = table_column::create(target_oid, "Items", data_type::MetadataColumnType::ChildTable(0), None, "", true, false, false, None).unwrap();
+        let source_oid = table::create(String::from("Sources"), &vec![]).unwrap();
+        let reference_column_oid = table_column::create(source_oid, "Target", data_type::MetadataColumnType::Reference(target_oid), None, "", true, false, false, None).unwrap();
+        let conn = db::open().unwrap();
+        let get_type_oid = |column_oid: i64| conn.query_one("SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", params![column_oid], |row| row.get::<_, i64>(0)).unwrap();
+        let (tags_oid, items_oid) = (get_type_oid(tags_column_oid), get_type_oid(items_column_oid));
+
+        // Leave gaps between the OIDs of the rows, then link to the rows after the first gap
+        conn.execute_batch(&format!("
+        INSERT INTO TABLE{target_oid} (OID, ORDER_KEY) VALUES (3, 1), (7, 2), (10, 3);
+        INSERT INTO TABLE{tags_oid} (OID, VALUE) VALUES (1, 'Urgent');
+        INSERT INTO TABLE{tags_oid}_MULTISELECT (ROW_OID, VALUE_OID) VALUES (7, 1), (10, 1);
+        INSERT INTO TABLE{items_oid} (OID, PARENT_OID) VALUES (1, 10);
+        INSERT INTO TABLE{source_oid} (OID, COLUMN{reference_column_oid}) VALUES (1, 7), (2, 10);")).unwrap();
+
+        // Each link follows its row to the row's new OID
+        compact_oids(target_oid).unwrap();
+        let get_oids = |select_cmd: String| {
+            let mut oids: Vec<i64> = Vec::new();
+            db::query_iterate(&conn, &select_cmd, [], &mut |row| {
+                oids.push(row.get(0)?);
+                return Ok(());
+            }).unwrap();
+            return oids;
+        };
+        assert_eq!(get_oids(format!("SELECT OID FROM TABLE{target_oid} ORDER BY OID;")), vec![1, 2, 3]);
+        assert_eq!(get_oids(format!("SELECT COLUMN{reference_column_oid} FROM TABLE{source_oid} ORDER BY OID;")), vec![2, 3]);
+        assert_eq!(get_oids(format!("SELECT ROW_OID FROM TABLE{tags_oid}_MULTISELECT ORDER BY ROW_OID;")), vec![2, 3]);
+        assert_eq!(get_oids(format!("SELECT PARENT_OID FROM TABLE{items_oid};")), vec![3]);
+    }
+
+    /// Gets the display value of a column in each row of a page of table data, as it is sent to the frontend.
+    fn get_page_display_values(table_oid: i64, parent_row_oid: Option<i64>, page_num: i64, page_size: i64, column_oid: i64) -> Vec<(i64, Option<String>)> {
+        let (cell_channel, packets) = test_util::collect_channel::<Cell>();
+        send_table_data(table_oid, parent_row_oid, page_num, page_size, cell_channel).unwrap();
+        return packets.lock().unwrap().iter()
+            .filter(|packet| packet["columnOid"].as_i64() == Some(column_oid))
+            .map(|packet| (packet["rowOid"].as_i64().unwrap(), packet["displayValue"].as_str().map(String::from)))
+            .collect();
+    }
+
+    #[test]
+    fn missing_surrogate_view_is_rebuilt() {
+        let _db = test_util::open_test_db();
+
+        // Create a table with a text primary key, and a table referencing one of its rows
+        let target_oid = table::create(String::from("Targets"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(target_oid, "Name", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, true, None).unwrap();
+        let source_oid = table::create(String::from("Sources"), &vec![]).unwrap();
+        let reference_column_oid = table_column::create(source_oid, "Target", data_type::MetadataColumnType::Reference(target_oid), None, "", true, false, false, None).unwrap();
+        db::open().unwrap().execute_batch(&format!("
+        INSERT INTO TABLE{target_oid} (OID, COLUMN{name_column_oid}) VALUES (1, 'Alpha');
+        INSERT INTO TABLE{source_oid} (OID, COLUMN{reference_column_oid}) VALUES (1, 1);
+        DROP VIEW TABLE{target_oid}_SURROGATE;")).unwrap();
+
+        // Loading the referencing table rebuilds the view instead of failing
+        assert_eq!(get_page_display_values(source_oid, None, 1, 10, reference_column_oid), vec![(1, Some(String::from("Alpha")))]);
+        let view_exists: bool = db::open().unwrap()
+            .query_one("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'view' AND name = ?1);", params![format!("TABLE{target_oid}_SURROGATE")], |row| row.get(0))
+            .unwrap();
+        assert!(view_exists);
+    }
+
+    #[test]
+    fn new_rows_take_default_values() {
+        let _db = test_util::open_test_db();
+
+        // Create a table with a text default, an integer default, a date default, and a column without a default
+        let table_oid = table::create(String::from("Tasks"), &vec![]).unwrap();
+        let status_column_oid = table_column::create(table_oid, "Status", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, false, Some(String::from("Open"))).unwrap();
+        let priority_column_oid = table_column::create(table_oid, "Priority", data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer), None, "", true, false, false, Some(String::from("3"))).unwrap();
+        let due_column_oid = table_column::create(table_oid, "Due", data_type::MetadataColumnType::Primitive(data_type::Primitive::Date), None, "", true, false, false, Some(String::from("2024-01-02"))).unwrap();
+        let note_column_oid = table_column::create(table_oid, "Note", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, false, None).unwrap();
+        let select_cmd = format!("SELECT COLUMN{status_column_oid}, COLUMN{priority_column_oid}, DATE(COLUMN{due_column_oid}, 'julianday'), COLUMN{note_column_oid} FROM TABLE{table_oid} WHERE OID = ?1;");
+        let get_row = |row_oid: i64| db::open().unwrap()
+            .query_one(&select_cmd, params![row_oid], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, Option<String>>(3)?)))
+            .unwrap();
+
+        // Both pushed and inserted rows start out with the defaults, and with nothing in the column without one
+        let expected_row = (String::from("Open"), 3, String::from("2024-01-02"), None);
+        let pushed_row_oid = push(table_oid).unwrap();
+        assert_eq!(get_row(pushed_row_oid), expected_row);
+        let inserted_row_oid = insert(table_oid, pushed_row_oid).unwrap();
+        assert_eq!(get_row(inserted_row_oid), expected_row);
+    }
+
+    #[test]
+    fn number_columns_display_decimal_places() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Measurements"), &vec![]).unwrap();
+        let column_oid = table_column::create(table_oid, "Value", data_type::MetadataColumnType::Primitive(data_type::Primitive::Number), None, "", true, false, false, None).unwrap();
+        for value in ["0.1", "1.50", "123456789012.5"] {
+            let row_oid = push(table_oid).unwrap();
+            try_update_primitive_value(table_oid, row_oid, column_oid, Some(String::from(value))).unwrap();
+        }
+        let get_display_values = || get_page_display_values(table_oid, None, 1, 10, column_oid).into_iter()
+            .map(|(_, display_value)| display_value.unwrap())
+            .collect::<Vec<String>>();
+
+        // Without a number of decimal places, each value is shown as briefly as it can be without changing it
+        assert_eq!(get_display_values(), vec!["0.1", "1.5", "123456789012.5"]);
+
+        // With a number of decimal places, each value is rounded or padded to it, while the stored value is left as it was
+        table_column::set_decimal_places(column_oid, Some(2)).unwrap();
+        assert_eq!(get_display_values(), vec!["0.10", "1.50", "123456789012.50"]);
+        let stored_value: f64 = db::open().unwrap().query_one(&format!("SELECT COLUMN{column_oid} FROM TABLE{table_oid} WHERE OID = 1;"), [], |row| row.get(0)).unwrap();
+        assert_eq!(stored_value, 0.1);
+
+        // Only number columns can be given a number of decimal places
+        let text_column_oid = table_column::create(table_oid, "Unit", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, false, None).unwrap();
+        assert!(table_column::set_decimal_places(text_column_oid, Some(2)).is_err());
+    }
+
+    #[test]
+    fn table_data_is_paged_with_and_without_parent_row() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+
+        // Create a table of five rows, and a child table with three rows under its first row and one under its second
+        let parent_oid = table::create(String::from("Orders"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(parent_oid, "Name", text_type.clone(), None, "", true, false, false, None).unwrap();
+        let child_column_oid = table_column::create(parent_oid, "Items", data_type::MetadataColumnType::ChildTable(0), None, "", true, false, false, None).unwrap();
+        let conn = db::open().unwrap();
+        let child_oid: i64 = conn.query_one("SELECT TYPE_OID FROM METADATA_TABLE_COLUMN WHERE OID = ?1;", params![child_column_oid], |row| row.get(0)).unwrap();
+        let note_column_oid = table_column::create(child_oid, "Note", text_type, None, "", true, false, false, None).unwrap();
+        conn.execute_batch(&format!("
+        INSERT INTO TABLE{parent_oid} (OID, ORDER_KEY, COLUMN{name_column_oid}) VALUES (1, 1, 'A'), (2, 2, 'B'), (3, 3, 'C'), (4, 4, 'D'), (5, 5, 'E');
+        INSERT INTO TABLE{child_oid} (OID, PARENT_OID, ORDER_KEY, COLUMN{note_column_oid}) VALUES 
+            (1, 1, 1, 'A1'), 
+            (2, 2, 2, 'B1'), 
+            (3, 1, 3, 'A2'), 
+            (4, 1, 4, 'A3');")).unwrap();
+        let get_page = |table_oid: i64, parent_row_oid: Option<i64>, page_num: i64, column_oid: i64| get_page_display_values(table_oid, parent_row_oid, page_num, 2, column_oid).into_iter()
+            .map(|(_, display_value)| display_value.unwrap())
+            .collect::<Vec<String>>();
+
+        // Without a parent row, every row of the table is paged through
+        assert_eq!(get_page(parent_oid, None, 1, name_column_oid), vec!["A", "B"]);
+        assert_eq!(get_page(parent_oid, None, 3, name_column_oid), vec!["E"]);
+
+        // With a parent row, only the rows under it are paged through
+        assert_eq!(get_page(child_oid, Some(1), 1, note_column_oid), vec!["A1", "A2"]);
+        assert_eq!(get_page(child_oid, Some(1), 2, note_column_oid), vec!["A3"]);
+        assert_eq!(get_page(child_oid, Some(2), 1, note_column_oid), vec!["B1"]);
+    }
+
+    #[test]
+    fn primary_key_collisions_span_supertype_columns() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+
+        // Create an object type whose primary key is split between it and its supertype
+        let person_oid = obj_type::create(String::from("Person"), &vec![]).unwrap();
+        let family_name_column_oid = table_column::create(person_oid, "Family Name", text_type.clone(), None, "", true, false, true, None).unwrap();
+        let employee_oid = obj_type::create(String::from("Employee"), &vec![person_oid]).unwrap();
+        let given_name_column_oid = table_column::create(employee_oid, "Given Name", text_type, None, "", true, false, true, None).unwrap();
+
+        // Employees with the same given name but different family names can both be added
+        let conn = db::open().unwrap();
+        conn.execute_batch(&format!("
+        INSERT INTO TABLE{person_oid} (OID, COLUMN{family_name_column_oid}) VALUES (1, 'Smith'), (2, 'Jones');
+        INSERT INTO TABLE{employee_oid} (OID, MASTER{person_oid}_OID, COLUMN{given_name_column_oid}) VALUES (1, 1, 'John'), (2, 2, 'John');")).unwrap();
+
+        // Simulate rows added before the key was enforced, where only the first and third employees share both parts of the key
+        table::drop_primary_key_index(&conn, employee_oid).unwrap();
+        conn.execute(&format!("INSERT INTO TABLE{employee_oid} (OID, MASTER{person_oid}_OID, COLUMN{given_name_column_oid}) VALUES (3, 1, 'John'), (4, 1, 'Jane');"), []).unwrap();
+
+        // Each column of the key is flagged in the rows that collide, and nowhere else
+        let (cell_channel, packets) = test_util::collect_channel::<Cell>();
+        send_table_data(employee_oid, None, 1, 10, cell_channel).unwrap();
+        let mut flagged_cells: Vec<(i64, i64)> = Vec::new();
+        let mut row_oid: i64 = 0;
+        for packet in packets.lock().unwrap().iter() {
+            // Cells of supertype columns carry the OID of the supertype row, so keep track of the row each cell belongs to
+            if packet.get("rowIndex").is_some() {
+                row_oid = packet["rowOid"].as_i64().unwrap();
+            } else if packet["failedValidations"].as_array().is_some_and(|v| v.iter().any(|f| f["key"] == "validation.primaryKeyNotUnique")) {
+                flagged_cells.push((row_oid, packet["columnOid"].as_i64().unwrap()));
+            }
+        }
+        flagged_cells.sort();
+        let mut expected_cells = vec![(1, family_name_column_oid), (1, given_name_column_oid), (3, family_name_column_oid), (3, given_name_column_oid)];
+        expected_cells.sort();
+        assert_eq!(flagged_cells, expected_cells);
+    }
+
+    #[test]
+    fn duplicate_values_of_unique_columns_are_rejected() {
+        let _db = test_util::open_test_db();
+        let text_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Text);
+        let table_oid = table::create(String::from("Accounts"), &vec![]).unwrap();
+        let email_column_oid = table_column::create(table_oid, "Email", text_type.clone(), None, "", true, true, false, None).unwrap();
+        let login_column_oid = table_column::create(table_oid, "Login", text_type, None, "", true, false, true, None).unwrap();
+        let row_oids: Vec<i64> = (0..2).map(|_| push(table_oid).unwrap()).collect();
+        for (row_oid, value) in row_oids.iter().zip(["a", "b"]) {
+            try_update_primitive_value(table_oid, *row_oid, email_column_oid, Some(format!("{value}@example.com"))).unwrap();
+            try_update_primitive_value(table_oid, *row_oid, login_column_oid, Some(String::from(value))).unwrap();
+        }
+
+        // Taking the value of another row is rejected as a conflict, and leaves the cell as it was
+        let result = try_update_primitive_value(table_oid, row_oids[1], email_column_oid, Some(String::from("a@example.com")));
+        assert!(matches!(result, Err(error::Error::Conflict(_))));
+        let result = try_update_primitive_value(table_oid, row_oids[1], login_column_oid, Some(String::from("a")));
+        assert!(matches!(result, Err(error::Error::Conflict(_))));
+        assert_eq!(get_cell_values(table_oid, row_oids[1], vec![email_column_oid]).unwrap()[&email_column_oid], Some(String::from("b@example.com")));
+
+        // Rewriting a row's own value and clearing a value are both allowed
+        try_update_primitive_value(table_oid, row_oids[0], email_column_oid, Some(String::from("a@example.com"))).unwrap();
+        try_update_primitive_value(table_oid, row_oids[1], email_column_oid, None).unwrap();
+
+        // Duplicates written some other way are still flagged when the table is read
+        db::open().unwrap().execute(&format!("UPDATE TABLE{table_oid} SET COLUMN{email_column_oid} = 'a@example.com' WHERE OID = ?1;"), params![row_oids[1]]).unwrap();
+        let (cell_channel, packets) = test_util::collect_channel::<Cell>();
+        send_table_data(table_oid, None, 1, 10, cell_channel).unwrap();
+        let flagged_row_oids: Vec<i64> = packets.lock().unwrap().iter()
+            .filter(|packet| packet["failedValidations"].as_array().is_some_and(|v| v.iter().any(|f| f["key"] == "validation.notUnique")))
+            .map(|packet| packet["rowOid"].as_i64().unwrap())
+            .collect();
+        assert_eq!(flagged_row_oids, row_oids);
+    }
+
+    #[test]
+    fn auto_update_columns_change_when_another_cell_is_edited() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Documents"), &vec![]).unwrap();
+        let title_column_oid = table_column::create(table_oid, "Title", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, false, None).unwrap();
+        let updated_column_oid = table_column::create(table_oid, "Updated", data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp), None, "", true, false, false, None).unwrap();
+        table_column::set_auto_update(updated_column_oid, true).unwrap();
+        let row_oid = push(table_oid).unwrap();
+        let conn = db::open().unwrap();
+        conn.execute(&format!("UPDATE TABLE{table_oid} SET COLUMN{updated_column_oid} = julianday('2000-01-01') WHERE OID = ?1;"), params![row_oid]).unwrap();
+        let get_updated = || conn.query_one(&format!("SELECT STRFTIME('%FT%TZ', COLUMN{updated_column_oid}, 'julianday') FROM TABLE{table_oid} WHERE OID = ?1;"), params![row_oid], |row| row.get::<_, String>(0)).unwrap();
+
+        // Editing another cell in the row moves the timestamp up to now
+        try_update_primitive_value(table_oid, row_oid, title_column_oid, Some(String::from("Draft"))).unwrap();
+        let updated = get_updated();
+        assert!(updated.as_str() > "2000-01-01T00:00:00Z");
+        let now: String = conn.query_one("SELECT STRFTIME('%FT%TZ', 'now');", [], |row| row.get(0)).unwrap();
+        assert!(updated <= now);
+
+        // Editing the timestamp itself keeps the value it was given
+        try_update_primitive_value(table_oid, row_oid, updated_column_oid, Some(String::from("2001-02-03T04:05:06Z"))).unwrap();
+        assert_eq!(get_updated(), "2001-02-03T04:05:06Z");
+
+        // Only timestamp columns can be updated automatically
+        assert!(table_column::set_auto_update(title_column_oid, true).is_err());
+    }
+
+    #[test]
+    fn now_and_today_defaults_are_evaluated_when_rows_are_added() {
+        let _db = test_util::open_test_db();
+        let timestamp_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Timestamp);
+        let date_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Date);
+        let table_oid = table::create(String::from("Events"), &vec![]).unwrap();
+        let created_column_oid = table_column::create(table_oid, "Created", timestamp_type.clone(), None, "", true, false, false, Some(String::from(table_column::DEFAULT_NOW))).unwrap();
+        let day_column_oid = table_column::create(table_oid, "Day", date_type.clone(), None, "", true, false, false, Some(String::from(table_column::DEFAULT_TODAY))).unwrap();
+        let conn = db::open().unwrap();
+        let get_row = |row_oid: i64| conn
+            .query_one(&format!("SELECT COLUMN{created_column_oid}, DATE(COLUMN{day_column_oid}, 'julianday') FROM TABLE{table_oid} WHERE OID = ?1;"), params![row_oid], |row| Ok((row.get::<_, f64>(0)?, row.get::<_, String>(1)?)))
+            .unwrap();
+        let today: String = conn.query_one("SELECT DATE('now', 'localtime');", [], |row| row.get(0)).unwrap();
+
+        // Rows added a moment apart get increasing timestamps, and today's date
+        let first_row_oid = push(table_oid).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let second_row_oid = insert(table_oid, first_row_oid).unwrap();
+        let (first_created, first_day) = get_row(first_row_oid);
+        let (second_created, second_day) = get_row(second_row_oid);
+        assert!(second_created > first_created);
+        assert_eq!((first_day, second_day), (today.clone(), today));
+
+        // The tokens cannot be used for columns of the wrong type
+        assert!(table_column::create(table_oid, "Start", date_type, None, "", true, false, false, Some(String::from(table_column::DEFAULT_NOW))).is_err());
+        assert!(table_column::create(table_oid, "End", timestamp_type, None, "", true, false, false, Some(String::from(table_column::DEFAULT_TODAY))).is_err());
+    }
+
+    #[test]
+    fn string_lists_are_stored_as_json_arrays() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Articles"), &vec![]).unwrap();
+        let tags_column_oid = table_column::create(table_oid, "Tags", data_type::MetadataColumnType::Primitive(data_type::Primitive::StringList), None, "", true, false, false, None).unwrap();
+        let title_column_oid = table_column::create(table_oid, "Title", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, false, None).unwrap();
+        let row_oid = push(table_oid).unwrap();
+        let get_stored_value = || db::open().unwrap().query_one(&format!("SELECT COLUMN{tags_column_oid} FROM TABLE{table_oid} WHERE OID = ?1;"), params![row_oid], |row| row.get::<_, Option<String>>(0)).unwrap();
+
+        // A list is stored as a JSON array, and displayed as a bracketed list
+        set_string_list(table_oid, row_oid, tags_column_oid, vec![String::from("news"), String::from("local, weekly")]).unwrap();
+        assert_eq!(get_stored_value(), Some(String::from(r#"["news","local, weekly"]"#)));
+        assert_eq!(get_cell_values(table_oid, row_oid, vec![tags_column_oid]).unwrap()[&tags_column_oid], Some(String::from("[news,local, weekly]")));
+
+        // Values written directly must be JSON arrays of strings
+        for invalid_value in ["news", "[1, 2]", r#"{"tag": "news"}"#] {
+            assert!(try_update_primitive_value(table_oid, row_oid, tags_column_oid, Some(String::from(invalid_value))).is_err(), "{invalid_value:?} was accepted");
+        }
+        try_update_primitive_value(table_oid, row_oid, tags_column_oid, Some(String::from(r#"["sports"]"#))).unwrap();
+        assert_eq!(get_stored_value(), Some(String::from(r#"["sports"]"#)));
+
+        // An empty list clears the cell, and other columns cannot be given a list
+        set_string_list(table_oid, row_oid, tags_column_oid, vec![]).unwrap();
+        assert_eq!(get_stored_value(), None);
+        assert!(set_string_list(table_oid, row_oid, title_column_oid, vec![String::from("news")]).is_err());
+    }
+
+    #[test]
+    fn dates_out_of_bounds_are_flagged() {
+        let _db = test_util::open_test_db();
+        let date_type = data_type::MetadataColumnType::Primitive(data_type::Primitive::Date);
+        let table_oid = table::create(String::from("People"), &vec![]).unwrap();
+        let born_column_oid = table_column::create(table_oid, "Born", date_type.clone(), None, "", true, false, false, None).unwrap();
+        let hired_column_oid = table_column::create(table_oid, "Hired", date_type.clone(), None, "", true, false, false, None).unwrap();
+        let review_column_oid = table_column::create(table_oid, "Review Due", date_type, None, "", true, false, false, None).unwrap();
+        let name_column_oid = table_column::create(table_oid, "Name", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, false, None).unwrap();
+        table_column::set_date_bound(born_column_oid, Some(table_column::DateBound::NotFuture)).unwrap();
+        table_column::set_date_bound(hired_column_oid, Some(table_column::DateBound::Range { min: Some(String::from("2020-01-01")), max: Some(String::from("2020-12-31")) })).unwrap();
+        table_column::set_date_bound(review_column_oid, Some(table_column::DateBound::NotPast)).unwrap();
+        for (born, hired, review) in [("1990-05-01", "2020-12-31", "2999-06-01"), ("2999-01-01", "2021-01-01", "2999-06-01"), ("1985-02-03", "2020-01-01", "2000-06-01")] {
+            let row_oid = push(table_oid).unwrap();
+            try_update_primitive_value(table_oid, row_oid, born_column_oid, Some(String::from(born))).unwrap();
+            try_update_primitive_value(table_oid, row_oid, hired_column_oid, Some(String::from(hired))).unwrap();
+            try_update_primitive_value(table_oid, row_oid, review_column_oid, Some(String::from(review))).unwrap();
+        }
+
+        // The birthdate in the future and the hiring date outside of the range are likely mistakes, while the review date that has passed is only informational
+        let (cell_channel, packets) = test_util::collect_channel::<Cell>();
+        send_table_data(table_oid, None, 1, 10, cell_channel).unwrap();
+        let flagged_cells: Vec<(i64, i64, String)> = packets.lock().unwrap().iter()
+            .filter_map(|packet| {
+                let failed_validation = packet["failedValidations"].as_array()?.iter().find(|f| f["key"] == "validation.dateOutOfBounds")?;
+                return Some((packet["rowOid"].as_i64().unwrap(), packet["columnOid"].as_i64().unwrap(), String::from(failed_validation["severity"].as_str().unwrap())));
+            })
+            .collect();
+        assert_eq!(flagged_cells, vec![
+            (2, born_column_oid, String::from("Warning")), 
+            (2, hired_column_oid, String::from("Warning")), 
+            (3, review_column_oid, String::from("Info"))
+        ]);
+
+        // Only date and timestamp columns can be bounded
+        assert!(table_column::set_date_bound(name_column_oid, Some(table_column::DateBound::NotPast)).is_err());
+    }
+
+    #[test]
+    fn text_in_numeric_columns_is_flagged() {
+        let _db = test_util::open_test_db();
+        let table_oid = table::create(String::from("Readings"), &vec![]).unwrap();
+        let count_column_oid = table_column::create(table_oid, "Count", data_type::MetadataColumnType::Primitive(data_type::Primitive::Integer), None, "", true, false, false, None).unwrap();
+        let value_column_oid = table_column::create(table_oid, "Value", data_type::MetadataColumnType::Primitive(data_type::Primitive::Number), None, "", true, false, false, None).unwrap();
+
+        // Simulate a table created before data tables were strict, so that text that cannot be converted into a number can be stored in its numeric columns
+        db::open().unwrap().execute_batch(&format!("
+        DROP TABLE TABLE{table_oid};
+        CREATE TABLE TABLE{table_oid} (OID INTEGER PRIMARY KEY, TRASH INTEGER NOT NULL DEFAULT 0, ORDER_KEY REAL, COLUMN{count_column_oid} INTEGER, COLUMN{value_column_oid} REAL);
+        INSERT INTO TABLE{table_oid} (OID, ORDER_KEY, COLUMN{count_column_oid}, COLUMN{value_column_oid}) VALUES 
+            (1, 1, 3, 2.5), 
+            (2, 2, 'three', '12'), 
+            (3, 3, NULL, 'n/a');")).unwrap();
+
+        // Only the text is flagged, while empty cells and text that SQLite converted into a number are not
+        let (cell_channel, packets) = test_util::collect_channel::<Cell>();
+        send_table_data(table_oid, None, 1, 10, cell_channel).unwrap();
+        let flagged_cells: Vec<(i64, i64)> = packets.lock().unwrap().iter()
+            .filter(|packet| packet["failedValidations"].as_array().is_some_and(|v| v.iter().any(|f| f["key"] == "validation.notANumber")))
+            .map(|packet| (packet["rowOid"].as_i64().unwrap(), packet["columnOid"].as_i64().unwrap()))
+            .collect();
+        assert_eq!(flagged_cells, vec![(2, count_column_oid), (3, value_column_oid)]);
+    }
+
+    /// Creates a table with more reference columns than SQLite can join in a single SELECT statement.
+    fn create_wide_reference_table(column_count: usize) -> (i64, Vec<i64>) {
+        let target_oid = table::create(String::from("Targets"), &vec![]).unwrap();
+        let name_column_oid = table_column::create(target_oid, "Name", data_type::MetadataColumnType::Primitive(data_type::Primitive::Text), None, "", true, false, true, None).unwrap();
+        let source_oid = table::create(String::from("Sources"), &vec![]).unwrap();
+        let reference_column_oids: Vec<i64> = (0..column_count)
+            .map(|i| table_column::create(source_oid, &format!("Target {i}"), data_type::MetadataColumnType::Reference(target_oid), None, "", true, false, false, None).unwrap())
+            .collect();
+        db::open().unwrap().execute(&format!("INSERT INTO TABLE{target_oid} (OID, COLUMN{name_column_oid}) VALUES (1, 'Alpha');"), []).unwrap();
+        return (source_oid, reference_column_oids);
+    }
+
+    #[test]
+    fn reference_columns_past_the_join_limit_are_displayed() {
+        let _db = test_util::open_test_db();
+
+        // Every column refers to the same row, except the last which is left empty
+        let (source_oid, reference_column_oids) = create_wide_reference_table(70);
+        let set_cols = reference_column_oids[..69].iter().map(|column_oid| format!("COLUMN{column_oid} = 1")).collect::<Vec<String>>().join(", ");
+        db::open().unwrap().execute_batch(&format!("
+        INSERT INTO TABLE{source_oid} (OID) VALUES (1);
+        UPDATE TABLE{source_oid} SET {set_cols} WHERE OID = 1;")).unwrap();
+
+        // Columns looked up by join and by subquery show the same display value
+        for column_oid in &reference_column_oids[..69] {
+            assert_eq!(get_page_display_values(source_oid, None, 1, 10, *column_oid), vec![(1, Some(String::from("Alpha")))]);
+        }
+        assert_eq!(get_page_display_values(source_oid, None, 1, 10, reference_column_oids[69]), vec![(1, None)]);
+    }
+
+    /// Loads a page of 1,000 rows from a table with 70 reference columns, half of which are looked up with subqueries.
+    /// Run with `cargo test wide_reference_table_benchmark -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn wide_reference_table_benchmark() {
+        const ROW_COUNT: i64 = 1000;
+        let _db = test_util::open_test_db();
+        let (source_oid, reference_column_oids) = create_wide_reference_table(70);
+        let set_cols = reference_column_oids.iter().map(|column_oid| format!("COLUMN{column_oid} = 1")).collect::<Vec<String>>().join(", ");
+        db::open().unwrap().execute_batch(&format!("
+        WITH RECURSIVE n(OID) AS (SELECT 1 UNION ALL SELECT OID + 1 FROM n WHERE OID < {ROW_COUNT})
+        INSERT INTO TABLE{source_oid} (OID, ORDER_KEY) SELECT OID, OID FROM n;
+        UPDATE TABLE{source_oid} SET {set_cols};")).unwrap();
+
+        let start = std::time::Instant::now();
+        let (cell_channel, packets) = test_util::collect_channel::<Cell>();
+        send_table_data(source_oid, None, 1, ROW_COUNT, cell_channel).unwrap();
+        println!("{ROW_COUNT} rows with {} reference columns: {:?}", reference_column_oids.len(), start.elapsed());
+        assert!(packets.lock().unwrap().len() >= ROW_COUNT as usize * reference_column_oids.len());
+    }
+}
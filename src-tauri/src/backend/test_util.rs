@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use crate::backend::db;
+
+/// The path of the open database is global, so tests that use a database take turns.
+static DB_LOCK: Mutex<()> = Mutex::new(());
+static NEXT_DB_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A new database opened for the duration of a test, whose files are deleted once the test is done with it.
+pub struct TestDb {
+    pub path: PathBuf,
+    _guard: MutexGuard<'static, ()>
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", self.path.display()));
+        }
+    }
+}
+
+/// Creates and opens a new, empty database for a test.
+pub fn open_test_db() -> TestDb {
+    // A test that panicked while holding the lock leaves nothing behind that another test depends on
+    let guard = DB_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = std::env::temp_dir().join(format!("static-db-test-{}-{}.db", std::process::id(), NEXT_DB_ID.fetch_add(1, Ordering::SeqCst)));
+    db::init(path.to_string_lossy().into_owned()).unwrap();
+    return TestDb { path, _guard: guard };
+}
+
+/// Creates a channel that keeps every packet sent through it as JSON, for a test to inspect once the sending is done.
+pub fn collect_channel<T>() -> (Channel<T>, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let packets: Arc<Mutex<Vec<serde_json::Value>>> = Arc::new(Mutex::new(Vec::new()));
+    let sent_packets = packets.clone();
+    let channel = Channel::new(move |body| {
+        if let InvokeResponseBody::Json(json) = body {
+            sent_packets.lock().unwrap().push(serde_json::from_str(&json).unwrap());
+        }
+        return Ok(());
+    });
+    return (channel, packets);
+}
@@ -3,27 +3,107 @@ mod util;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Log warnings and errors by default, which can be overridden by setting RUST_LOG
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            // Keep the write-ahead log from growing without bound during long sessions
+            backend::spawn_checkpoint_timer();
+
+            // Notice when another process edits the open database
+            backend::spawn_external_change_watcher(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             backend::init,
+            backend::get_document_metadata,
+            backend::set_document_metadata,
+            backend::add_recent_file,
+            backend::get_recent_files,
+            backend::clear_recent_files,
+            backend::relocate_database,
             backend::dialog_close,
             backend::dialog_create_table,
             backend::dialog_create_table_column,
             backend::dialog_edit_table_column,
             backend::dialog_table_data,
             backend::get_table_list,
+            backend::get_tables_without_primary_key,
+            backend::get_row_display_value,
+            backend::get_row_display_values,
+            backend::set_row_display_value_cache_size,
             backend::get_report_list,
             backend::get_object_type_list,
+            backend::get_object_type_subtype_counts,
+            backend::get_object_type_integrity_issues,
+            backend::run_query,
+            backend::refresh_friendly_views,
+            backend::export_schema_json,
+            backend::export_table_csv,
+            backend::export_table_xlsx,
+            backend::import_table_csv,
+            backend::preview_csv_import,
+            backend::begin_operation,
+            backend::cancel_operation,
+            backend::apply_schema_json,
+            backend::get_orphaned_types,
+            backend::get_unreferenced_value_tables,
+            backend::preview_repair_database,
+            backend::repair_database,
+            backend::rebuild_surrogate_views,
+            backend::get_table_column_mismatches,
+            backend::get_integrity_report,
+            backend::get_database_health,
             backend::get_table_column,
+            backend::get_column_validation_rules,
             backend::get_table_column_list,
+            backend::get_all_table_names_for_autocomplete,
             backend::get_table_column_dropdown_values,
+            backend::reorder_table_column_dropdown_values,
+            backend::get_duplicate_dropdown_values,
+            backend::set_table_column_default_value,
+            backend::set_table_column_auto_update,
+            backend::set_table_column_date_bound,
+            backend::set_table_column_decimal_places,
             backend::get_table_column_reference_values,
             backend::get_table_column_object_values,
+            backend::set_profiling_enabled,
             backend::get_table_data,
+            backend::get_table_grouped_data,
+            backend::get_table_delete_mode,
+            backend::set_table_delete_mode,
+            backend::get_table_format_rules,
+            backend::add_table_format_rule,
+            backend::remove_table_format_rule,
+            backend::set_table_display_value_materialized,
+            backend::get_table_data_query,
+            backend::get_table_data_query_plan,
             backend::get_table_row,
+            backend::get_adjacent_row,
+            backend::bulk_insert_table_rows,
+            backend::get_row_edit_form,
+            backend::get_cell_values,
+            backend::get_column_values,
+            backend::get_column_numeric_values,
+            backend::get_cell_comment,
+            backend::get_table_tags,
+            backend::get_table_rows_by_tag,
+            backend::save_table_view_state,
+            backend::get_table_view_state,
+            backend::upload_table_cell_file,
+            backend::download_table_cell_file,
+            backend::get_file_metadata,
+            backend::stream_table_cell_file,
+            backend::get_object_type_flat_data,
             backend::execute,
+            backend::preview,
+            backend::compact_table_oids,
+            backend::get_audit_log,
+            backend::set_checkpoint_interval,
+            backend::get_data_version,
             backend::undo,
             backend::redo,
         ])
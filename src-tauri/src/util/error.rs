@@ -1,13 +1,25 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Error as IoError;
 use rusqlite::Error as RusqliteError;
+use serde_json::Error as SerdeJsonError;
+use rust_xlsxwriter::XlsxError;
 use tauri::{Error as TauriError, ipc::Invoke};
 use serde::Serialize;
 use tauri::ipc::InvokeError;
 
+#[derive(Debug)]
 pub enum Error {
     AdhocError(&'static str),
     SaveInitializationError(RusqliteError),
     RusqliteError(RusqliteError),
     TauriError(TauriError),
+    IoError(IoError),
+    JsonError(SerdeJsonError),
+    XlsxError(XlsxError),
+    FileUnavailable(String),
+    Conflict(&'static str),
+    Cancelled,
 }
 
 impl Into<InvokeError> for Error {
@@ -29,6 +41,24 @@ impl From<TauriError> for Error {
     }
 }
 
+impl From<IoError> for Error {
+    fn from(e: IoError) -> Error {
+        Error::IoError(e)
+    }
+}
+
+impl From<SerdeJsonError> for Error {
+    fn from(e: SerdeJsonError) -> Error {
+        Error::JsonError(e)
+    }
+}
+
+impl From<XlsxError> for Error {
+    fn from(e: XlsxError) -> Error {
+        Error::XlsxError(e)
+    }
+}
+
 impl Into<String> for Error {
     fn into(self) -> String {
         match self {
@@ -43,15 +73,89 @@ impl Into<String> for Error {
             },
             Self::TauriError(e) => { 
                 return format!("Tauri error occurred: {}", e); 
+            },
+            Self::IoError(e) => {
+                return format!("File error occurred: {}", e);
+            },
+            Self::JsonError(e) => {
+                return format!("JSON error occurred: {}", e);
+            },
+            Self::XlsxError(e) => {
+                return format!("Excel error occurred: {}", e);
+            },
+            Self::FileUnavailable(path) => {
+                return format!("[FileUnavailable] The database file at {} could not be opened. It may have been moved, deleted, or stored on a drive that is no longer connected.", path);
+            },
+            Self::Conflict(s) => {
+                return format!("[Conflict] {}", s);
+            },
+            Self::Cancelled => {
+                return String::from("[Cancelled] The operation was cancelled.");
             }
         }
     }
 }
 
 
+#[derive(Serialize, Clone, Copy, PartialEq, PartialOrd)]
+/// How serious a failed validation check is.
+pub enum Severity {
+    /// The value is suspicious, but may be intended.
+    Info,
+    /// The value is likely a mistake.
+    Warning,
+    /// The value breaks a rule of the table.
+    Error
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 /// A flag for a validation check that was not passed.
+/// The key identifies the message to display, so that the frontend can translate it, and the parameters are substituted into the message.
 pub struct FailedValidation {
-    pub description: String 
-}
\ No newline at end of file
+    pub key: String,
+    pub severity: Severity,
+    pub params: HashMap<String, String>
+}
+
+impl FailedValidation {
+    /// Creates a flag for a failed validation check.
+    pub fn new(key: &str, severity: Severity, params: &[(&str, &str)]) -> Self {
+        return FailedValidation {
+            key: String::from(key),
+            severity,
+            params: params.iter().map(|(k, v)| (String::from(*k), String::from(*v))).collect()
+        };
+    }
+}
+
+impl Display for FailedValidation {
+    /// Renders the message in English.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let column_name: &str = match self.params.get("columnName") {
+            Some(s) => s,
+            None => ""
+        };
+        return match self.key.as_str() {
+            "validation.notNull" => write!(f, "{column_name} cannot be NULL!"),
+            "validation.notUnique" => write!(f, "{column_name} value is not unique!"),
+            "validation.primaryKeyNotUnique" => write!(f, "Primary key for this row is not unique!"),
+            "validation.notANumber" => write!(f, "{column_name} value is not a number!"),
+            "validation.dateOutOfBounds" => write!(f, "{column_name} value is outside of its plausible range!"),
+            _ => write!(f, "{}", self.key)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_validation_key_has_an_english_rendering() {
+        for key in ["validation.notNull", "validation.notUnique", "validation.primaryKeyNotUnique", "validation.notANumber", "validation.dateOutOfBounds"] {
+            let failed_validation = FailedValidation::new(key, Severity::Error, &[("columnName", "Name")]);
+            assert_ne!(failed_validation.to_string(), key);
+        }
+    }
+}
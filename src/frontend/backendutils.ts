@@ -9,7 +9,7 @@ export type BasicHierarchicalMetadata = BasicMetadata & {
     hierarchyLevel: number
 };
 
-export type ColumnType = { primitive: 'Any' | 'Boolean' | 'Integer' | 'Number' | 'Date' | 'Timestamp' | 'Text' | 'JSON' | 'File' | 'Image' } 
+export type ColumnType = { primitive: 'Any' | 'Boolean' | 'Integer' | 'Number' | 'Date' | 'Timestamp' | 'Text' | 'JSON' | 'File' | 'Image' | 'StringList' } 
     | { singleSelectDropdown: number }
     | { multiSelectDropdown: number }
     | { reference: number } 
@@ -25,6 +25,8 @@ export type TableColumnMetadata = {
     isNullable: boolean,
     isUnique: boolean,
     isPrimaryKey: boolean,
+    isPinned: boolean,
+    defaultValue: string | null,
 };
 
 export type DropdownValue = {
@@ -32,6 +34,12 @@ export type DropdownValue = {
     displayValue: string | null
 };
 
+export type FailedValidation = {
+    key: string,
+    severity: 'Info' | 'Warning' | 'Error',
+    params: { [param: string]: string }
+};
+
 export type TableColumnCell = {
     tableOid: number,
     rowOid: number,
@@ -39,14 +47,26 @@ export type TableColumnCell = {
     columnType: ColumnType, 
     trueValue: string | null,
     displayValue: string | null,
-    failedValidations: { description: string }[]
+    failedValidations: FailedValidation[],
+    hasComment: boolean
+};
+
+export type CellComment = {
+    comment: string,
+    createdAt: string
 };
 
 export type TableCellChannelPacket = {
     rowOid: number,
-    rowIndex: number
+    rowIndex: number,
+    rowStyle: string | null
 } | TableColumnCell;
 
+export type TableGroupedCellChannelPacket = {
+    groupValue: string | null,
+    rowCount: number
+} | TableCellChannelPacket;
+
 export type TableRowCellChannelPacket = {
     rowExists: boolean,
     tableOid: number
@@ -94,11 +114,14 @@ export type Query = {
 } | {
     invokeAction: 'get_table_column_reference_values',
     invokeParams: {
+        tableOid?: number,
+        allowSelf?: boolean,
         referenceTypeChannel: Channel<BasicMetadata>
     }
 } | {
     invokeAction: 'get_table_column_object_values',
     invokeParams: {
+        tableOid: number,
         objectTypeChannel: Channel<BasicMetadata>
     }
 } | {
@@ -110,6 +133,13 @@ export type Query = {
         pageSize: number,
         cellChannel: Channel<TableCellChannelPacket>
     }
+} | {
+    invokeAction: 'get_table_grouped_data',
+    invokeParams: {
+        tableOid: number,
+        groupColumnOid: number,
+        cellChannel: Channel<TableGroupedCellChannelPacket>
+    }
 } | {
     invokeAction: 'get_table_row',
     invokeParams: {
@@ -160,6 +190,22 @@ export type Action = {
     deleteTable: {
         tableOid: number
     }
+} | {
+    renameTable: {
+        tableOid: number,
+        tableName: string
+    }
+} | {
+    setTablePrimaryKey: {
+        tableOid: number,
+        columnOids: number[]
+    }
+} | {
+    duplicateTable: {
+        tableOid: number,
+        tableName: string,
+        includeData: boolean
+    }
 } | {
     createTableColumn: {
         tableOid: number,
@@ -169,7 +215,8 @@ export type Action = {
         columnStyle: string,
         isNullable: boolean,
         isUnique: boolean,
-        isPrimaryKey: boolean
+        isPrimaryKey: boolean,
+        defaultValue: string | null
     }
 } | {
     editTableColumnMetadata: {
@@ -180,7 +227,9 @@ export type Action = {
         columnStyle: string,
         isNullable: boolean,
         isUnique: boolean,
-        isPrimaryKey: boolean
+        isPrimaryKey: boolean,
+        isPinned: boolean,
+        defaultValue: string | null
     }
 } | {
     editTableColumnDropdownValues: {
@@ -188,6 +237,31 @@ export type Action = {
         columnOid: number,
         dropdownValues: DropdownValue[]
     }
+} | {
+    importTableColumnDropdownValues: {
+        tableOid: number,
+        columnOid: number,
+        values: string[]
+    }
+} | {
+    deleteTableColumnDropdownValues: {
+        tableOid: number,
+        columnOid: number,
+        valueOids: number[]
+    }
+} | {
+    restoreDeletedTableColumnDropdownValues: {
+        tableOid: number,
+        columnOid: number,
+        valueOids: number[]
+    }
+} | {
+    mergeTableColumnDropdownValues: {
+        tableOid: number,
+        columnOid: number,
+        fromValueOid: number,
+        intoValueOid: number
+    }
 } | {
     deleteTableColumn: {
         tableOid: number,
@@ -207,6 +281,12 @@ export type Action = {
         tableOid: number,
         rowOid: number
     }
+} | {
+    deduplicateTableRows: {
+        tableOid: number,
+        keyColumnOids: number[],
+        keep: 'first' | 'last'
+    }
 } | {
     updateTableCellStoredAsPrimitiveValue: {
         tableOid: number,
@@ -214,6 +294,83 @@ export type Action = {
         columnOid: number,
         value: string | null
     }
+} | {
+    setTableCellStringList: {
+        tableOid: number,
+        columnOid: number,
+        rowOid: number,
+        values: string[]
+    }
+} | {
+    setTableCellComment: {
+        tableOid: number,
+        rowOid: number,
+        columnOid: number,
+        comment: string | null
+    }
+} | {
+    addTableRowTag: {
+        tableOid: number,
+        rowOid: number,
+        tag: string
+    }
+} | {
+    removeTableRowTag: {
+        tableOid: number,
+        rowOid: number,
+        tag: string
+    }
+} | {
+    findReplaceTableColumn: {
+        tableOid: number,
+        columnOid: number,
+        find: string,
+        replace: string,
+        caseSensitive: boolean
+    }
+} | {
+    normalizeTableColumn: {
+        tableOid: number,
+        columnOid: number,
+        ops: ('trimWhitespace' | 'collapseSpaces' | 'toUpper' | 'toLower' | 'toTitleCase')[]
+    }
+} | {
+    fillDownTableColumn: {
+        tableOid: number,
+        columnOid: number
+    }
+} | {
+    splitTableColumn: {
+        tableOid: number,
+        sourceColumnOid: number,
+        delimiter: string,
+        intoColumnOids: number[],
+        createColumns: boolean
+    }
+} | {
+    mergeTableColumns: {
+        tableOid: number,
+        sourceColumnOids: number[],
+        separator: string,
+        intoColumnOid: number,
+        skipNulls: boolean
+    }
+} | {
+    promoteTableChildTable: {
+        tableOid: number,
+        columnOid: number
+    }
+} | {
+    reorderTableColumn: {
+        tableOid: number,
+        columnOid: number,
+        newOrdering: number
+    }
+} | {
+    promoteTableColumnDropdown: {
+        tableOid: number,
+        columnOid: number
+    }
 };
 
 
@@ -271,6 +428,25 @@ export async function executeAsync(action: Action): Promise<void> {
     return await invoke('execute', { action: action });
 }
 
+/**
+ * What an action would affect if it were executed.
+ */
+export type ActionPreview = {
+    affectedRowCount: number,
+    changedRowCount: number,
+    error: string | null
+};
+
+/**
+ * Previews what an action would affect, without changing the database.
+ * Only actions that change many rows at once can be previewed: deleting a table or a column, splitting a column, merging columns, removing duplicate rows, and clearing a table.
+ * @param action The action to preview.
+ * @returns What the action would affect.
+ */
+export async function previewAsync(action: Action): Promise<ActionPreview> {
+    return await invoke('preview', { action: action });
+}
+
 /**
  * Undoes the last action performed.
  */
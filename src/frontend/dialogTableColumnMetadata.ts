@@ -49,6 +49,8 @@ async function loadMetadataFromFields(): Promise<TableColumnMetadata> {
     let isUnique: boolean = (document.getElementById('column-is-unique') as HTMLInputElement)?.checked ?? false;
     let isPrimaryKey: boolean = (document.getElementById('column-is-primary-key') as HTMLInputElement)?.checked ?? false;
     const columnStyle: string = (document.getElementById('column-style') as HTMLTextAreaElement)?.value ?? '';
+    const isPinned: boolean = (document.getElementById('column-is-pinned') as HTMLInputElement)?.checked ?? false;
+    const defaultValue: string = (document.getElementById('column-default-value') as HTMLInputElement)?.value ?? '';
 
     let columnType: ColumnType;
     switch (columnTypeStr) {
@@ -62,6 +64,7 @@ async function loadMetadataFromFields(): Promise<TableColumnMetadata> {
         case 'Timestamp':
         case 'Text':
         case 'JSON':
+        case 'StringList':
             columnType = { primitive: columnTypeStr };
             break;
         case 'File':
@@ -110,7 +113,9 @@ async function loadMetadataFromFields(): Promise<TableColumnMetadata> {
         columnType: columnType,
         isNullable: isNullable,
         isUnique: isUnique,
-        isPrimaryKey: isPrimaryKey
+        isPrimaryKey: isPrimaryKey,
+        isPinned: isPinned,
+        defaultValue: defaultValue == '' ? null : defaultValue
     };
 }
 
@@ -151,7 +156,8 @@ window.addEventListener("DOMContentLoaded", async () => {
 
     // Fill in the dropdown of possible Global Data Type types
     let columnObjDataTypeInput: HTMLSelectElement | null = document.getElementById('column-type-oid-object') as HTMLSelectElement;
-    if (columnObjDataTypeInput) {
+    const objTableOid = urlParams.get('table_oid');
+    if (columnObjDataTypeInput && objTableOid) {
         const objDataTypeChannel: Channel<BasicMetadata> = new Channel<BasicMetadata>();
         objDataTypeChannel.onmessage = (objDataType) => {
             let columnObjDataTypeOption: HTMLOptionElement = document.createElement('option');
@@ -162,6 +168,7 @@ window.addEventListener("DOMContentLoaded", async () => {
         await queryAsync({
             invokeAction: 'get_table_column_object_values',
             invokeParams: {
+                tableOid: parseInt(objTableOid),
                 objectTypeChannel: objDataTypeChannel
             }
         });
@@ -227,6 +234,14 @@ window.addEventListener("DOMContentLoaded", async () => {
             if (isPrimaryKeyInput)
                 isPrimaryKeyInput.checked = columnMetadata.isPrimaryKey;
 
+            let isPinnedInput: HTMLInputElement | null = document.getElementById('column-is-pinned') as HTMLInputElement;
+            if (isPinnedInput)
+                isPinnedInput.checked = columnMetadata.isPinned;
+
+            let defaultValueInput: HTMLInputElement | null = document.getElementById('column-default-value') as HTMLInputElement;
+            if (defaultValueInput)
+                defaultValueInput.value = columnMetadata.defaultValue ?? '';
+
             // Edit the column when OK is clicked
             document.querySelector('#create-table-column-button')?.addEventListener("click", async (e) => {
                 e.preventDefault();
@@ -252,7 +267,9 @@ window.addEventListener("DOMContentLoaded", async () => {
                             columnStyle: changedMetadata.columnStyle,
                             isNullable: changedMetadata.isNullable,
                             isUnique: changedMetadata.isUnique,
-                            isPrimaryKey: changedMetadata.isPrimaryKey
+                            isPrimaryKey: changedMetadata.isPrimaryKey,
+                            isPinned: changedMetadata.isPinned,
+                            defaultValue: changedMetadata.defaultValue
                         }
                     });
 
@@ -315,7 +332,8 @@ window.addEventListener("DOMContentLoaded", async () => {
                     columnOrdering: columnOrdering ? parseInt(columnOrdering) : null,
                     isNullable: metadata.isNullable,
                     isUnique: metadata.isUnique,
-                    isPrimaryKey: metadata.isPrimaryKey
+                    isPrimaryKey: metadata.isPrimaryKey,
+                    defaultValue: metadata.defaultValue
                 }
             }))
             .then(async (_) => await closeDialogAsync())
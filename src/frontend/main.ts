@@ -278,3 +278,8 @@ window.addEventListener("DOMContentLoaded", () => {
 listen<any>("update-table-list", loadTables);
 listen<any>("update-report-list", loadReports);
 listen<any>("update-object-type-list", loadObjectTypes);
+listen<any>("external-change", () => {
+  loadTables();
+  loadReports();
+  loadObjectTypes();
+});
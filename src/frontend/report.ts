@@ -261,6 +261,7 @@ if (urlParamReportOid) {
       invokeAction: "get_table_data",
       invokeParams: {
         tableOid: reportOid, 
+        parentRowOid: null,
         pageNum: pageNum,
         pageSize: pageSize,
         cellChannel: onReceiveCell 
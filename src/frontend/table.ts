@@ -264,6 +264,9 @@ if (urlParamTableOid) {
         currentRowOid = rowOid;
         if (tableBodyNode) {
           currentRowNode = addRowToTable(tableBodyNode, rowOid, rowIndex);
+          if (cell.rowStyle) {
+            currentRowNode.style.cssText += cell.rowStyle;
+          }
         }
       } else {
         // Add cell to current row
@@ -357,6 +360,9 @@ if (urlParamTableOid) {
       }
     });
   });
+  listen<null>("external-change", () => {
+    navigator.locks.request('table-content', async () => await refreshTableAsync());
+  });
   listen<[number, number]>("update-table-row", (e) => {
     const updateTableOid = e.payload[0];
     const updateRowOid = e.payload[1];
@@ -1,9 +1,32 @@
 import { Menu, MenuItem } from "@tauri-apps/api/menu";
 import { message } from "@tauri-apps/plugin-dialog";
-import { DropdownValue, TableColumnCell, executeAsync, queryAsync } from './backendutils';
+import { DropdownValue, FailedValidation, TableColumnCell, executeAsync, queryAsync } from './backendutils';
 import { Channel } from "@tauri-apps/api/core";
 
 
+/**
+ * Renders a failed validation as a human-readable message.
+ * @param failure The failed validation, identified by its message key.
+ * @returns The message to display.
+ */
+function formatFailedValidation(failure: FailedValidation): string {
+  switch (failure.key) {
+    case 'validation.notNull':
+      return `${failure.params.columnName} cannot be NULL!`;
+    case 'validation.notUnique':
+      return `${failure.params.columnName} value is not unique!`;
+    case 'validation.primaryKeyNotUnique':
+      return 'Primary key for this row is not unique!';
+    case 'validation.notANumber':
+      return `${failure.params.columnName} value is not a number!`;
+    case 'validation.dateOutOfBounds':
+      return `${failure.params.columnName} value is outside of its plausible range!`;
+    default:
+      return failure.key;
+  }
+}
+
+
 /**
  * Adds a cell representing a table cell to the end of a row.
  * @param rowNode The row of the table to insert the cell into.
@@ -163,13 +186,26 @@ export async function addTableColumnCellToRow(rowNode: HTMLTableRowElement, cell
     tableCellNode.classList.add('cell-null');
   }
 
+  // Add comment class for CSS
+  if (cell.hasComment) {
+    tableCellNode.classList.add('cell-has-comment');
+  }
+
   // Add validation errors
   if (cell.failedValidations.length > 0) {
-    tableCellNode.classList.add('cell-error');
+    // Style the cell by the most severe failure
+    const severities = cell.failedValidations.map((failure) => failure.severity);
+    if (severities.includes('Error')) {
+      tableCellNode.classList.add('cell-error');
+    } else if (severities.includes('Warning')) {
+      tableCellNode.classList.add('cell-warning');
+    } else {
+      tableCellNode.classList.add('cell-info');
+    }
 
     let failureMsgTooltipNode = document.createElement('div');
     failureMsgTooltipNode.classList.add('cell-error-tooltip');
-    failureMsgTooltipNode.innerText = cell.failedValidations.map((failure) => failure.description).join('\n');
+    failureMsgTooltipNode.innerText = cell.failedValidations.map(formatFailedValidation).join('\n');
     tableCellNode.insertAdjacentElement('beforeend', failureMsgTooltipNode);
   }
 